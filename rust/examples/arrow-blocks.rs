@@ -1,7 +1,7 @@
-use arrow::{ipc::reader::StreamReader, util::pretty::print_batches};
+use arrow::util::pretty::print_batches;
 use futures::StreamExt;
 use pangea_client::{
-    core::types::ChainId, provider::ChainProvider, query::Bound,
+    arrow::record_batches, core::types::ChainId, provider::ChainProvider, query::Bound,
     requests::blocks::GetBlocksRequest, ClientBuilder, Format, WsProvider,
 };
 use std::collections::HashSet;
@@ -41,15 +41,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .get_blocks_by_format(request, Format::Arrow, false)
         .await
         .unwrap();
-    futures::pin_mut!(stream);
-
-    while let Some(Ok(data)) = stream.next().await {
-        let cursor = std::io::Cursor::new(data);
-        let mut reader = StreamReader::try_new(cursor, None)?;
-        while let Some(batch) = reader.next() {
-            let batch = batch?;
-            print_batches(&[batch])?;
-        }
+    let mut batches = record_batches(stream);
+
+    while let Some(batch) = batches.next().await {
+        print_batches(&[batch?])?;
     }
 
     Ok(())
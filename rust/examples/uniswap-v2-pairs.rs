@@ -0,0 +1,36 @@
+use std::collections::HashSet;
+
+use futures::StreamExt;
+use pangea_client::{
+    core::types::ChainId, query::Bound, requests::uniswap_v2::GetPairsRequest, ClientBuilder,
+    WsProvider,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv_override().ok();
+
+    let client = ClientBuilder::default()
+        .build::<WsProvider>()
+        .await
+        .unwrap();
+
+    // pairs created in the last 10000 blocks
+    let request = GetPairsRequest {
+        chains: HashSet::from([ChainId::ETH]),
+        from_block: Bound::FromLatest(10_000),
+        to_block: Bound::Latest,
+        ..Default::default()
+    };
+
+    let mut pairs = client.get_uniswap_v2_pairs(request).await.unwrap();
+    while let Some(pair) = pairs.next().await {
+        let pair = pair?;
+        println!(
+            "{:?}: {:?}/{:?} at block {}",
+            pair.pair_address, pair.token0, pair.token1, pair.block_number
+        );
+    }
+
+    Ok(())
+}
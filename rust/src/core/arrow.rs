@@ -0,0 +1,576 @@
+//! Decoding of [`Format::Arrow`](crate::Format::Arrow) responses into [`RecordBatch`]es
+
+use std::{
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use ::arrow::{
+    array::{Array, ArrayRef, AsArray},
+    buffer::Buffer,
+    datatypes::{self as dt, DataType, SchemaRef, TimeUnit},
+    error::ArrowError,
+    ipc::{convert::fb_to_schema, reader::StreamDecoder, root_as_message},
+    record_batch::RecordBatch,
+};
+use futures::{stream, Stream, StreamExt};
+use serde::de::{
+    self, value::StrDeserializer, DeserializeOwned, DeserializeSeed, Deserializer, MapAccess,
+    SeqAccess, Visitor,
+};
+
+use super::{
+    error::{Error, ResponseError, Result},
+    provider::{ResponseStream, StreamResponse},
+    types::{self, blocks::Block, logs::Log, transfers::Transfer, txs::Transaction},
+};
+
+const CONTINUATION_MARKER: [u8; 4] = [0xff; 4];
+
+/// Decodes an Arrow IPC response into [`RecordBatch`]es as they arrive.
+///
+/// The transport does not split the response on message boundaries, so a batch
+/// may span several chunks and a chunk may hold several batches. Responses made
+/// of several IPC streams back to back are decoded one after the other.
+pub fn record_batches(stream: ResponseStream<Vec<u8>>) -> RecordBatchStream {
+    RecordBatchStream {
+        inner: ResponseError::map_stream(stream).boxed(),
+        decoder: StreamDecoder::new(),
+        pending: Buffer::from_vec(Vec::<u8>::new()),
+        header: Vec::new(),
+        schema: None,
+        done: false,
+    }
+}
+
+/// Reads the schema from the header of an Arrow IPC response.
+///
+/// Only the chunks holding the header are read, the rest of the response is
+/// dropped, which aborts the request. Works for the response of every dataset
+/// requested in [`Format::Arrow`](crate::Format::Arrow), so the schema can be
+/// checked before a large backfill.
+pub async fn peek_schema(stream: StreamResponse<Vec<u8>>) -> Result<SchemaRef> {
+    let mut stream = ResponseError::map_stream(stream?);
+    let mut header = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        header.extend_from_slice(&chunk?);
+        if let Some(schema) = read_schema(&header)? {
+            return Ok(schema);
+        }
+    }
+
+    Err(Error::Custom(
+        "the response did not contain an arrow schema".into(),
+    ))
+}
+
+/// A stream of [`RecordBatch`]es, see [`record_batches`]
+pub struct RecordBatchStream {
+    inner: ResponseStream<Vec<u8>>,
+    decoder: StreamDecoder,
+    /// The part of the last chunk not consumed by the decoder yet
+    pending: Buffer,
+    /// The leading bytes of the current IPC stream, until its schema is read
+    header: Vec<u8>,
+    schema: Option<SchemaRef>,
+    done: bool,
+}
+
+impl RecordBatchStream {
+    /// Returns the schema of the current IPC stream, once its header was read
+    pub fn schema(&self) -> Option<SchemaRef> {
+        self.schema.clone()
+    }
+
+    fn push_header(&mut self, bytes: &[u8]) -> Result<()> {
+        if self.schema.is_some() {
+            return Ok(());
+        }
+
+        self.header.extend_from_slice(bytes);
+        if let Some(schema) = read_schema(&self.header)? {
+            self.schema = Some(schema);
+            self.header = Vec::new();
+        }
+
+        Ok(())
+    }
+}
+
+impl Stream for RecordBatchStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if !this.pending.is_empty() {
+                match this.decoder.decode(&mut this.pending) {
+                    Ok(Some(batch)) => return Poll::Ready(Some(Ok(batch))),
+                    Ok(None) => continue,
+                    // the decoder only refuses input after the end of stream marker,
+                    // what follows is the next IPC stream of the response
+                    Err(_) if this.decoder.finish().is_ok() => {
+                        let pending = this.pending.clone();
+                        this.decoder = StreamDecoder::new();
+                        this.schema = None;
+                        this.header = Vec::new();
+                        if let Err(err) = this.push_header(&pending) {
+                            this.done = true;
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                        continue;
+                    }
+                    Err(err) => {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(err.into())));
+                    }
+                }
+            }
+
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            match this.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    if let Err(err) = this.push_header(&chunk) {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                    this.pending = Buffer::from_vec(chunk);
+                }
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => {
+                    this.done = true;
+                    if let Err(err) = this.decoder.finish() {
+                        return Poll::Ready(Some(Err(err.into())));
+                    }
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Projects every batch down to `columns`, in the given order.
+///
+/// Pairs with the `columns` field of a request, which asks for all columns when
+/// it is empty. Servers that prune columns only send the requested ones, the
+/// others ignore the field and their extra columns are dropped here before the
+/// caller sees them. A column missing from a batch ends the stream with
+/// [`Error::MissingColumn`].
+pub fn select_columns<S>(batches: S, columns: &[&str]) -> ResponseStream<RecordBatch>
+where
+    S: Stream<Item = Result<RecordBatch>> + Send + 'static,
+{
+    let columns = columns.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    batches
+        .map(move |batch| {
+            let batch = batch?;
+            let schema = batch.schema();
+            let indices = columns
+                .iter()
+                .map(|name| {
+                    schema
+                        .index_of(name)
+                        .map_err(|_| Error::MissingColumn(name.clone()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(batch.project(&indices)?)
+        })
+        .scan(false, |failed, batch| {
+            if *failed {
+                return futures::future::ready(None);
+            }
+            *failed = batch.is_err();
+            futures::future::ready(Some(batch))
+        })
+        .boxed()
+}
+
+/// Reads the schema from the first message of an IPC stream, or returns `None`
+/// if `bytes` do not hold the whole message yet
+fn read_schema(bytes: &[u8]) -> Result<Option<SchemaRef>> {
+    let offset = if bytes.starts_with(&CONTINUATION_MARKER) {
+        4
+    } else {
+        0
+    };
+    let Some(size) = bytes.get(offset..offset + 4) else {
+        return Ok(None);
+    };
+    let size = u32::from_le_bytes(size.try_into().unwrap()) as usize;
+    let Some(message) = bytes.get(offset + 4..offset + 4 + size) else {
+        return Ok(None);
+    };
+
+    let message = root_as_message(message)
+        .map_err(|err| ArrowError::ParseError(format!("Unable to get root as message: {err}")))?;
+    let schema = message.header_as_schema().ok_or_else(|| {
+        Error::Arrow(ArrowError::IpcError(
+            "Expected the stream to start with a schema".to_string(),
+        ))
+    })?;
+
+    Ok(Some(Arc::new(fb_to_schema(schema))))
+}
+
+/// Types that can be read from the rows of a [`RecordBatch`]
+pub trait FromRecordBatch: Sized {
+    fn from_record_batch(batch: &RecordBatch) -> Result<Vec<Self>>;
+}
+
+macro_rules! impl_from_record_batch {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl FromRecordBatch for $ty {
+                fn from_record_batch(batch: &RecordBatch) -> Result<Vec<Self>> {
+                    deserialize_batch(batch)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_record_batch!(
+    Block,
+    Log,
+    Transaction,
+    Transfer,
+    types::erc20::Approval,
+    types::erc20::Erc20Transfer,
+    types::uniswap_v2::Price,
+    types::uniswap_v3::PriceRecord,
+);
+
+/// Converts a stream of [`RecordBatch`]es into a stream of records, see [`FromRecordBatch`]
+pub fn batches_to_records<T, S>(batches: S) -> ResponseStream<T>
+where
+    T: FromRecordBatch + Send + 'static,
+    S: Stream<Item = Result<RecordBatch>> + Send + 'static,
+{
+    batches
+        .flat_map(|batch| {
+            let records = batch.and_then(|batch| T::from_record_batch(&batch));
+            let records: Vec<Result<T>> = match records {
+                Ok(records) => records.into_iter().map(Ok).collect(),
+                Err(err) => vec![Err(err)],
+            };
+            stream::iter(records)
+        })
+        .boxed()
+}
+
+/// Deserializes every row of `batch` into `T`, matching columns to fields by name.
+///
+/// Null values map to `None`, so nullable columns need `Option` fields. Integer
+/// columns are also accepted by fields that expect hex strings, such as `U64`.
+pub fn deserialize_batch<T: DeserializeOwned>(batch: &RecordBatch) -> Result<Vec<T>> {
+    (0..batch.num_rows())
+        .map(|row| {
+            T::deserialize(RowDeserializer { batch, row })
+                .map_err(|RowError(reason)| Error::RecordBatchRow { row, reason })
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct RowError(String);
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RowError {}
+
+impl de::Error for RowError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self(format!("missing column `{field}`"))
+    }
+}
+
+struct RowDeserializer<'a> {
+    batch: &'a RecordBatch,
+    row: usize,
+}
+
+impl<'de> Deserializer<'de> for RowDeserializer<'_> {
+    type Error = RowError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        let schema = self.batch.schema();
+        let names = schema.fields().iter().map(|field| field.name().as_str());
+        visitor.visit_map(ColumnsAccess {
+            names: names.collect(),
+            columns: self.batch.columns(),
+            row: self.row,
+            next: 0,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Yields the values of a row, or of a struct cell, keyed by column name
+struct ColumnsAccess<'a> {
+    names: Vec<&'a str>,
+    columns: &'a [ArrayRef],
+    row: usize,
+    next: usize,
+}
+
+impl<'de> MapAccess<'de> for ColumnsAccess<'_> {
+    type Error = RowError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, RowError> {
+        match self.names.get(self.next) {
+            Some(name) => seed.deserialize(StrDeserializer::new(name)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, RowError> {
+        let name = self.names[self.next];
+        let array = self.columns[self.next].as_ref();
+        self.next += 1;
+        seed.deserialize(CellDeserializer {
+            array,
+            row: self.row,
+        })
+        .map_err(|RowError(reason)| RowError(format!("column `{name}`: {reason}")))
+    }
+}
+
+struct ElementsAccess {
+    values: ArrayRef,
+    next: usize,
+}
+
+impl<'de> SeqAccess<'de> for ElementsAccess {
+    type Error = RowError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, RowError> {
+        if self.next == self.values.len() {
+            return Ok(None);
+        }
+        let row = self.next;
+        self.next += 1;
+        seed.deserialize(CellDeserializer {
+            array: self.values.as_ref(),
+            row,
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len() - self.next)
+    }
+}
+
+struct CellDeserializer<'a> {
+    array: &'a dyn Array,
+    row: usize,
+}
+
+impl CellDeserializer<'_> {
+    fn unsigned(&self) -> Option<u64> {
+        let (array, row) = (self.array, self.row);
+        Some(match array.data_type() {
+            DataType::UInt8 => array.as_primitive::<dt::UInt8Type>().value(row).into(),
+            DataType::UInt16 => array.as_primitive::<dt::UInt16Type>().value(row).into(),
+            DataType::UInt32 => array.as_primitive::<dt::UInt32Type>().value(row).into(),
+            DataType::UInt64 => array.as_primitive::<dt::UInt64Type>().value(row),
+            _ => return None,
+        })
+    }
+
+    fn signed(&self) -> Option<i64> {
+        let (array, row) = (self.array, self.row);
+        Some(match array.data_type() {
+            DataType::Int8 => array.as_primitive::<dt::Int8Type>().value(row).into(),
+            DataType::Int16 => array.as_primitive::<dt::Int16Type>().value(row).into(),
+            DataType::Int32 => array.as_primitive::<dt::Int32Type>().value(row).into(),
+            DataType::Int64 => array.as_primitive::<dt::Int64Type>().value(row),
+            DataType::Timestamp(TimeUnit::Second, _) => {
+                array.as_primitive::<dt::TimestampSecondType>().value(row)
+            }
+            DataType::Timestamp(TimeUnit::Millisecond, _) => array
+                .as_primitive::<dt::TimestampMillisecondType>()
+                .value(row),
+            DataType::Timestamp(TimeUnit::Microsecond, _) => array
+                .as_primitive::<dt::TimestampMicrosecondType>()
+                .value(row),
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => array
+                .as_primitive::<dt::TimestampNanosecondType>()
+                .value(row),
+            _ => return None,
+        })
+    }
+
+    fn binary(&self) -> Option<&[u8]> {
+        let (array, row) = (self.array, self.row);
+        Some(match array.data_type() {
+            DataType::Binary => array.as_binary::<i32>().value(row),
+            DataType::LargeBinary => array.as_binary::<i64>().value(row),
+            DataType::FixedSizeBinary(_) => array.as_fixed_size_binary().value(row),
+            _ => return None,
+        })
+    }
+
+    fn string(&self) -> Option<&str> {
+        let (array, row) = (self.array, self.row);
+        Some(match array.data_type() {
+            DataType::Utf8 => array.as_string::<i32>().value(row),
+            DataType::LargeUtf8 => array.as_string::<i64>().value(row),
+            _ => return None,
+        })
+    }
+}
+
+impl<'de> Deserializer<'de> for CellDeserializer<'_> {
+    type Error = RowError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        let (array, row) = (self.array, self.row);
+        if array.is_null(row) {
+            return visitor.visit_unit();
+        }
+        if let Some(value) = self.unsigned() {
+            return visitor.visit_u64(value);
+        }
+        if let Some(value) = self.signed() {
+            return visitor.visit_i64(value);
+        }
+        if let Some(value) = self.string() {
+            return visitor.visit_str(value);
+        }
+        // binary values are hex encoded, which is what the hash and bytes types expect
+        if let Some(value) = self.binary() {
+            return visitor.visit_string(to_hex(value));
+        }
+
+        match array.data_type() {
+            DataType::Boolean => visitor.visit_bool(array.as_boolean().value(row)),
+            DataType::Float32 => {
+                visitor.visit_f64(array.as_primitive::<dt::Float32Type>().value(row).into())
+            }
+            DataType::Float64 => {
+                visitor.visit_f64(array.as_primitive::<dt::Float64Type>().value(row))
+            }
+            DataType::Decimal128(_, _) => visitor.visit_string(
+                array
+                    .as_primitive::<dt::Decimal128Type>()
+                    .value_as_string(row),
+            ),
+            DataType::Decimal256(_, _) => visitor.visit_string(
+                array
+                    .as_primitive::<dt::Decimal256Type>()
+                    .value_as_string(row),
+            ),
+            DataType::List(_) => visitor.visit_seq(ElementsAccess {
+                values: array.as_list::<i32>().value(row),
+                next: 0,
+            }),
+            DataType::LargeList(_) => visitor.visit_seq(ElementsAccess {
+                values: array.as_list::<i64>().value(row),
+                next: 0,
+            }),
+            DataType::Struct(fields) => visitor.visit_map(ColumnsAccess {
+                names: fields.iter().map(|field| field.name().as_str()).collect(),
+                columns: array.as_struct().columns(),
+                row,
+                next: 0,
+            }),
+            other => Err(de::Error::custom(format!("unsupported data type {other}"))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        if self.array.is_null(self.row) {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    // `U64`, `U256` and the like only deserialize from hex strings
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        if self.array.is_null(self.row) {
+            return self.deserialize_any(visitor);
+        }
+        match self.unsigned() {
+            Some(value) => visitor.visit_string(format!("{value:#x}")),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        match self.binary().filter(|_| !self.array.is_null(self.row)) {
+            Some(value) => visitor.visit_bytes(value),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, RowError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, RowError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, RowError> {
+        match self.string().filter(|_| !self.array.is_null(self.row)) {
+            Some(variant) => visitor.visit_enum(StrDeserializer::new(variant)),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes.iter().fold(String::from("0x"), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
@@ -0,0 +1,254 @@
+//! Decoding of [`Format::Avro`](crate::Format::Avro) responses
+
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    str::FromStr,
+    task::{Context, Poll},
+};
+
+use apache_avro::{from_avro_datum, from_value, types::Value, Codec, Schema};
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+use super::{
+    error::{Error, ResponseError, Result},
+    provider::ResponseStream,
+};
+
+const MAGIC: [u8; 4] = [b'O', b'b', b'j', 1];
+const SYNC_SIZE: usize = 16;
+
+/// Decodes an Avro object container response into [`Value`]s.
+///
+/// The transport does not split the response on block boundaries, so blocks are
+/// buffered until complete. Every block must end with the sync marker of the
+/// header, otherwise the stream ends with an error.
+pub fn avro_records(stream: ResponseStream<Vec<u8>>) -> AvroRecords<Value> {
+    AvroRecords::new(stream, Ok)
+}
+
+/// Decodes an Avro object container response into `T`, see [`avro_records`]
+pub fn avro_records_as<T: DeserializeOwned>(stream: ResponseStream<Vec<u8>>) -> AvroRecords<T> {
+    AvroRecords::new(stream, |value| Ok(from_value(&value)?))
+}
+
+struct Header {
+    schema: Schema,
+    codec: Codec,
+    sync: [u8; SYNC_SIZE],
+}
+
+/// A stream of Avro records, see [`avro_records`]
+pub struct AvroRecords<T> {
+    inner: ResponseStream<Vec<u8>>,
+    convert: fn(Value) -> Result<T>,
+    buffer: Vec<u8>,
+    header: Option<Header>,
+    records: VecDeque<Result<T>>,
+    done: bool,
+}
+
+impl<T> AvroRecords<T> {
+    fn new(stream: ResponseStream<Vec<u8>>, convert: fn(Value) -> Result<T>) -> Self {
+        Self {
+            inner: ResponseError::map_stream(stream).boxed(),
+            convert,
+            buffer: Vec::new(),
+            header: None,
+            records: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Returns the writer schema embedded in the container, once its header was read
+    pub fn schema(&self) -> Option<&Schema> {
+        self.header.as_ref().map(|header| &header.schema)
+    }
+
+    /// Decodes the header and all complete blocks from the buffer
+    fn decode(&mut self) -> Result<()> {
+        loop {
+            let consumed = match &self.header {
+                None => match parse_header(&self.buffer)? {
+                    Some((header, consumed)) => {
+                        self.header = Some(header);
+                        consumed
+                    }
+                    None => return Ok(()),
+                },
+                Some(header) => match parse_block(&self.buffer, header)? {
+                    Some((values, consumed)) => {
+                        self.records.extend(values.into_iter().map(self.convert));
+                        consumed
+                    }
+                    None => return Ok(()),
+                },
+            };
+            self.buffer.drain(..consumed);
+        }
+    }
+}
+
+// records are never pinned in place, only the boxed inner stream is polled
+impl<T> Unpin for AvroRecords<T> {}
+
+impl<T> Stream for AvroRecords<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            if let Some(record) = this.records.pop_front() {
+                return Poll::Ready(Some(record));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            match this.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    this.buffer.extend_from_slice(&chunk);
+                    if let Err(err) = this.decode() {
+                        this.done = true;
+                        this.records.push_back(Err(err));
+                    }
+                }
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => {
+                    this.done = true;
+                    if !this.buffer.is_empty() {
+                        this.records
+                            .push_back(Err(malformed("the container ends mid block")));
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+fn malformed(reason: impl Into<std::borrow::Cow<'static, str>>) -> Error {
+    Error::AvroContainer(reason.into())
+}
+
+/// Reads the primitives of the container framing, returning `None` when the
+/// bytes end before the value does
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn long(&mut self) -> Result<Option<i64>> {
+        let mut value = 0u64;
+        let rest = self.bytes.get(self.pos..).unwrap_or_default();
+        for (i, byte) in rest.iter().enumerate() {
+            if i == 10 {
+                return Err(malformed("a varint is longer than 10 bytes"));
+            }
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.pos += i + 1;
+                return Ok(Some((value >> 1) as i64 ^ -((value & 1) as i64)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn bytes(&mut self) -> Result<Option<&'a [u8]>> {
+        let Some(len) = self.long()? else {
+            return Ok(None);
+        };
+        let len = usize::try_from(len).map_err(|_| malformed("a negative length"))?;
+        Ok(self.take(len))
+    }
+}
+
+fn parse_header(bytes: &[u8]) -> Result<Option<(Header, usize)>> {
+    let magic = &bytes[..bytes.len().min(MAGIC.len())];
+    if !MAGIC.starts_with(magic) {
+        return Err(malformed("the response is not an avro object container"));
+    }
+
+    let mut cursor = Cursor {
+        bytes,
+        pos: MAGIC.len(),
+    };
+    let mut metadata = HashMap::new();
+    loop {
+        let Some(count) = cursor.long()? else {
+            return Ok(None);
+        };
+        if count == 0 {
+            break;
+        }
+        // a negative count is followed by the size of the block in bytes
+        if count < 0 && cursor.long()?.is_none() {
+            return Ok(None);
+        }
+        for _ in 0..count.unsigned_abs() {
+            let Some(key) = cursor.bytes()? else {
+                return Ok(None);
+            };
+            let Some(value) = cursor.bytes()? else {
+                return Ok(None);
+            };
+            metadata.insert(key, value);
+        }
+    }
+    let Some(sync) = cursor.take(SYNC_SIZE) else {
+        return Ok(None);
+    };
+
+    let schema = metadata
+        .get(b"avro.schema".as_slice())
+        .ok_or_else(|| malformed("the header has no schema"))?;
+    let schema = std::str::from_utf8(schema).map_err(|_| malformed("the schema is not utf-8"))?;
+    let codec = match metadata.get(b"avro.codec".as_slice()) {
+        Some(codec) => String::from_utf8_lossy(codec).into_owned(),
+        None => "null".to_string(),
+    };
+
+    let header = Header {
+        schema: Schema::parse_str(schema)?,
+        codec: Codec::from_str(&codec)
+            .map_err(|_| malformed(format!("unsupported codec {codec}")))?,
+        sync: sync.try_into().unwrap(),
+    };
+
+    Ok(Some((header, cursor.pos)))
+}
+
+fn parse_block(bytes: &[u8], header: &Header) -> Result<Option<(Vec<Value>, usize)>> {
+    let mut cursor = Cursor { bytes, pos: 0 };
+    let Some(count) = cursor.long()? else {
+        return Ok(None);
+    };
+    let Some(data) = cursor.bytes()? else {
+        return Ok(None);
+    };
+    let Some(sync) = cursor.take(SYNC_SIZE) else {
+        return Ok(None);
+    };
+    if sync != header.sync {
+        return Err(malformed("a block does not end with the sync marker"));
+    }
+
+    let mut data = data.to_vec();
+    header.codec.decompress(&mut data)?;
+
+    let mut reader = data.as_slice();
+    let values = (0..count)
+        .map(|_| from_avro_datum(&header.schema, &mut reader, None))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(Some((values, cursor.pos)))
+}
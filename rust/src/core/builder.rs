@@ -52,6 +52,10 @@ pub struct ClientBuilder {
 /// ```
 impl ClientBuilder {
     /// Sets the endpoint of the client.
+    ///
+    /// The endpoint is a host with an optional port and path prefix, e.g.
+    /// `ingress.internal:8443/pangea` for a deployment that serves the API
+    /// under `/pangea/v1/api/` and `/pangea/v1/websocket`.
     pub fn endpoint(mut self, endpoint: &str) -> Self {
         self.endpoint = endpoint.to_string();
         self
@@ -1,18 +1,33 @@
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt};
+use serde::Deserialize;
+use tokio::io::AsyncWrite;
 
 use super::{
-    error::ResponseError,
+    download::{self, Checkpoint, DownloadSummary, ResumableRequest},
+    error::Result,
+    ext::StreamResponseExt,
     provider::{
         BtcProvider, ChainProvider, CurveProvider, Erc20Provider, FuelProvider, Provider,
         StreamResponse, UniswapV2Provider, UniswapV3Provider,
     },
     requests::{
         blocks, btc, curve, erc20, fuel, logs, mira, transfers, txs, uniswap_v2, uniswap_v3,
+        HasBlockBounds,
     },
-    types::{format::Format, status::Status},
+    types::{
+        self,
+        blocks::Block,
+        format::Format,
+        logs::Log,
+        page::{Cursor, PageOptions},
+        status::Status,
+        transfers::Transfer,
+        txs::Transaction,
+    },
+    utils::json_lines,
 };
-use crate::{Operation, WsProvider};
+use crate::{HttpProvider, Operation, RateLimitInfo, RetryPolicy, WsProvider};
 
 pub struct Client<T> {
     pub inner: T,
@@ -28,19 +43,439 @@ where
 
     pub async fn get_status(&self) -> StreamResponse<Status> {
         let raw_data_stream = self.inner.get_status_by_format(Format::JsonStream).await?;
-        let raw_data_stream = ResponseError::map_stream(raw_data_stream);
+        json_lines(raw_data_stream)
+    }
+}
+
+impl<T> Client<T>
+where
+    T: ChainProvider + Send + Sync,
+{
+    /// Streams blocks as typed [`Block`] records
+    pub async fn get_blocks(&self, request: blocks::GetBlocksRequest) -> StreamResponse<Block> {
+        let raw_data_stream = self
+            .get_blocks_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams logs as typed [`Log`] records
+    pub async fn get_logs(&self, request: logs::GetLogsRequest) -> StreamResponse<Log> {
+        let limit = request.limit;
+        let raw_data_stream = self
+            .get_logs_by_format(request, Format::JsonStream, false)
+            .await?;
+        limited(json_lines(raw_data_stream), limit)
+    }
+
+    /// Streams transactions as typed [`Transaction`] records
+    pub async fn get_txs(&self, request: txs::GetTxsRequest) -> StreamResponse<Transaction> {
+        let limit = request.limit;
+        let raw_data_stream = self
+            .get_txs_by_format(request, Format::JsonStream, false)
+            .await?;
+        limited(json_lines(raw_data_stream), limit)
+    }
+
+    /// Streams native value transfers as typed [`Transfer`] records
+    pub async fn get_transfers(
+        &self,
+        request: transfers::GetTransfersRequest,
+    ) -> StreamResponse<Transfer> {
+        let limit = request.limit;
+        let raw_data_stream = self
+            .get_transfers_by_format(request, Format::JsonStream, false)
+            .await?;
+        limited(json_lines(raw_data_stream), limit)
+    }
+}
+
+impl<T> Client<T>
+where
+    T: UniswapV2Provider + Send + Sync,
+{
+    /// Streams Uniswap V2 pairs as typed [`Pair`](types::uniswap_v2::Pair) records
+    pub async fn get_uniswap_v2_pairs(
+        &self,
+        request: uniswap_v2::GetPairsRequest,
+    ) -> StreamResponse<types::uniswap_v2::Pair> {
+        let raw_data_stream = self
+            .get_pairs_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Uniswap V2 reserve updates as typed [`Price`](types::uniswap_v2::Price) records
+    pub async fn get_uniswap_v2_prices(
+        &self,
+        request: uniswap_v2::GetPricesRequest,
+    ) -> StreamResponse<types::uniswap_v2::Price> {
+        let limit = request.limit;
+        let raw_data_stream = self
+            .get_prices_by_format(request, Format::JsonStream, false)
+            .await?;
+        limited(json_lines(raw_data_stream), limit)
+    }
+}
+
+impl<T> Client<T>
+where
+    T: UniswapV3Provider + Send + Sync,
+{
+    /// Streams Uniswap V3 fee updates as typed [`FeeRecord`](types::uniswap_v3::FeeRecord) records
+    pub async fn get_uniswap_v3_fees(
+        &self,
+        request: uniswap_v3::GetFeesRequest,
+    ) -> StreamResponse<types::uniswap_v3::FeeRecord> {
+        let raw_data_stream = self
+            .get_fees_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Uniswap V3 pools as typed [`Pool`](types::uniswap_v3::Pool) records
+    pub async fn get_uniswap_v3_pools(
+        &self,
+        request: uniswap_v3::GetPoolsRequest,
+    ) -> StreamResponse<types::uniswap_v3::Pool> {
+        let raw_data_stream = self
+            .get_pools_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Uniswap V3 positions as typed [`Position`](types::uniswap_v3::Position) records
+    pub async fn get_uniswap_v3_positions(
+        &self,
+        request: uniswap_v3::GetPositionsRequest,
+    ) -> StreamResponse<types::uniswap_v3::Position> {
+        let raw_data_stream = self
+            .get_positions_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Uniswap V3 swaps as typed [`PriceRecord`](types::uniswap_v3::PriceRecord) records
+    pub async fn get_uniswap_v3_prices(
+        &self,
+        request: uniswap_v3::GetPricesRequest,
+    ) -> StreamResponse<types::uniswap_v3::PriceRecord> {
+        let limit = request.limit;
+        let raw_data_stream = self
+            .get_prices_by_format(request, Format::JsonStream, false)
+            .await?;
+        limited(json_lines(raw_data_stream), limit)
+    }
+}
+
+impl<T> Client<T>
+where
+    T: CurveProvider + Send + Sync,
+{
+    /// Streams Curve exchanges as typed [`CrvPrice`](types::curve::CrvPrice) records
+    pub async fn get_curve_prices(
+        &self,
+        request: curve::GetCrvPriceRequest,
+    ) -> StreamResponse<types::curve::CrvPrice> {
+        let raw_data_stream = self
+            .get_prices_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Curve tokens as typed [`CrvToken`](types::curve::CrvToken) records
+    pub async fn get_curve_tokens(
+        &self,
+        request: curve::GetCrvTokenRequest,
+    ) -> StreamResponse<types::curve::CrvToken> {
+        let raw_data_stream = self
+            .get_tokens_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Curve pools as typed [`CrvPool`](types::curve::CrvPool) records
+    pub async fn get_curve_pools(
+        &self,
+        request: curve::GetCrvPoolRequest,
+    ) -> StreamResponse<types::curve::CrvPool> {
+        let raw_data_stream = self
+            .get_pools_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+}
+
+impl<T> Client<T>
+where
+    T: Erc20Provider + Send + Sync,
+{
+    /// Streams ERC-20 approvals as typed [`Approval`](types::erc20::Approval) records
+    pub async fn get_erc20_approvals(
+        &self,
+        request: erc20::GetErc20ApprovalsRequest,
+    ) -> StreamResponse<types::erc20::Approval> {
+        let raw_data_stream = self
+            .get_erc20_approval_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams ERC-20 transfers as typed [`Erc20Transfer`](types::erc20::Erc20Transfer) records
+    pub async fn get_erc20_transfers(
+        &self,
+        request: erc20::GetErc20TransfersRequest,
+    ) -> StreamResponse<types::erc20::Erc20Transfer> {
+        let limit = request.limit;
+        let raw_data_stream = self
+            .get_erc20_transfers_by_format(request, Format::JsonStream, false)
+            .await?;
+        limited(json_lines(raw_data_stream), limit)
+    }
+}
+
+impl<T> Client<T>
+where
+    T: FuelProvider + Send + Sync,
+{
+    /// Streams Fuel blocks as typed [`FuelBlock`](types::fuel::FuelBlock) records
+    pub async fn get_fuel_blocks(
+        &self,
+        request: fuel::GetFuelBlocksRequest,
+    ) -> StreamResponse<types::fuel::FuelBlock> {
+        let raw_data_stream = self
+            .get_fuel_blocks_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams ABI-decoded Fuel logs as typed [`DecodedLog`](types::fuel::DecodedLog) records
+    pub async fn get_fuel_logs_decoded(
+        &self,
+        request: fuel::GetFuelLogsRequest,
+    ) -> StreamResponse<types::fuel::DecodedLog> {
+        let raw_data_stream = self
+            .get_fuel_logs_decoded_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Fuel receipts as typed [`Receipt`](types::fuel::Receipt) records
+    pub async fn get_fuel_receipts(
+        &self,
+        request: fuel::GetFuelReceiptsRequest,
+    ) -> StreamResponse<types::fuel::Receipt> {
+        let raw_data_stream = self
+            .get_fuel_receipts_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
 
-        let records = raw_data_stream
-            .map(|chunk_result| {
-                chunk_result.and_then(|chunk| Ok(serde_json::from_slice::<Status>(&chunk)?))
-            })
-            .boxed();
+    /// Streams Spark order events as typed [`SparkOrder`](types::fuel::SparkOrder) records
+    pub async fn get_fuel_spark_orders(
+        &self,
+        request: fuel::GetSparkOrderRequest,
+    ) -> StreamResponse<types::fuel::SparkOrder> {
+        let raw_data_stream = self
+            .get_fuel_spark_orders_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
 
-        Ok(records)
+    /// Streams SRC-20 assets as typed [`Src20Asset`](types::fuel::Src20Asset) records
+    pub async fn get_fuel_src20(
+        &self,
+        request: fuel::GetSrc20,
+    ) -> StreamResponse<types::fuel::Src20Asset> {
+        let raw_data_stream = self
+            .get_fuel_src20_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Mira swaps as typed [`MiraSwap`](types::mira::MiraSwap) records
+    pub async fn get_fuel_mira_swaps(
+        &self,
+        request: mira::GetMiraSwapsRequest,
+    ) -> StreamResponse<types::mira::MiraSwap> {
+        let limit = request.limit;
+        let raw_data_stream = self
+            .get_fuel_mira_v1_swaps_by_format(request, Format::JsonStream, false)
+            .await?;
+        limited(json_lines(raw_data_stream), limit)
     }
 }
 
+impl<T> Client<T>
+where
+    T: BtcProvider + Send + Sync,
+{
+    /// Streams Bitcoin blocks as typed [`BtcBlock`](types::btc::BtcBlock) records
+    pub async fn get_btc_blocks(
+        &self,
+        request: btc::GetBtcBlocksRequest,
+    ) -> StreamResponse<types::btc::BtcBlock> {
+        let raw_data_stream = self
+            .get_btc_blocks_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+
+    /// Streams Bitcoin transactions as typed [`BtcTransaction`](types::btc::BtcTransaction) records
+    pub async fn get_btc_txs(
+        &self,
+        request: btc::GetBtcTxsRequest,
+    ) -> StreamResponse<types::btc::BtcTransaction> {
+        let raw_data_stream = self
+            .get_btc_txs_by_format(request, Format::JsonStream, false)
+            .await?;
+        json_lines(raw_data_stream)
+    }
+}
+
+impl<T> Client<T>
+where
+    T: Send + Sync,
+{
+    /// Downloads the response of `request` into `sink`, resuming after the last
+    /// complete block when it fails.
+    ///
+    /// Records are held back until the first record of the next block arrives,
+    /// so only complete blocks are written. Once a block is written and the sink
+    /// flushed, it is saved to `checkpoint`. A failed response is requested
+    /// again with `from_block` after the checkpoint, and appended to the sink,
+    /// retried as the default [`RetryPolicy`] says. A download started with a
+    /// saved checkpoint continues after it, e.g. after the process restarted.
+    ///
+    /// Only [`Format::JsonStream`] responses can be resumed, as the block of a
+    /// record is read from its `block_number`. The `limit` of a resumed request
+    /// is lowered by the records written before, so a limited download fails
+    /// with [`Error::InvalidRequest`](crate::Error::InvalidRequest) if it would
+    /// continue after a checkpoint of an earlier run, as do requests in
+    /// descending order.
+    pub async fn download_resumable<R, W, C>(
+        &self,
+        request: R,
+        format: Format,
+        sink: &mut W,
+        checkpoint: &C,
+    ) -> Result<DownloadSummary>
+    where
+        R: ResumableRequest<T>,
+        W: AsyncWrite + Unpin + Send + ?Sized,
+        C: Checkpoint + ?Sized,
+    {
+        let policy = RetryPolicy::default();
+        download::download(&self.inner, request, format, sink, checkpoint, policy).await
+    }
+
+    /// Downloads like [`download_resumable`](Self::download_resumable), and
+    /// resumes as `policy` says. Its attempts are counted from the last block
+    /// written.
+    pub async fn download_resumable_with_retry<R, W, C>(
+        &self,
+        request: R,
+        format: Format,
+        sink: &mut W,
+        checkpoint: &C,
+        policy: RetryPolicy,
+    ) -> Result<DownloadSummary>
+    where
+        R: ResumableRequest<T>,
+        W: AsyncWrite + Unpin + Send + ?Sized,
+        C: Checkpoint + ?Sized,
+    {
+        download::download(&self.inner, request, format, sink, checkpoint, policy).await
+    }
+}
+
+impl Client<HttpProvider> {
+    /// See [`HttpProvider::rate_limit`]
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        self.inner.rate_limit()
+    }
+
+    /// Requests a single page of logs, and returns it with the cursor of the
+    /// next page, `None` on the last one.
+    ///
+    /// The cursor is read from the `x-next-cursor` header, or from a trailing
+    /// `{"next_cursor": ...}` record, which is not returned as a log. Pass it
+    /// back in [`PageOptions::cursor`] to request the next page.
+    pub async fn get_logs_page(
+        &self,
+        request: logs::GetLogsRequest,
+        page: PageOptions,
+    ) -> Result<(Vec<Log>, Option<Cursor>)> {
+        request.validate()?;
+        let (raw_data_stream, next) = self
+            .inner
+            .get_logs_page_by_format(request, Format::JsonStream, &page)
+            .await?;
+        let mut body: Vec<u8> = raw_data_stream.try_concat().await?;
+        let trailer = split_trailer(&mut body);
+
+        let body = futures::stream::once(async { Ok(body) }).boxed();
+        let logs = json_lines(body).collect_vec().await?;
+        Ok((logs, next.or(trailer)))
+    }
+
+    /// See [`HttpProvider::get_logs_count`]
+    pub async fn get_logs_count(&self, request: logs::GetLogsRequest) -> Result<u64> {
+        request.validate()?;
+        self.inner.get_logs_count(request).await
+    }
+
+    /// See [`HttpProvider::get_transfers_count`]
+    pub async fn get_transfers_count(
+        &self,
+        request: transfers::GetTransfersRequest,
+    ) -> Result<u64> {
+        request.validate()?;
+        self.inner.get_transfers_count(request).await
+    }
+
+    /// See [`HttpProvider::get_erc20_transfers_count`]
+    pub async fn get_erc20_transfers_count(
+        &self,
+        request: erc20::GetErc20TransfersRequest,
+    ) -> Result<u64> {
+        request.validate()?;
+        self.inner.get_erc20_transfers_count(request).await
+    }
+
+    /// See [`HttpProvider::raw_get`]
+    pub async fn raw_get(
+        &self,
+        path: &str,
+        params: impl serde::Serialize,
+        format: Format,
+    ) -> StreamResponse<Vec<u8>> {
+        self.inner.raw_get(path, params, format).await
+    }
+}
+
+/// Removes a trailing `{"next_cursor": ...}` record from a page, and returns
+/// its cursor
+fn split_trailer(body: &mut Vec<u8>) -> Option<Cursor> {
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Trailer {
+        next_cursor: Option<Cursor>,
+    }
+
+    let end = body.iter().rposition(|byte| !byte.is_ascii_whitespace())? + 1;
+    let start = body[..end]
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let trailer = serde_json::from_slice::<Trailer>(&body[start..end]).ok()?;
+    body.truncate(start);
+    trailer.next_cursor
+}
+
 impl Client<WsProvider> {
+    /// Sends `params` as a request of `operation`, and returns the raw
+    /// response, e.g. for parameters the typed requests do not have
     pub async fn raw_request(
         &self,
         operation: Operation,
@@ -63,6 +498,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_blocks_by_format(request, format, deltas)
             .await
@@ -74,6 +511,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner.get_logs_by_format(request, format, deltas).await
     }
 
@@ -83,6 +522,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner.get_txs_by_format(request, format, deltas).await
     }
 
@@ -92,6 +533,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_transfers_by_format(request, format, deltas)
             .await
@@ -109,6 +552,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_pairs_by_format(request, format, deltas)
             .await
@@ -120,6 +565,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_prices_by_format(request, format, deltas)
             .await
@@ -137,6 +584,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner.get_fees_by_format(request, format, deltas).await
     }
 
@@ -146,6 +595,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_pools_by_format(request, format, deltas)
             .await
@@ -157,6 +608,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_prices_by_format(request, format, deltas)
             .await
@@ -168,6 +621,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_positions_by_format(request, format, deltas)
             .await
@@ -185,6 +640,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_tokens_by_format(request, format, deltas)
             .await
@@ -196,6 +653,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_pools_by_format(request, format, deltas)
             .await
@@ -207,6 +666,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_prices_by_format(request, format, deltas)
             .await
@@ -224,6 +685,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_erc20_by_format(request, format, deltas)
             .await
@@ -235,6 +698,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_erc20_approval_by_format(request, format, deltas)
             .await
@@ -242,10 +707,12 @@ where
 
     async fn get_erc20_transfers_by_format(
         &self,
-        request: erc20::GetErc20TransferssRequest,
+        request: erc20::GetErc20TransfersRequest,
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_erc20_transfers_by_format(request, format, deltas)
             .await
@@ -263,6 +730,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -276,6 +744,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -289,6 +758,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -302,6 +772,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -315,6 +786,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -328,6 +800,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -341,6 +814,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -354,6 +828,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -367,6 +842,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -380,6 +856,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -393,6 +870,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -406,6 +884,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -419,6 +898,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -432,6 +912,7 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
         self.check_chain(&request.chains)?;
 
         self.inner
@@ -451,6 +932,8 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_btc_blocks_by_format(request, format, deltas)
             .await
@@ -462,8 +945,27 @@ where
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
+        request.validate()?;
+
         self.inner
             .get_btc_txs_by_format(request, format, deltas)
             .await
     }
 }
+
+/// Ends `stream` after `limit` records, in case the server ignored the limit.
+/// Records that failed to decode are passed on without counting.
+fn limited<T: Send + 'static>(stream: StreamResponse<T>, limit: Option<u64>) -> StreamResponse<T> {
+    let Some(limit) = limit else {
+        return stream;
+    };
+    let records = futures::stream::unfold((stream?, limit), |(mut stream, left)| async move {
+        if left == 0 {
+            return None;
+        }
+        let record = stream.next().await?;
+        let left = left - u64::from(record.is_ok());
+        Some((record, (stream, left)))
+    });
+    Ok(records.boxed())
+}
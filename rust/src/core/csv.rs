@@ -0,0 +1,94 @@
+//! Decoding of [`Format::Csv`](crate::Format::Csv) responses into rows
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use csv_async::{AsyncReaderBuilder, StringRecord};
+use futures::{AsyncRead, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+use super::{
+    error::{ResponseError, Result},
+    provider::ResponseStream,
+    types::format::CsvOptions,
+};
+
+/// A stream of CSV rows along with the header of the response
+pub struct CsvRows<T> {
+    headers: StringRecord,
+    rows: ResponseStream<T>,
+}
+
+impl<T> CsvRows<T> {
+    /// Returns the header row, e.g. to check the column order, empty if the
+    /// response has none
+    pub fn headers(&self) -> &StringRecord {
+        &self.headers
+    }
+}
+
+impl<T> Stream for CsvRows<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rows.poll_next_unpin(cx)
+    }
+}
+
+/// Decodes a CSV response requested with `options` into [`StringRecord`]s.
+///
+/// The header, if any, is read before this returns. Rows and quoted fields may
+/// span several chunks, they are only yielded once complete.
+pub async fn csv_rows(
+    stream: ResponseStream<Vec<u8>>,
+    options: CsvOptions,
+) -> Result<CsvRows<StringRecord>> {
+    let mut reader = builder(options).create_reader(into_async_read(stream));
+    let headers = match options.header {
+        true => reader.headers().await?.clone(),
+        false => StringRecord::new(),
+    };
+    let rows = reader.into_records().map_err(Into::into).boxed();
+
+    Ok(CsvRows { headers, rows })
+}
+
+/// Decodes a CSV response requested with `options` into `T`, matching columns
+/// to fields by header name, or by position without a header.
+///
+/// See [`csv_rows`] for how the response is framed.
+pub async fn csv_rows_as<T>(
+    stream: ResponseStream<Vec<u8>>,
+    options: CsvOptions,
+) -> Result<CsvRows<T>>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let mut reader = builder(options).create_deserializer(into_async_read(stream));
+    let headers = match options.header {
+        true => reader.headers().await?.clone(),
+        false => StringRecord::new(),
+    };
+    let rows = reader.into_deserialize::<T>().map_err(Into::into).boxed();
+
+    Ok(CsvRows { headers, rows })
+}
+
+/// A reader of the dialect the server answers `options` with
+fn builder(options: CsvOptions) -> AsyncReaderBuilder {
+    let mut builder = AsyncReaderBuilder::new();
+    builder
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .has_headers(options.header);
+    builder
+}
+
+fn into_async_read(stream: ResponseStream<Vec<u8>>) -> impl AsyncRead + Unpin + Send {
+    ResponseError::map_stream(stream)
+        .map_err(io::Error::other)
+        .into_async_read()
+}
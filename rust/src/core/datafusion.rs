@@ -0,0 +1,312 @@
+//! A DataFusion [`TableProvider`] over the `logs` dataset
+
+use std::{any::Any, collections::HashSet, fmt, str::FromStr, sync::Arc};
+
+use ::datafusion::{
+    arrow::datatypes::SchemaRef,
+    catalog::Session,
+    common::{DataFusionError, ScalarValue},
+    datasource::{TableProvider, TableType},
+    error::Result as DataFusionResult,
+    execution::TaskContext,
+    logical_expr::{
+        expr::InList, Between, BinaryExpr, Expr, Operator, TableProviderFilterPushDown,
+    },
+    physical_plan::{
+        stream::RecordBatchStreamAdapter,
+        streaming::{PartitionStream, StreamingTableExec},
+        ExecutionPlan, SendableRecordBatchStream,
+    },
+};
+use async_trait::async_trait;
+use ethers_core::types::Address;
+use futures::TryStreamExt;
+
+use super::{
+    arrow::record_batches,
+    provider::ChainProvider,
+    requests::logs::GetLogsRequest,
+    types::{format::Format, query::Bound},
+};
+
+const BLOCK_NUMBER: &str = "block_number";
+const ADDRESS: &str = "address";
+
+/// A table of the `logs` dataset, to be registered with a DataFusion `SessionContext`.
+///
+/// Filters on `block_number` are translated into the block range of the request
+/// and filters on `address` into `address__in`. DataFusion still applies every
+/// filter to the returned rows, other filters are only applied there.
+pub struct PangeaTable<P> {
+    provider: Arc<P>,
+    schema: SchemaRef,
+    request: GetLogsRequest,
+}
+
+impl<P> PangeaTable<P>
+where
+    P: ChainProvider + Send + Sync + 'static,
+{
+    /// Creates a table over `request`, which filters pushed down by a query narrow further.
+    ///
+    /// The schema must match the Arrow schema the server sends for the dataset.
+    pub fn logs(provider: Arc<P>, schema: SchemaRef, request: GetLogsRequest) -> Self {
+        Self {
+            provider,
+            schema,
+            request,
+        }
+    }
+
+    /// Returns the request a scan with `filters` sends
+    pub fn request_for(&self, filters: &[Expr]) -> GetLogsRequest {
+        let mut request = self.request.clone();
+        for filter in filters {
+            push_down(&mut request, filter);
+        }
+        request
+    }
+}
+
+impl<P> fmt::Debug for PangeaTable<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PangeaTable")
+            .field("schema", &self.schema)
+            .field("request", &self.request)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<P> TableProvider for PangeaTable<P>
+where
+    P: ChainProvider + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> DataFusionResult<Vec<TableProviderFilterPushDown>> {
+        Ok(filters
+            .iter()
+            .map(|filter| {
+                let mut request = GetLogsRequest::default();
+                if push_down(&mut request, filter) {
+                    // the request may still return more rows, e.g. after disjoint address sets
+                    TableProviderFilterPushDown::Inexact
+                } else {
+                    TableProviderFilterPushDown::Unsupported
+                }
+            })
+            .collect())
+    }
+
+    async fn scan(
+        &self,
+        _state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let partition = LogsPartition {
+            provider: self.provider.clone(),
+            schema: self.schema.clone(),
+            request: self.request_for(filters),
+        };
+
+        Ok(Arc::new(StreamingTableExec::try_new(
+            self.schema.clone(),
+            vec![Arc::new(partition)],
+            projection,
+            vec![],
+            false,
+            limit,
+        )?))
+    }
+}
+
+/// Sends the request once the plan is executed and streams its batches
+struct LogsPartition<P> {
+    provider: Arc<P>,
+    schema: SchemaRef,
+    request: GetLogsRequest,
+}
+
+impl<P> fmt::Debug for LogsPartition<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogsPartition")
+            .field("request", &self.request)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P> PartitionStream for LogsPartition<P>
+where
+    P: ChainProvider + Send + Sync + 'static,
+{
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let provider = self.provider.clone();
+        let request = self.request.clone();
+
+        let batches = futures::stream::once(async move {
+            provider
+                .get_logs_by_format(request, Format::Arrow, false)
+                .await
+        })
+        .map_ok(record_batches)
+        .try_flatten()
+        .map_err(|err| DataFusionError::External(Box::new(err)));
+
+        Box::pin(RecordBatchStreamAdapter::new(self.schema.clone(), batches))
+    }
+}
+
+/// Narrows `request` by `filter`, returns false if the filter can not be pushed down
+fn push_down(request: &mut GetLogsRequest, filter: &Expr) -> bool {
+    match filter {
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+            Operator::And => {
+                // both sides narrow the request, so pushing down only one is fine
+                let left = push_down(request, left);
+                let right = push_down(request, right);
+                left && right
+            }
+            _ => match (column(left), column(right)) {
+                (Some(name), None) => compare(request, name, *op, right),
+                (None, Some(name)) => match op.swap() {
+                    Some(op) => compare(request, name, op, left),
+                    None => false,
+                },
+                _ => false,
+            },
+        },
+        Expr::Between(Between {
+            expr,
+            negated: false,
+            low,
+            high,
+        }) if column(expr) == Some(BLOCK_NUMBER) => match (block(low), block(high)) {
+            (Some(low), Some(high)) => {
+                narrow_from(request, low);
+                narrow_to(request, high.saturating_add(1));
+                true
+            }
+            _ => false,
+        },
+        Expr::InList(InList {
+            expr,
+            list,
+            negated: false,
+        }) if column(expr) == Some(ADDRESS) => {
+            let Some(addresses) = list.iter().map(address).collect::<Option<Vec<_>>>() else {
+                return false;
+            };
+            narrow_addresses(request, addresses);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Narrows `request` by `name <op> value`
+fn compare(request: &mut GetLogsRequest, name: &str, op: Operator, value: &Expr) -> bool {
+    match name {
+        BLOCK_NUMBER => {
+            let Some(value) = block(value) else {
+                return false;
+            };
+            match op {
+                Operator::Eq => {
+                    narrow_from(request, value);
+                    narrow_to(request, value.saturating_add(1));
+                }
+                Operator::Gt => narrow_from(request, value.saturating_add(1)),
+                Operator::GtEq => narrow_from(request, value),
+                Operator::Lt => narrow_to(request, value),
+                Operator::LtEq => narrow_to(request, value.saturating_add(1)),
+                _ => return false,
+            }
+            true
+        }
+        ADDRESS if op == Operator::Eq => match address(value) {
+            Some(address) => {
+                narrow_addresses(request, vec![address]);
+                true
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn narrow_from(request: &mut GetLogsRequest, block: i64) {
+    request.from_block = match request.from_block {
+        Bound::Exact(from) => Bound::Exact(from.max(block)),
+        _ => Bound::Exact(block),
+    };
+}
+
+fn narrow_to(request: &mut GetLogsRequest, block: i64) {
+    request.to_block = match request.to_block {
+        Bound::Exact(to) => Bound::Exact(to.min(block)),
+        _ => Bound::Exact(block),
+    };
+}
+
+fn narrow_addresses(request: &mut GetLogsRequest, addresses: Vec<Address>) {
+    let addresses = addresses.into_iter().collect::<HashSet<_>>();
+    if request.address__in.is_empty() {
+        request.address__in = addresses;
+    } else if !request.address__in.is_disjoint(&addresses) {
+        // an empty set would not filter at all, so disjoint sets keep the old one
+        request
+            .address__in
+            .retain(|address| addresses.contains(address));
+    }
+}
+
+fn column(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Column(column) => Some(column.name.as_str()),
+        _ => None,
+    }
+}
+
+fn block(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Literal(ScalarValue::Int64(Some(value))) => Some(*value),
+        Expr::Literal(ScalarValue::Int32(Some(value))) => Some((*value).into()),
+        Expr::Literal(ScalarValue::UInt64(Some(value))) => i64::try_from(*value).ok(),
+        Expr::Literal(ScalarValue::UInt32(Some(value))) => Some((*value).into()),
+        _ => None,
+    }
+}
+
+fn address(expr: &Expr) -> Option<Address> {
+    match expr {
+        Expr::Literal(ScalarValue::Utf8(Some(value)) | ScalarValue::LargeUtf8(Some(value))) => {
+            Address::from_str(value).ok()
+        }
+        Expr::Literal(
+            ScalarValue::FixedSizeBinary(20, Some(value))
+            | ScalarValue::Binary(Some(value))
+            | ScalarValue::LargeBinary(Some(value)),
+        ) if value.len() == 20 => Some(Address::from_slice(value)),
+        _ => None,
+    }
+}
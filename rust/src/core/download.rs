@@ -0,0 +1,361 @@
+//! Downloads that resume where they left off, see
+//! [`Client::download_resumable`](crate::Client::download_resumable)
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::warn;
+
+use super::{
+    error::{Error, Result},
+    provider::{
+        BtcProvider, ChainProvider, CurveProvider, Erc20Provider, FuelProvider, StreamResponse,
+        UniswapV2Provider, UniswapV3Provider,
+    },
+    requests::{
+        blocks, btc, curve, erc20, fuel, logs, mira, transfers, txs, uniswap_v2, uniswap_v3,
+        HasBlockBounds,
+    },
+    types::{
+        format::Format,
+        query::{Bound, SortOrder},
+    },
+    utils::block_number,
+};
+use crate::RetryPolicy;
+
+/// Where a resumable download records the last block it wrote completely.
+///
+/// Back it with a file, see [`FileCheckpoint`], or a database row, to resume a
+/// download after the process restarted.
+#[async_trait]
+pub trait Checkpoint: Send + Sync {
+    /// The last block that was written completely, `None` if there is none yet
+    async fn load(&self) -> Result<Option<i64>>;
+
+    /// Records that `block` and all blocks before it were written completely
+    async fn save(&self, block: i64) -> Result<()>;
+}
+
+/// A [`Checkpoint`] kept in a small text file, which is replaced atomically on
+/// every save
+#[derive(Clone, Debug)]
+pub struct FileCheckpoint {
+    path: PathBuf,
+}
+
+impl FileCheckpoint {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl Checkpoint for FileCheckpoint {
+    async fn load(&self) -> Result<Option<i64>> {
+        let path = self.path.clone();
+        let contents = tokio::task::spawn_blocking(move || std::fs::read_to_string(path))
+            .await
+            .map_err(|err| Error::Custom(err.to_string().into()))?;
+        match contents {
+            Ok(contents) => contents
+                .trim()
+                .parse()
+                .map(Some)
+                .map_err(|_| Error::Custom(format!("malformed checkpoint {contents:?}").into())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn save(&self, block: i64) -> Result<()> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut temporary = path.clone().into_os_string();
+            temporary.push(".tmp");
+            std::fs::write(&temporary, block.to_string())?;
+            std::fs::rename(&temporary, &path)
+        })
+        .await
+        .map_err(|err| Error::Custom(err.to_string().into()))??;
+        Ok(())
+    }
+}
+
+/// A request that can be downloaded with
+/// [`Client::download_resumable`](crate::Client::download_resumable)
+#[async_trait]
+pub trait ResumableRequest<P>: HasBlockBounds + Clone + Send + Sync {
+    /// Sends the request through the provider
+    async fn fetch(self, provider: &P, format: Format) -> StreamResponse<Vec<u8>>;
+}
+
+macro_rules! impl_resumable_request {
+    ($($request:ty => $provider:ident::$method:ident),* $(,)?) => {
+        $(
+            #[async_trait]
+            impl<P> ResumableRequest<P> for $request
+            where
+                P: $provider + Send + Sync,
+            {
+                async fn fetch(self, provider: &P, format: Format) -> StreamResponse<Vec<u8>> {
+                    $provider::$method(provider, self, format, false).await
+                }
+            }
+        )*
+    };
+}
+
+impl_resumable_request!(
+    blocks::GetBlocksRequest => ChainProvider::get_blocks_by_format,
+    logs::GetLogsRequest => ChainProvider::get_logs_by_format,
+    txs::GetTxsRequest => ChainProvider::get_txs_by_format,
+    transfers::GetTransfersRequest => ChainProvider::get_transfers_by_format,
+    uniswap_v2::GetPairsRequest => UniswapV2Provider::get_pairs_by_format,
+    uniswap_v2::GetPricesRequest => UniswapV2Provider::get_prices_by_format,
+    uniswap_v3::GetFeesRequest => UniswapV3Provider::get_fees_by_format,
+    uniswap_v3::GetPoolsRequest => UniswapV3Provider::get_pools_by_format,
+    uniswap_v3::GetPositionsRequest => UniswapV3Provider::get_positions_by_format,
+    uniswap_v3::GetPricesRequest => UniswapV3Provider::get_prices_by_format,
+    curve::GetCrvTokenRequest => CurveProvider::get_tokens_by_format,
+    curve::GetCrvPoolRequest => CurveProvider::get_pools_by_format,
+    curve::GetCrvPriceRequest => CurveProvider::get_prices_by_format,
+    erc20::GetErc20Request => Erc20Provider::get_erc20_by_format,
+    erc20::GetErc20ApprovalsRequest => Erc20Provider::get_erc20_approval_by_format,
+    erc20::GetErc20TransfersRequest => Erc20Provider::get_erc20_transfers_by_format,
+    fuel::GetFuelBlocksRequest => FuelProvider::get_fuel_blocks_by_format,
+    fuel::GetFuelLogsRequest => FuelProvider::get_fuel_logs_by_format,
+    fuel::GetFuelTxsRequest => FuelProvider::get_fuel_txs_by_format,
+    fuel::GetFuelReceiptsRequest => FuelProvider::get_fuel_receipts_by_format,
+    fuel::GetFuelMessagesRequest => FuelProvider::get_fuel_messages_by_format,
+    fuel::GetUtxoRequest => FuelProvider::get_fuel_unspent_utxos_by_format,
+    fuel::GetSparkMarketRequest => FuelProvider::get_fuel_spark_markets_by_format,
+    fuel::GetSparkOrderRequest => FuelProvider::get_fuel_spark_orders_by_format,
+    fuel::GetSrc20 => FuelProvider::get_fuel_src20_by_format,
+    fuel::GetSrc7 => FuelProvider::get_fuel_src7_by_format,
+    mira::GetMiraPoolsRequest => FuelProvider::get_fuel_mira_v1_pools_by_format,
+    mira::GetMiraLiquidityRequest => FuelProvider::get_fuel_mira_v1_liquidity_by_format,
+    mira::GetMiraSwapsRequest => FuelProvider::get_fuel_mira_v1_swaps_by_format,
+    btc::GetBtcBlocksRequest => BtcProvider::get_btc_blocks_by_format,
+    btc::GetBtcTxsRequest => BtcProvider::get_btc_txs_by_format,
+);
+
+/// What [`Client::download_resumable`](crate::Client::download_resumable)
+/// wrote to the sink
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DownloadSummary {
+    /// The bytes written by this call, not counting earlier runs
+    pub bytes: u64,
+    /// The records written by this call, not counting earlier runs
+    pub records: u64,
+    /// The last block written, including by earlier runs, `None` if there
+    /// were no records
+    pub last_block: Option<i64>,
+    /// How often the request was sent again after it failed
+    pub resumes: usize,
+}
+
+pub(crate) async fn download<P, R, W, C>(
+    provider: &P,
+    request: R,
+    format: Format,
+    sink: &mut W,
+    checkpoint: &C,
+    policy: RetryPolicy,
+) -> Result<DownloadSummary>
+where
+    R: ResumableRequest<P>,
+    W: AsyncWrite + Unpin + Send + ?Sized,
+    C: Checkpoint + ?Sized,
+{
+    if format != Format::JsonStream {
+        return Err(Error::UnsupportedFormat {
+            format,
+            reason: "only JSON lines downloads can be resumed".into(),
+        });
+    }
+    if request.order() == Some(SortOrder::Desc) {
+        return Err(Error::InvalidRequest(
+            "requests in descending order cannot be resumed".into(),
+        ));
+    }
+
+    let mut summary = DownloadSummary {
+        last_block: checkpoint.load().await?,
+        ..Default::default()
+    };
+    // the records of earlier runs are not known, to lower the limit by
+    if summary.last_block.is_some() && request.limit().is_some() {
+        return Err(Error::InvalidRequest(
+            "a limited download cannot continue after a saved checkpoint".into(),
+        ));
+    }
+    // failed attempts since the last block was written
+    let mut failures = 0;
+    loop {
+        let mut request = request.clone();
+        if let Some(last_block) = summary.last_block {
+            let from_block = match request.block_bounds().0 {
+                Bound::Exact(from_block) => from_block.max(last_block + 1),
+                _ => last_block + 1,
+            };
+            request.set_from_block(Bound::Exact(from_block));
+        }
+        if let Some(limit) = request.limit() {
+            if summary.records >= limit {
+                return Ok(summary);
+            }
+            request.set_limit(Some(limit - summary.records));
+        }
+
+        let mut block = Block::default();
+        let result = async {
+            let mut stream = request.fetch(provider, format).await?;
+            while let Some(chunk) = stream.next().await {
+                for complete in block.push(&chunk?) {
+                    write(sink, checkpoint, complete, &mut summary).await?;
+                    failures = 0;
+                }
+            }
+            Ok::<_, Failure>(())
+        }
+        .await;
+
+        let err = match result {
+            Ok(()) => {
+                if let Some(complete) = block.finish() {
+                    write(sink, checkpoint, complete, &mut summary)
+                        .await
+                        .map_err(Failure::into_error)?;
+                }
+                return Ok(summary);
+            }
+            Err(Failure::Sink(err)) => return Err(err),
+            Err(Failure::Response(err)) => err,
+        };
+
+        failures += 1;
+        if failures >= policy.max_attempts.max(1) || !is_transient(&err) {
+            return Err(err);
+        }
+        let delay = policy.delay(failures);
+        warn!(
+            "download failed after block {:?}, resuming in {delay:?}: {err}",
+            summary.last_block
+        );
+        tokio::time::sleep(delay).await;
+        summary.resumes += 1;
+    }
+}
+
+/// Whether a failed response is worth requesting again
+fn is_transient(err: &Error) -> bool {
+    match err {
+        Error::StreamInterrupted { source, .. } => is_transient(source),
+        Error::Http { status, .. } => *status >= 500 || *status == 429,
+        Error::Reqwest(_)
+        | Error::RateLimited { .. }
+        | Error::IO(_)
+        | Error::Timeout(_)
+        | Error::ConnectTimeout(_)
+        | Error::Stalled(_)
+        | Error::ConnectionClosed
+        | Error::ConnectionStale
+        | Error::UnexpectedClose
+        | Error::Tungstenite(_) => true,
+        _ => false,
+    }
+}
+
+/// An error of the response, which is retried, or of the sink, which is not
+enum Failure {
+    Response(Error),
+    Sink(Error),
+}
+
+impl Failure {
+    fn into_error(self) -> Error {
+        match self {
+            Failure::Response(err) | Failure::Sink(err) => err,
+        }
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(err: Error) -> Self {
+        Failure::Response(err)
+    }
+}
+
+/// Writes a complete block to the sink, and records it once it is flushed
+async fn write<W, C>(
+    sink: &mut W,
+    checkpoint: &C,
+    (block, records): (Option<i64>, Vec<u8>),
+    summary: &mut DownloadSummary,
+) -> std::result::Result<(), Failure>
+where
+    W: AsyncWrite + Unpin + Send + ?Sized,
+    C: Checkpoint + ?Sized,
+{
+    let written = async {
+        sink.write_all(&records).await?;
+        sink.flush().await?;
+        match block {
+            Some(block) => checkpoint.save(block).await,
+            None => Ok(()),
+        }
+    };
+    written.await.map_err(Failure::Sink)?;
+    summary.bytes += records.len() as u64;
+    summary.records += records.split_inclusive(|byte| *byte == b'\n').count() as u64;
+    summary.last_block = block.or(summary.last_block);
+    Ok(())
+}
+
+/// Holds back the records of a block until the first record of the next one
+/// shows it is complete
+#[derive(Default)]
+struct Block {
+    /// The unfinished last line of the data received so far
+    partial: Vec<u8>,
+    /// The block of the held back records
+    number: Option<i64>,
+    records: Vec<u8>,
+}
+
+impl Block {
+    /// Returns the blocks that `data` completed, with their records
+    fn push(&mut self, data: &[u8]) -> Vec<(Option<i64>, Vec<u8>)> {
+        self.partial.extend_from_slice(data);
+        let Some(end) = self.partial.iter().rposition(|byte| *byte == b'\n') else {
+            return Vec::new();
+        };
+        let lines: Vec<u8> = self.partial.drain(..=end).collect();
+
+        let mut complete = Vec::new();
+        for line in lines.split_inclusive(|byte| *byte == b'\n') {
+            // records without a block belong to the block they follow
+            match (block_number(line), self.number) {
+                (Some(block), Some(number)) if block != number => {
+                    complete.push((Some(number), std::mem::take(&mut self.records)));
+                    self.number = Some(block);
+                }
+                (Some(block), None) => self.number = Some(block),
+                _ => {}
+            }
+            self.records.extend_from_slice(line);
+        }
+        complete
+    }
+
+    /// Returns the last block once the response ended, `None` if the response
+    /// had no records
+    fn finish(&mut self) -> Option<(Option<i64>, Vec<u8>)> {
+        let mut records = std::mem::take(&mut self.records);
+        records.append(&mut self.partial);
+        (!records.is_empty()).then_some((self.number, records))
+    }
+}
@@ -14,6 +14,52 @@ pub enum Error {
     UnexpectedClose,
     #[error("The server did not respond with a PONG in time")]
     PingTimeout,
+    /// No message arrived within the pong timeout after a ping, the connection
+    /// is dead without having been closed
+    #[error("The connection is stale, the server did not answer a ping in time")]
+    ConnectionStale,
+    /// The server did not start to respond to a request in time, see
+    /// [`WsConfig::handshake_timeout`](crate::WsConfig::handshake_timeout) and
+    /// [`HttpConfig::first_byte_timeout`](crate::HttpConfig::first_byte_timeout)
+    #[error("The server did not respond to the request within {0:?}")]
+    Timeout(std::time::Duration),
+    /// More requests would run on the connection at once than allowed, by
+    /// [`WsConfig::max_subscriptions`](crate::WsConfig::max_subscriptions) or
+    /// by the server if `limit` is `None`
+    #[error("Too many requests are running on the connection")]
+    TooManySubscriptions { limit: Option<usize> },
+    /// The stream did not keep up with the response, see
+    /// [`OverflowPolicy::Error`](crate::OverflowPolicy::Error)
+    #[error("The stream did not keep up with the response and its buffer overflowed")]
+    BufferOverflow,
+    /// No connection was established within
+    /// [`WsConfig::connect_timeout`](crate::WsConfig::connect_timeout) or
+    /// [`HttpConfig::connect_timeout`](crate::HttpConfig::connect_timeout)
+    #[error("The connection was not established within {0:?}")]
+    ConnectTimeout(std::time::Duration),
+    /// The server refused the credentials, with a 401 or 403 status over HTTP.
+    /// See [`WsConfig::max_auth_failures`](crate::WsConfig::max_auth_failures)
+    /// for WebSocket connections.
+    #[error("The server refused the credentials")]
+    Unauthorized,
+    /// The server refused the request with `429 Too Many Requests`, and asked to
+    /// wait for `retry_after` if it said how long
+    #[error("The server rate limited the request, retry after {retry_after:?}")]
+    RateLimited {
+        retry_after: Option<std::time::Duration>,
+    },
+    /// The server has no such endpoint, e.g. a path of
+    /// [`HttpProvider::raw_get`](crate::HttpProvider::raw_get) that is misspelled
+    #[error("The server has no such endpoint")]
+    NotFound,
+    /// The server answered with a non-2xx status and a `body` that is not a
+    /// [`ResponseError`], cut off after 64 KiB
+    #[error("Request failed with ({status}): {body}")]
+    Http { status: u16, body: String },
+    /// The server sent a message of `size` bytes over the `limit`, see
+    /// [`WsConfig::max_message_size`](crate::WsConfig::max_message_size)
+    #[error("The server sent a message of {size} bytes, over the limit of {limit} bytes")]
+    MessageTooLarge { size: usize, limit: usize },
     /// The server sent and unexpected WebSocket message
     /// This should usually not happen
     #[error("The server sent an unexpected message")]
@@ -43,25 +89,127 @@ pub enum Error {
     /// The server sent an error as part of the response
     #[error(transparent)]
     ErrorResponse(#[from] ResponseError),
+    /// The server does not support the requested format for the dataset
+    #[error("the dataset does not support the {} format: {reason}", .format.as_str())]
+    UnsupportedFormat {
+        format: crate::Format,
+        reason: String,
+    },
+    /// The server redirected the request to a host that is not trusted with
+    /// the credentials, or too often, see
+    /// [`RedirectPolicy`](crate::RedirectPolicy)
+    #[error("Refused to follow the redirect to {location}: {reason}")]
+    Redirect { location: String, reason: String },
+    /// The response did not change since the last identical request, see
+    /// [`HttpConfig::conditional_requests`](crate::HttpConfig::conditional_requests)
+    #[error("The response did not change since the last request")]
+    NotModified,
+    /// A request is bounded by both block and time on the same side, or a time
+    /// bound is malformed
+    #[error("Invalid bounds: {0}")]
+    InvalidBounds(String),
+    /// A request built with a builder such as
+    /// [`GetLogsRequest::builder`](crate::requests::logs::GetLogsRequest::builder)
+    /// is incomplete or contradicts itself
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    /// The server cannot do the operation for the dataset, e.g. count its
+    /// records
+    #[error("the server does not support {operation} for {dataset}")]
+    Unsupported {
+        dataset: &'static str,
+        operation: &'static str,
+    },
+    /// The server responded in another format than requested
+    #[error("expected a response of type {expected}, got {got}")]
+    UnexpectedContentType { expected: &'static str, got: String },
     /// The websocket connection was closed by the server
     #[error("The websocket connection was closed")]
     ConnectionClosed,
 
+    /// An error encountered during arrow decoding
+    #[error(transparent)]
+    Arrow(#[from] arrow::error::ArrowError),
+    /// No chunk of an HTTP response arrived within
+    /// [`HttpConfig::idle_timeout`](crate::HttpConfig::idle_timeout), reported
+    /// as the source of [`Error::StreamInterrupted`]
+    #[error("the response stalled, no data arrived within {0:?}")]
+    Stalled(std::time::Duration),
+    /// The response failed after `bytes` of it were delivered, which is not
+    /// retried as they would be delivered again
+    #[error("the response failed after {bytes} bytes: {source}")]
+    StreamInterrupted { bytes: u64, source: Box<Error> },
+    /// A certificate or key of the [`TlsConfig`](crate::TlsConfig) is invalid
+    #[error("invalid TLS configuration: {0}")]
+    Tls(String),
+    /// The proxy could not be reached or refused to open a tunnel
+    #[error("proxy error: {0}")]
+    Proxy(String),
+    /// A header of the configuration is malformed or would replace the
+    /// credentials
+    #[error("invalid header {name:?}: {reason}")]
+    InvalidHeader { name: String, reason: String },
+    /// A raw request path leaves the API, e.g. through a `..` segment
+    #[error("invalid request path {0:?}")]
+    InvalidPath(String),
+    /// A requested column is not part of the response
+    #[error("the response has no column `{0}`")]
+    MissingColumn(String),
+    /// A row of a record batch could not be converted into a record
+    #[error("failed to read row {row} of the record batch: {reason}")]
+    RecordBatchRow { row: usize, reason: String },
+    /// An error encountered during avro decoding
+    #[cfg(feature = "avro")]
+    #[error(transparent)]
+    Avro(#[from] apache_avro::Error),
+    /// The avro object container framing of a response is invalid
+    #[cfg(feature = "avro")]
+    #[error("malformed avro container: {0}")]
+    AvroContainer(Cow<'static, str>),
+    /// An error encountered during ClickHouse inserts
+    #[cfg(feature = "clickhouse")]
+    #[error(transparent)]
+    ClickHouse(#[from] clickhouse::error::Error),
+    /// A sink stopped before all records were committed, resume from `from_block`
+    /// to continue. `to_block` is the last block received, if any.
+    #[cfg(feature = "clickhouse")]
+    #[error("records from block {from_block:?} on were not committed: {source}")]
+    UncommittedBlocks {
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        source: Box<Error>,
+    },
     /// An error encountered during csv parsing
     #[error(transparent)]
     CsvAsync(#[from] csv_async::Error),
     /// An IO error
     #[error(transparent)]
     IO(#[from] std::io::Error),
+    /// An error encountered during parquet writing
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+    /// An error encountered during protobuf decoding
+    #[cfg(feature = "proto")]
+    #[error(transparent)]
+    Proto(#[from] prost::DecodeError),
+    /// A compressed HTTP response could not be decompressed
+    #[cfg(feature = "compression")]
+    #[error("failed to decompress the {encoding} response: {reason}")]
+    Decompression { encoding: String, reason: String },
+    /// The length delimited framing of a protobuf response is invalid
+    #[cfg(feature = "proto")]
+    #[error("malformed protobuf stream: {0}")]
+    ProtoFraming(Cow<'static, str>),
     /// An error encountered during making HTTP requests
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
     /// An error encountered during cbor parsing
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
-    /// An error encountered during websocket handling
+    /// An error encountered during websocket handling, boxed as it is large
     #[error(transparent)]
-    Tungstenite(#[from] tungstenite::Error),
+    Tungstenite(Box<tungstenite::Error>),
     /// An error encountered during url parsing
     #[error(transparent)]
     Url(#[from] url::ParseError),
@@ -86,6 +234,32 @@ pub enum Error {
 
     #[error("invalid chain id: {0:?}")]
     InvalidChainId(HashSet<ChainId>),
+
+    #[error("a line of the response exceeded the maximum size of {0} bytes")]
+    MaxLineSizeExceeded(usize),
+
+    /// A line of a JSON stream response is not valid JSON
+    #[error("malformed json line {line:?}: {source}")]
+    MalformedJsonLine {
+        line: String,
+        source: serde_json::Error,
+    },
+}
+
+impl From<tungstenite::Error> for Error {
+    fn from(err: tungstenite::Error) -> Self {
+        use tungstenite::error::CapacityError;
+
+        match err {
+            tungstenite::Error::Capacity(CapacityError::MessageTooLong { size, max_size }) => {
+                Error::MessageTooLarge {
+                    size,
+                    limit: max_size,
+                }
+            }
+            err => Error::Tungstenite(Box::new(err)),
+        }
+    }
 }
 
 /// An error that is returned by the server if something goes wrong
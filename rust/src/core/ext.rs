@@ -0,0 +1,90 @@
+//! Helpers to consume a whole [`StreamResponse`] at once
+
+use async_trait::async_trait;
+use futures::{StreamExt, TryStreamExt};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use super::{
+    error::{Error, ResponseError, Result},
+    provider::StreamResponse,
+};
+
+/// Buffers small responses, where streaming is not worth it
+#[async_trait]
+pub trait StreamResponseExt<T> {
+    /// Collects all records of the response
+    async fn collect_vec(self) -> Result<Vec<T>>;
+
+    /// Returns the first record and drops the rest of the response.
+    ///
+    /// Dropping the stream cancels the request, without affecting other
+    /// requests on a WebSocket connection.
+    async fn first(self) -> Result<Option<T>>;
+
+    /// Concatenates the chunks of a raw response
+    async fn collect_bytes(self) -> Result<Vec<u8>>
+    where
+        T: AsRef<[u8]>;
+
+    /// Concatenates the chunks of a raw response into a UTF-8 string
+    async fn collect_string(self) -> Result<String>
+    where
+        T: AsRef<[u8]>;
+
+    /// Writes the chunks of a raw response into `writer` and returns the number
+    /// of bytes written.
+    ///
+    /// A chunk is written before the next one is read, so a slow writer slows
+    /// down the response. The writer is flushed but not shut down. Dropping the
+    /// future cancels the request like [`first`](Self::first) does.
+    async fn copy_to<W>(self, writer: &mut W) -> Result<u64>
+    where
+        T: AsRef<[u8]>,
+        W: AsyncWrite + Unpin + Send + ?Sized;
+}
+
+#[async_trait]
+impl<T: Send + 'static> StreamResponseExt<T> for StreamResponse<T> {
+    async fn collect_vec(self) -> Result<Vec<T>> {
+        self?.try_collect().await
+    }
+
+    async fn first(self) -> Result<Option<T>> {
+        self?.next().await.transpose()
+    }
+
+    async fn collect_bytes(self) -> Result<Vec<u8>>
+    where
+        T: AsRef<[u8]>,
+    {
+        let chunks = self?.map_ok(|chunk| chunk.as_ref().to_vec()).boxed();
+        ResponseError::map_stream(chunks).try_concat().await
+    }
+
+    async fn collect_string(self) -> Result<String>
+    where
+        T: AsRef<[u8]>,
+    {
+        String::from_utf8(self.collect_bytes().await?)
+            .map_err(|_| Error::Custom("the response is not valid UTF-8".into()))
+    }
+
+    async fn copy_to<W>(self, writer: &mut W) -> Result<u64>
+    where
+        T: AsRef<[u8]>,
+        W: AsyncWrite + Unpin + Send + ?Sized,
+    {
+        let chunks = self?.map_ok(|chunk| chunk.as_ref().to_vec()).boxed();
+        let mut chunks = ResponseError::map_stream(chunks);
+
+        let mut written = 0;
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+}
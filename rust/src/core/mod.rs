@@ -1,7 +1,21 @@
+pub mod arrow;
+#[cfg(feature = "avro")]
+pub mod avro;
 pub mod builder;
 pub mod client;
+pub mod csv;
+#[cfg(feature = "datafusion")]
+pub mod datafusion;
+pub mod download;
 pub mod error;
+pub mod ext;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "proto")]
+pub mod proto;
 pub mod provider;
 pub mod requests;
+#[cfg(any(feature = "clickhouse", feature = "compression", feature = "parquet"))]
+pub mod sinks;
 pub mod types;
 pub mod utils;
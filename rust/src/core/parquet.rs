@@ -0,0 +1,21 @@
+//! Reading of [`Format::Parquet`](crate::Format::Parquet) responses
+
+use ::parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use bytes::Bytes;
+use futures::TryStreamExt;
+
+use super::{
+    error::{ResponseError, Result},
+    provider::StreamResponse,
+};
+
+/// Collects a Parquet response in memory and opens it.
+///
+/// Parquet keeps its metadata in the footer, so nothing can be read before the
+/// whole response arrived. Use [`sinks::write_parquet`](crate::sinks::write_parquet)
+/// for responses that do not fit in memory.
+pub async fn read_parquet(stream: StreamResponse<Vec<u8>>) -> Result<ParquetRecordBatchReader> {
+    let data = ResponseError::map_stream(stream?).try_concat().await?;
+
+    Ok(ParquetRecordBatchReaderBuilder::try_new(Bytes::from(data))?.build()?)
+}
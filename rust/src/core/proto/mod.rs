@@ -0,0 +1,154 @@
+//! Decoding of [`Format::Proto`](crate::Format::Proto) responses
+
+mod pangea;
+
+use std::collections::VecDeque;
+
+use futures::StreamExt;
+use prost::Message;
+
+pub use self::pangea::{Block, Log, Transfer};
+use super::{
+    error::{Error, ResponseError, Result},
+    provider::StreamResponse,
+    types::format::Format,
+};
+
+/// The longest varint, which prefixes every message with its length
+const MAX_VARINT_LEN: usize = 10;
+
+/// Decodes a response of length delimited protobuf messages into `T`.
+///
+/// The transport does not split the response on message boundaries, so messages
+/// are buffered until complete. If the server refuses protobuf for the dataset,
+/// or ignored the format and responded with JSON, [`Error::UnsupportedFormat`]
+/// is returned instead of a decode error.
+pub fn proto_records<T>(stream: StreamResponse<Vec<u8>>) -> StreamResponse<T>
+where
+    T: Message + Default + Send + 'static,
+{
+    struct State<T> {
+        buffer: Vec<u8>,
+        records: VecDeque<Result<T>>,
+        /// Whether a message was decoded, after which a JSON response is ruled out
+        decoded: bool,
+        done: bool,
+    }
+
+    impl<T: Message + Default> State<T> {
+        fn push_chunk(&mut self, chunk: &[u8]) {
+            self.buffer.extend_from_slice(chunk);
+            let mut consumed = 0;
+            while !self.done {
+                match next_message(&self.buffer[consumed..]) {
+                    Ok(Some((start, end))) => {
+                        let message = &self.buffer[consumed + start..consumed + end];
+                        match T::decode(message) {
+                            Ok(record) => {
+                                self.decoded = true;
+                                self.records.push_back(Ok(record));
+                            }
+                            Err(err) => self.fail(err.into()),
+                        }
+                        consumed += end;
+                    }
+                    Ok(None) => break,
+                    Err(err) => self.fail(err),
+                }
+            }
+            self.buffer.drain(..consumed);
+        }
+
+        fn finish(&mut self) {
+            if !self.done && !self.buffer.is_empty() {
+                self.fail(malformed("the response ends mid message"));
+            }
+            self.done = true;
+        }
+
+        fn fail(&mut self, err: Error) {
+            let err = if !self.decoded && self.buffer.starts_with(b"{\"") {
+                unsupported_format("the server responded with JSON".to_string())
+            } else {
+                err
+            };
+            self.done = true;
+            self.records.push_back(Err(err));
+        }
+    }
+
+    let stream = stream.map_err(refused)?;
+    let state = State::<T> {
+        buffer: Vec::new(),
+        records: VecDeque::new(),
+        decoded: false,
+        done: false,
+    };
+
+    let records = futures::stream::unfold(
+        (ResponseError::map_stream(stream), state),
+        |(mut stream, mut state)| async move {
+            loop {
+                if let Some(record) = state.records.pop_front() {
+                    return Some((record, (stream, state)));
+                }
+                if state.done {
+                    return None;
+                }
+                match stream.next().await {
+                    Some(Ok(chunk)) => state.push_chunk(&chunk),
+                    Some(Err(err)) => state.records.push_back(Err(refused(err))),
+                    None => state.finish(),
+                }
+            }
+        },
+    );
+
+    Ok(records.boxed())
+}
+
+/// Returns the bounds of the next message after its length prefix, or `None`
+/// if `bytes` do not hold the whole message yet
+fn next_message(bytes: &[u8]) -> Result<Option<(usize, usize)>> {
+    let mut len = 0u64;
+    for (i, byte) in bytes.iter().take(MAX_VARINT_LEN).enumerate() {
+        len |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            let start = i + 1;
+            let end = usize::try_from(len)
+                .ok()
+                .and_then(|len| start.checked_add(len))
+                .ok_or_else(|| malformed("a message length overflows"))?;
+            return Ok((end <= bytes.len()).then_some((start, end)));
+        }
+    }
+
+    if bytes.len() >= MAX_VARINT_LEN {
+        return Err(malformed("a length prefix is longer than 10 bytes"));
+    }
+    Ok(None)
+}
+
+/// Reports a server refusing the format as [`Error::UnsupportedFormat`]
+fn refused(err: Error) -> Error {
+    match err {
+        Error::UnexpectedContentType { got, .. } => {
+            unsupported_format(format!("the server responded with {got}"))
+        }
+        Error::ErrorResponse(err) if matches!(err.status, 400 | 406 | 415) => {
+            unsupported_format(err.error)
+        }
+        err => err,
+    }
+}
+
+fn unsupported_format(reason: String) -> Error {
+    Error::UnsupportedFormat {
+        format: Format::Proto,
+        reason,
+    }
+}
+
+fn malformed(reason: &'static str) -> Error {
+    Error::ProtoFraming(reason.into())
+}
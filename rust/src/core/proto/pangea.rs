@@ -0,0 +1,64 @@
+// Message types of `proto/pangea.proto`, written in the shape prost-build
+// generates, so the build does not depend on protoc. Keep both in sync.
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Block {
+    #[prost(uint64, tag = "1")]
+    pub chain: u64,
+    #[prost(uint64, tag = "2")]
+    pub block_number: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub hash: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub parent_hash: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub miner: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub gas_used: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "7")]
+    pub gas_limit: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", optional, tag = "8")]
+    pub base_fee_per_gas: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    #[prost(uint64, tag = "9")]
+    pub timestamp: u64,
+    #[prost(bytes = "vec", tag = "10")]
+    pub extra_data: ::prost::alloc::vec::Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Log {
+    #[prost(uint64, tag = "1")]
+    pub chain: u64,
+    #[prost(uint64, tag = "2")]
+    pub block_number: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "4")]
+    pub log_index: u64,
+    #[prost(bytes = "vec", tag = "5")]
+    pub address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "6")]
+    pub topics: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    #[prost(bytes = "vec", tag = "7")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bool, tag = "8")]
+    pub removed: bool,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Transfer {
+    #[prost(uint64, tag = "1")]
+    pub chain: u64,
+    #[prost(uint64, tag = "2")]
+    pub block_number: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub from: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub to: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub value: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag = "7")]
+    pub kind: ::prost::alloc::string::String,
+}
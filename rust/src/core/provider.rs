@@ -19,7 +19,7 @@ use super::{
 use crate::{
     requests::{
         curve::{GetCrvPoolRequest, GetCrvPriceRequest, GetCrvTokenRequest},
-        erc20::{GetErc20ApprovalsRequest, GetErc20Request, GetErc20TransferssRequest},
+        erc20::{GetErc20ApprovalsRequest, GetErc20Request, GetErc20TransfersRequest},
         fuel::{
             GetFuelBlocksRequest, GetFuelLogsRequest, GetFuelTxsRequest, GetSparkMarketRequest,
             GetSparkOrderRequest,
@@ -158,7 +158,7 @@ pub trait Erc20Provider {
 
     async fn get_erc20_transfers_by_format(
         &self,
-        request: GetErc20TransferssRequest,
+        request: GetErc20TransfersRequest,
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>>;
@@ -2,10 +2,14 @@ use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
+use super::HasBlockBounds;
 use crate::{
-    core::types::{default_chains, ChainId},
-    query::Bound,
-    utils::serialize_comma_separated,
+    core::{
+        error::{Error, Result},
+        types::{default_chains, ChainId},
+    },
+    query::{Bound, TimeBound},
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -14,6 +18,7 @@ pub struct GetBlocksRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -26,21 +31,103 @@ pub struct GetBlocksRequest {
     pub to_block: Bound,
 
     // Inclusive lower bound if is Some for block timestamp
-    #[serde(default)]
-    pub from_timestamp: Option<i64>,
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
     // Exclusive upper bound if is Some for block timestamp
-    #[serde(default)]
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
+    /// The former `from_time`, sent when it is set instead
+    #[deprecated(note = "use `from_time`")]
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub from_timestamp: Option<i64>,
+    /// The former `to_time`, sent when it is set instead
+    #[deprecated(note = "use `to_time`")]
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
     pub to_timestamp: Option<i64>,
+
+    /// The columns to return, see [`select_columns`](crate::arrow::select_columns)
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub columns: Vec<String>,
 }
 
 impl Default for GetBlocksRequest {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             from_timestamp: None,
             to_timestamp: None,
+            columns: Vec::new(),
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl HasBlockBounds for GetBlocksRequest {
+    fn block_bounds(&self) -> (Bound, Bound) {
+        (self.from_block, self.to_block)
+    }
+
+    fn set_from_block(&mut self, from_block: Bound) {
+        self.from_block = from_block;
+        self.from_time = None;
+        self.from_timestamp = None;
+    }
+
+    fn chains(&self) -> &HashSet<ChainId> {
+        &self.chains
+    }
+
+    fn time_bounds(&self) -> (Option<&TimeBound>, Option<&TimeBound>) {
+        (self.from_time.as_ref(), self.to_time.as_ref())
+    }
+
+    /// Fails with [`Error::InvalidBounds`] if a side of the range is bounded by
+    /// both a time and its deprecated timestamp, or by the timestamp and a block
+    fn check_filters(&self) -> Result<()> {
+        for (side, block, time, timestamp) in [
+            (
+                "from",
+                self.from_block,
+                &self.from_time,
+                self.from_timestamp,
+            ),
+            ("to", self.to_block, &self.to_time, self.to_timestamp),
+        ] {
+            if timestamp.is_none() {
+                continue;
+            }
+            if time.is_some() {
+                return Err(Error::InvalidBounds(format!(
+                    "{side}_time and {side}_timestamp are both set"
+                )));
+            }
+            if block != Bound::default() {
+                return Err(Error::InvalidBounds(format!(
+                    "{side}_block and {side}_timestamp are both set"
+                )));
+            }
         }
+        Ok(())
     }
 }
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     core::types::{default_chains, ChainId},
     query::Bound,
-    utils::serialize_comma_separated,
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -14,6 +14,7 @@ pub struct GetBtcBlocksRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -42,6 +43,7 @@ pub struct GetBtcTxsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     core::types::{default_chains, ChainId},
     query::Bound,
-    utils::serialize_comma_separated,
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -15,6 +15,7 @@ pub struct GetCrvTokenRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -29,6 +30,7 @@ pub struct GetCrvTokenRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub address__in: HashSet<Address>,
@@ -36,6 +38,7 @@ pub struct GetCrvTokenRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub symbol__in: HashSet<String>,
@@ -43,6 +46,7 @@ pub struct GetCrvTokenRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub name__in: HashSet<String>,
@@ -50,6 +54,7 @@ pub struct GetCrvTokenRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pool_address__in: HashSet<Address>,
@@ -83,6 +88,7 @@ pub struct GetCrvPoolRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -97,6 +103,7 @@ pub struct GetCrvPoolRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pool_address__in: HashSet<Address>,
@@ -104,6 +111,7 @@ pub struct GetCrvPoolRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token__in: HashSet<Address>,
@@ -111,6 +119,7 @@ pub struct GetCrvPoolRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub owner__in: HashSet<Address>,
@@ -118,6 +127,7 @@ pub struct GetCrvPoolRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub base_pool__in: HashSet<Address>,
@@ -125,6 +135,7 @@ pub struct GetCrvPoolRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub coins__in: HashSet<Address>,
@@ -132,6 +143,7 @@ pub struct GetCrvPoolRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub base_coins__in: HashSet<Address>,
@@ -215,6 +227,7 @@ pub struct GetCrvPriceRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -229,6 +242,7 @@ pub struct GetCrvPriceRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pool_address__in: HashSet<Address>,
@@ -236,6 +250,7 @@ pub struct GetCrvPriceRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub buyer__in: HashSet<Address>,
@@ -243,6 +258,7 @@ pub struct GetCrvPriceRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub tokens_address__in: HashSet<Address>,
@@ -250,13 +266,16 @@ pub struct GetCrvPriceRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub tokens_symbol__in: HashSet<String>,
 
-    #[serde(default)]
+    /// The tokens sold to the pool, the `token_in` of the prices
+    #[serde(default, alias = "token_in__in")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub sold_address__in: HashSet<Address>,
@@ -264,6 +283,7 @@ pub struct GetCrvPriceRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub sold_symbol__in: HashSet<String>,
@@ -274,9 +294,11 @@ pub struct GetCrvPriceRequest {
     #[serde(default)]
     pub sold_decimals__lte: Option<u8>,
 
-    #[serde(default)]
+    /// The tokens bought from the pool, the `token_out` of the prices
+    #[serde(default, alias = "token_out__in")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub bought_address__in: HashSet<Address>,
@@ -284,6 +306,7 @@ pub struct GetCrvPriceRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub bought_symbol__in: HashSet<String>,
@@ -340,3 +363,24 @@ impl Default for GetCrvPriceRequest {
         }
     }
 }
+
+impl GetCrvPriceRequest {
+    /// Filters by the prices between `token_a` and `token_b`, in both
+    /// directions as Curve prices a swap in the direction it went.
+    ///
+    /// The tokens are set as both [`sold_address__in`](Self::sold_address__in)
+    /// and [`bought_address__in`](Self::bought_address__in). Fails with
+    /// [`Error::InvalidRequest`](crate::Error::InvalidRequest) if either is set
+    /// already, e.g. by another pair, as it would also match swaps between a
+    /// token of each pair.
+    pub fn pair(mut self, token_a: Address, token_b: Address) -> crate::Result<Self> {
+        if !self.sold_address__in.is_empty() || !self.bought_address__in.is_empty() {
+            return Err(crate::Error::InvalidRequest(
+                "a pair cannot be added to other token filters".into(),
+            ));
+        }
+        self.sold_address__in.extend([token_a, token_b]);
+        self.bought_address__in.extend([token_a, token_b]);
+        Ok(self)
+    }
+}
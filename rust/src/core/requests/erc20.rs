@@ -1,12 +1,18 @@
 use std::collections::HashSet;
 
-use ethers_core::types::Address;
+use ethers_core::types::{Address, U256};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    core::types::{default_chains, ChainId},
-    query::Bound,
-    utils::serialize_comma_separated,
+    core::{
+        error::{Error, Result},
+        types::{default_chains, ChainId},
+    },
+    query::{Bound, SortOrder, TimeBound},
+    utils::{
+        deserialize_comma_separated, deserialize_optional_u256, serialize_comma_separated,
+        serialize_optional_decimal,
+    },
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -15,6 +21,7 @@ pub struct GetErc20Request {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -26,9 +33,27 @@ pub struct GetErc20Request {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub address__in: HashSet<Address>,
@@ -36,6 +61,7 @@ pub struct GetErc20Request {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub symbol__in: HashSet<String>,
@@ -43,6 +69,7 @@ pub struct GetErc20Request {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub name__in: HashSet<String>,
@@ -60,6 +87,8 @@ impl Default for GetErc20Request {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             address__in: HashSet::new(),
             symbol__in: HashSet::new(),
             name__in: HashSet::new(),
@@ -75,6 +104,7 @@ pub struct GetErc20ApprovalsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -86,9 +116,27 @@ pub struct GetErc20ApprovalsRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub address__in: HashSet<Address>,
@@ -96,6 +144,7 @@ pub struct GetErc20ApprovalsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub symbol__in: HashSet<String>,
@@ -103,6 +152,7 @@ pub struct GetErc20ApprovalsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub name__in: HashSet<String>,
@@ -116,6 +166,7 @@ pub struct GetErc20ApprovalsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub owner__in: HashSet<Address>,
@@ -123,6 +174,7 @@ pub struct GetErc20ApprovalsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub spender__in: HashSet<Address>,
@@ -140,6 +192,8 @@ impl Default for GetErc20ApprovalsRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             symbol__in: HashSet::new(),
             name__in: HashSet::new(),
             decimals__gte: None,
@@ -155,10 +209,11 @@ impl Default for GetErc20ApprovalsRequest {
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
 #[allow(non_snake_case)]
-pub struct GetErc20TransferssRequest {
+pub struct GetErc20TransfersRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -170,9 +225,27 @@ pub struct GetErc20TransferssRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub address__in: HashSet<Address>,
@@ -180,6 +253,7 @@ pub struct GetErc20TransferssRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub symbol__in: HashSet<String>,
@@ -187,6 +261,7 @@ pub struct GetErc20TransferssRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub name__in: HashSet<String>,
@@ -200,6 +275,7 @@ pub struct GetErc20TransferssRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub from__in: HashSet<Address>,
@@ -207,6 +283,7 @@ pub struct GetErc20TransferssRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub to__in: HashSet<Address>,
@@ -216,14 +293,52 @@ pub struct GetErc20TransferssRequest {
 
     #[serde(default)]
     pub value__gte: Option<f64>,
+
+    /// The smallest raw amount of the transfers, before applying the decimals
+    /// of the token
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_decimal",
+        deserialize_with = "deserialize_optional_u256",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub amount__gte: Option<U256>,
+
+    /// The largest raw amount of the transfers, before applying the decimals
+    /// of the token
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_decimal",
+        deserialize_with = "deserialize_optional_u256",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub amount__lte: Option<U256>,
+
+    /// The most records to return, all of them if `None`. The typed methods
+    /// of [`Client`](crate::Client) stop after this many records even if the
+    /// server ignores it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+
+    /// The order of the records by block, ascending if `None`. Resumed
+    /// WebSocket streams continue from the last block received, so only
+    /// ascending streams are resumed correctly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
 }
 
-impl Default for GetErc20TransferssRequest {
+/// The former name of [`GetErc20TransfersRequest`]
+#[deprecated(note = "renamed to `GetErc20TransfersRequest`")]
+pub type GetErc20TransferssRequest = GetErc20TransfersRequest;
+
+impl Default for GetErc20TransfersRequest {
     fn default() -> Self {
         Self {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             address__in: HashSet::new(),
             value__lte: None,
             value__gte: None,
@@ -233,6 +348,23 @@ impl Default for GetErc20TransferssRequest {
             decimals__lte: None,
             from__in: HashSet::new(),
             to__in: HashSet::new(),
+            amount__gte: None,
+            amount__lte: None,
+            limit: None,
+            order: None,
+        }
+    }
+}
+
+impl GetErc20TransfersRequest {
+    /// Fails with [`Error::InvalidRequest`] if the amount range is empty, for
+    /// [`HasBlockBounds::check_filters`](super::HasBlockBounds::check_filters)
+    pub(super) fn check_amounts(&self) -> Result<()> {
+        match (self.amount__gte, self.amount__lte) {
+            (Some(gte), Some(lte)) if gte > lte => Err(Error::InvalidRequest(format!(
+                "amount__gte {gte} is above amount__lte {lte}"
+            ))),
+            _ => Ok(()),
         }
     }
 }
@@ -2,16 +2,20 @@ use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
+use super::HasBlockBounds;
 use crate::{
-    core::types::{
-        fuel::{
-            LimitType, MarketEventType, MessageType, OrderEventType, OrderType, ReceiptType,
-            TransactionType,
+    core::{
+        error::{Error, Result},
+        types::{
+            fuel::{
+                LimitType, MarketEventType, MessageType, OrderEventType, OrderType, ReceiptType,
+                TransactionType,
+            },
+            ChainId,
         },
-        ChainId,
     },
     query::Bound,
-    utils::serialize_comma_separated,
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 
 use fuel_core_types::fuel_types::Address;
@@ -22,6 +26,7 @@ pub struct GetFuelBlocksRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -60,6 +65,7 @@ pub struct GetFuelLogsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -71,23 +77,30 @@ pub struct GetFuelLogsRequest {
     #[serde(default)]
     pub to_block: Bound,
 
-    #[serde(default)]
+    /// The contracts that emitted the logs
+    #[serde(default, alias = "contract_id__in")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub id__in: HashSet<Address>,
 
+    /// The `ra` register of the logs, which Sway's `log` and `logd` set to
+    /// the log id of the event type
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub ra__in: HashSet<u64>,
 
+    /// The `rb` register of the logs
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub rb__in: HashSet<u64>,
@@ -112,6 +125,7 @@ pub struct GetFuelTxsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -126,6 +140,7 @@ pub struct GetFuelTxsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub transaction_type__in: HashSet<TransactionType>,
@@ -133,6 +148,7 @@ pub struct GetFuelTxsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub metadata_contract_id__in: HashSet<Address>,
@@ -140,6 +156,7 @@ pub struct GetFuelTxsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub input_contract_contract_id__in: HashSet<Address>,
@@ -147,6 +164,7 @@ pub struct GetFuelTxsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub mint_asset_id__in: HashSet<Address>,
@@ -180,6 +198,7 @@ pub struct GetFuelReceiptsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -194,6 +213,7 @@ pub struct GetFuelReceiptsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub receipt_type__in: HashSet<ReceiptType>,
@@ -216,6 +236,7 @@ pub struct GetFuelMessagesRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -236,6 +257,7 @@ pub struct GetFuelMessagesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub sender__in: HashSet<Address>,
@@ -243,6 +265,7 @@ pub struct GetFuelMessagesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub recipient__in: HashSet<Address>,
@@ -256,6 +279,7 @@ pub struct GetFuelMessagesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub message_type__in: HashSet<MessageType>,
@@ -284,6 +308,7 @@ pub struct GetSparkMarketRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -295,21 +320,25 @@ pub struct GetSparkMarketRequest {
 
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub event_type__in: HashSet<MarketEventType>,
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub base_asset__in: HashSet<Address>,
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub quote_asset__in: HashSet<Address>,
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub market_id__in: HashSet<Address>,
@@ -335,6 +364,7 @@ pub struct GetSparkOrderRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -349,6 +379,7 @@ pub struct GetSparkOrderRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub order_id__in: HashSet<Address>,
@@ -356,40 +387,47 @@ pub struct GetSparkOrderRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub order_type__in: HashSet<OrderType>,
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub event_type__in: HashSet<OrderEventType>,
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub limit_type__in: HashSet<LimitType>,
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub user__in: HashSet<Address>,
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub asset__in: HashSet<Address>,
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub market_id__in: HashSet<Address>,
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub address__in: HashSet<Address>,
@@ -413,12 +451,47 @@ impl Default for GetSparkOrderRequest {
     }
 }
 
+impl HasBlockBounds for GetSparkOrderRequest {
+    fn block_bounds(&self) -> (Bound, Bound) {
+        (self.from_block, self.to_block)
+    }
+
+    fn set_from_block(&mut self, from_block: Bound) {
+        self.from_block = from_block;
+    }
+
+    fn chains(&self) -> &HashSet<ChainId> {
+        &self.chains
+    }
+
+    fn requires_chains(&self) -> bool {
+        false
+    }
+
+    /// Fails with [`Error::InvalidRequest`] if a filter has an `Unknown` type,
+    /// which only stands for the types of newer indexers in responses
+    fn check_filters(&self) -> Result<()> {
+        if self.order_type__in.contains(&OrderType::Unknown) {
+            return Err(Error::InvalidRequest(
+                "order_type__in has the unknown order type".into(),
+            ));
+        }
+        if self.event_type__in.contains(&OrderEventType::Unknown) {
+            return Err(Error::InvalidRequest(
+                "event_type__in has the unknown event type".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug)]
 #[allow(non_snake_case)]
 pub struct GetUtxoRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -436,6 +509,7 @@ pub struct GetUtxoRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub address__in: HashSet<Address>,
@@ -459,6 +533,7 @@ pub struct GetSrc20 {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -473,6 +548,7 @@ pub struct GetSrc20 {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub contract_id__in: HashSet<Address>,
@@ -480,6 +556,7 @@ pub struct GetSrc20 {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub asset_id__in: HashSet<Address>,
@@ -487,6 +564,7 @@ pub struct GetSrc20 {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub symbol__in: HashSet<String>,
@@ -494,6 +572,7 @@ pub struct GetSrc20 {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub name__in: HashSet<String>,
@@ -527,6 +606,7 @@ pub struct GetSrc7 {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -541,6 +621,7 @@ pub struct GetSrc7 {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub asset__in: HashSet<Address>,
@@ -548,6 +629,7 @@ pub struct GetSrc7 {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub key__in: HashSet<String>,
@@ -555,6 +637,7 @@ pub struct GetSrc7 {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub sender__in: HashSet<Address>,
@@ -1,12 +1,19 @@
 use std::collections::HashSet;
 
-use ethers_core::types::{Address, H256};
+use ethers_core::{
+    types::{Address, H256},
+    utils::keccak256,
+};
 use serde::{Deserialize, Serialize};
 
+use super::HasBlockBounds;
 use crate::{
-    core::types::{default_chains, ChainId},
-    query::Bound,
-    utils::serialize_comma_separated,
+    core::{
+        error::Result,
+        types::{default_chains, ChainId},
+    },
+    query::{Bound, SortOrder, TimeBound},
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -15,6 +22,7 @@ pub struct GetLogsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -26,9 +34,27 @@ pub struct GetLogsRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub address__in: HashSet<Address>,
@@ -36,27 +62,52 @@ pub struct GetLogsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub topic0__in: HashSet<H256>,
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub topic1__in: HashSet<H256>,
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub topic2__in: HashSet<H256>,
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub topic3__in: HashSet<H256>,
+
+    /// The columns to return, see [`select_columns`](crate::arrow::select_columns)
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub columns: Vec<String>,
+
+    /// The most records to return, all of them if `None`. The typed methods
+    /// of [`Client`](crate::Client) stop after this many records even if the
+    /// server ignores it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+
+    /// The order of the records by block, ascending if `None`. Resumed
+    /// WebSocket streams continue from the last block received, so only
+    /// ascending streams are resumed correctly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
 }
 
 impl Default for GetLogsRequest {
@@ -65,11 +116,165 @@ impl Default for GetLogsRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             address__in: HashSet::new(),
             topic0__in: HashSet::new(),
             topic1__in: HashSet::new(),
             topic2__in: HashSet::new(),
             topic3__in: HashSet::new(),
+            columns: Vec::new(),
+            limit: None,
+            order: None,
+        }
+    }
+}
+
+impl GetLogsRequest {
+    /// A request for the logs of an event, by the keccak256 hash of its
+    /// `signature` as topic0, on the default chains.
+    ///
+    /// ```
+    /// use pangea_client::{requests::logs::GetLogsRequest, Address};
+    /// use ethers_core::types::H256;
+    ///
+    /// // transfers to an address, which is topic2 of the event
+    /// let mut request = GetLogsRequest::event("Transfer(address,address,uint256)");
+    /// request.topic2__in.insert(H256::from(Address::repeat_byte(1)));
+    /// ```
+    pub fn event(signature: &str) -> Self {
+        Self {
+            topic0__in: HashSet::from([H256(keccak256(signature))]),
+            ..Default::default()
         }
     }
+
+    /// A builder that checks the request before it is sent. Unlike
+    /// [`GetLogsRequest::default`] it starts without chains.
+    ///
+    /// ```
+    /// use pangea_client::{requests::logs::GetLogsRequest, query::Bound, ChainId};
+    ///
+    /// let request = GetLogsRequest::builder()
+    ///     .chain(ChainId::ETH)
+    ///     .from_block(Bound::Exact(19_000_000))
+    ///     .to_block(Bound::Exact(19_001_000))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> GetLogsRequestBuilder {
+        GetLogsRequestBuilder::default()
+    }
+}
+
+/// A builder for [`GetLogsRequest`], see [`GetLogsRequest::builder`]
+#[derive(Clone, Debug)]
+pub struct GetLogsRequestBuilder {
+    request: GetLogsRequest,
+}
+
+impl Default for GetLogsRequestBuilder {
+    fn default() -> Self {
+        Self {
+            request: GetLogsRequest {
+                chains: HashSet::new(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl GetLogsRequestBuilder {
+    /// Adds a chain to request the logs of
+    pub fn chain(mut self, chain: ChainId) -> Self {
+        self.request.chains.insert(chain);
+        self
+    }
+
+    /// Adds chains to request the logs of
+    pub fn chains(mut self, chains: impl IntoIterator<Item = ChainId>) -> Self {
+        self.request.chains.extend(chains);
+        self
+    }
+
+    /// Adds a contract address the logs are emitted by
+    pub fn address(mut self, address: Address) -> Self {
+        self.request.address__in.insert(address);
+        self
+    }
+
+    /// Adds contract addresses the logs are emitted by
+    pub fn addresses(mut self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        self.request.address__in.extend(addresses);
+        self
+    }
+
+    /// Adds a first topic, e.g. an event signature, the logs match
+    pub fn topic0(mut self, topic: H256) -> Self {
+        self.request.topic0__in.insert(topic);
+        self
+    }
+
+    /// Adds a second topic, e.g. an indexed event argument, the logs match
+    pub fn topic1(mut self, topic: H256) -> Self {
+        self.request.topic1__in.insert(topic);
+        self
+    }
+
+    /// Adds a third topic, e.g. an indexed event argument, the logs match
+    pub fn topic2(mut self, topic: H256) -> Self {
+        self.request.topic2__in.insert(topic);
+        self
+    }
+
+    /// Adds a fourth topic, e.g. an indexed event argument, the logs match
+    pub fn topic3(mut self, topic: H256) -> Self {
+        self.request.topic3__in.insert(topic);
+        self
+    }
+
+    /// The first block of the range (inclusive)
+    pub fn from_block(mut self, from_block: Bound) -> Self {
+        self.request.from_block = from_block;
+        self
+    }
+
+    /// The end of the range (exclusive)
+    pub fn to_block(mut self, to_block: Bound) -> Self {
+        self.request.to_block = to_block;
+        self
+    }
+
+    /// The first block timestamp of the range (inclusive)
+    pub fn from_time(mut self, from_time: impl Into<TimeBound>) -> Self {
+        self.request.from_time = Some(from_time.into());
+        self
+    }
+
+    /// The end of the range by block timestamp (exclusive)
+    pub fn to_time(mut self, to_time: impl Into<TimeBound>) -> Self {
+        self.request.to_time = Some(to_time.into());
+        self
+    }
+
+    /// The most logs to return
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.request.limit = Some(limit);
+        self
+    }
+
+    /// The order of the logs by block
+    pub fn order(mut self, order: SortOrder) -> Self {
+        self.request.order = Some(order);
+        self
+    }
+
+    /// The request, or [`Error::InvalidRequest`](crate::Error::InvalidRequest)
+    /// if it has no chains or its range ends before it starts, and
+    /// [`Error::InvalidBounds`](crate::Error::InvalidBounds) if its bounds
+    /// contradict each other
+    pub fn build(self) -> Result<GetLogsRequest> {
+        self.request.validate()?;
+        Ok(self.request)
+    }
 }
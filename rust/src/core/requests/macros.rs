@@ -0,0 +1,220 @@
+/// Defines a request over a range of blocks and time from the fields specific
+/// to its dataset, all of them sets of values to filter by.
+///
+/// The request gets the common `chains`, `from_block`, `to_block`,
+/// `from_time`, `to_time`, `limit` and `order` fields, a [`Default`] with the
+/// default chains,
+/// [`HasBlockBounds`](super::HasBlockBounds), and a builder with a setter
+/// per field that is checked by
+/// [`HasBlockBounds::validate`](super::HasBlockBounds::validate).
+macro_rules! block_range_request {
+    (
+        $(#[$meta:meta])*
+        pub struct $request:ident, $builder:ident {
+            $(
+                $(#[$field_meta:meta])*
+                pub $field:ident: HashSet<$item:ty>,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, serde::Deserialize, serde::Serialize, Debug)]
+        #[allow(non_snake_case)]
+        pub struct $request {
+            #[serde(default = "crate::core::types::default_chains")]
+            #[serde(
+                serialize_with = "crate::utils::serialize_comma_separated",
+                deserialize_with = "crate::utils::deserialize_comma_separated",
+                skip_serializing_if = "std::collections::HashSet::is_empty"
+            )]
+            pub chains: std::collections::HashSet<crate::ChainId>,
+
+            /// The first block of the range (inclusive)
+            #[serde(default)]
+            pub from_block: crate::query::Bound,
+            /// The end of the range (exclusive)
+            #[serde(default)]
+            pub to_block: crate::query::Bound,
+
+            /// The first block timestamp of the range (inclusive)
+            #[serde(
+                default,
+                rename = "from_timestamp",
+                alias = "from_time",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub from_time: Option<crate::query::TimeBound>,
+            /// The end of the range by block timestamp (exclusive)
+            #[serde(
+                default,
+                rename = "to_timestamp",
+                alias = "to_time",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub to_time: Option<crate::query::TimeBound>,
+
+            $(
+                $(#[$field_meta])*
+                #[serde(
+                    default,
+                    serialize_with = "crate::utils::serialize_comma_separated",
+                    deserialize_with = "crate::utils::deserialize_comma_separated"
+                )]
+                pub $field: std::collections::HashSet<$item>,
+            )*
+
+            /// The most records to return, all of them if `None`. The typed
+            /// methods of [`Client`](crate::Client) stop after this many
+            /// records even if the server ignores it.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub limit: Option<u64>,
+
+            /// The order of the records by block, ascending if `None`
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub order: Option<crate::query::SortOrder>,
+        }
+
+        impl Default for $request {
+            fn default() -> Self {
+                Self {
+                    chains: crate::core::types::default_chains(),
+                    from_block: Default::default(),
+                    to_block: Default::default(),
+                    from_time: None,
+                    to_time: None,
+                    $($field: Default::default(),)*
+                    limit: None,
+                    order: None,
+                }
+            }
+        }
+
+        impl crate::core::requests::HasBlockBounds for $request {
+            fn block_bounds(&self) -> (crate::query::Bound, crate::query::Bound) {
+                (self.from_block, self.to_block)
+            }
+
+            fn set_from_block(&mut self, from_block: crate::query::Bound) {
+                self.from_block = from_block;
+                self.from_time = None;
+            }
+
+            fn chains(&self) -> &std::collections::HashSet<crate::ChainId> {
+                &self.chains
+            }
+
+            fn requires_chains(&self) -> bool {
+                false
+            }
+
+            fn time_bounds(
+                &self,
+            ) -> (
+                Option<&crate::query::TimeBound>,
+                Option<&crate::query::TimeBound>,
+            ) {
+                (self.from_time.as_ref(), self.to_time.as_ref())
+            }
+
+            fn order(&self) -> Option<crate::query::SortOrder> {
+                self.order
+            }
+
+            fn limit(&self) -> Option<u64> {
+                self.limit
+            }
+
+            fn set_limit(&mut self, limit: Option<u64>) {
+                self.limit = limit;
+            }
+        }
+
+        impl $request {
+            /// A builder that checks the request before it is sent. Unlike
+            #[doc = concat!("[`", stringify!($request), "::default`]")]
+            /// it starts without chains.
+            pub fn builder() -> $builder {
+                $builder {
+                    request: $request {
+                        chains: Default::default(),
+                        ..Default::default()
+                    },
+                }
+            }
+        }
+
+        #[doc = concat!("A builder for [`", stringify!($request), "`], see [`", stringify!($request), "::builder`]")]
+        #[derive(Clone, Debug)]
+        pub struct $builder {
+            request: $request,
+        }
+
+        #[allow(non_snake_case)]
+        impl $builder {
+            /// Adds a chain to request the records of
+            pub fn chain(mut self, chain: crate::ChainId) -> Self {
+                self.request.chains.insert(chain);
+                self
+            }
+
+            /// Adds chains to request the records of
+            pub fn chains(mut self, chains: impl IntoIterator<Item = crate::ChainId>) -> Self {
+                self.request.chains.extend(chains);
+                self
+            }
+
+            /// The first block of the range (inclusive)
+            pub fn from_block(mut self, from_block: crate::query::Bound) -> Self {
+                self.request.from_block = from_block;
+                self
+            }
+
+            /// The end of the range (exclusive)
+            pub fn to_block(mut self, to_block: crate::query::Bound) -> Self {
+                self.request.to_block = to_block;
+                self
+            }
+
+            /// The first block timestamp of the range (inclusive)
+            pub fn from_time(mut self, from_time: impl Into<crate::query::TimeBound>) -> Self {
+                self.request.from_time = Some(from_time.into());
+                self
+            }
+
+            /// The end of the range by block timestamp (exclusive)
+            pub fn to_time(mut self, to_time: impl Into<crate::query::TimeBound>) -> Self {
+                self.request.to_time = Some(to_time.into());
+                self
+            }
+
+            /// The most records to return
+            pub fn limit(mut self, limit: u64) -> Self {
+                self.request.limit = Some(limit);
+                self
+            }
+
+            /// The order of the records by block
+            pub fn order(mut self, order: crate::query::SortOrder) -> Self {
+                self.request.order = Some(order);
+                self
+            }
+
+            $(
+                #[doc = concat!("Adds values to `", stringify!($field), "`")]
+                pub fn $field(mut self, values: impl IntoIterator<Item = $item>) -> Self {
+                    self.request.$field.extend(values);
+                    self
+                }
+            )*
+
+            /// The request, or [`Error::InvalidRequest`](crate::Error::InvalidRequest)
+            /// if its range ends before it starts, and
+            /// [`Error::InvalidBounds`](crate::Error::InvalidBounds) if its
+            /// bounds contradict each other
+            pub fn build(self) -> crate::Result<$request> {
+                crate::core::requests::HasBlockBounds::validate(&self.request)?;
+                Ok(self.request)
+            }
+        }
+    };
+}
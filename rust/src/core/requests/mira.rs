@@ -1,104 +1,75 @@
-use std::collections::HashSet;
-
-use serde::{Deserialize, Serialize};
-
 use ethers_core::types::H256;
 
-use crate::{
-    core::types::{default_chains, ChainId},
-    query::Bound,
-    utils::serialize_comma_separated,
-};
-
-#[derive(Clone, Deserialize, Serialize, Debug)]
-#[allow(non_snake_case)]
-pub struct GetMiraPoolsRequest {
-    #[serde(default = "default_chains")]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub chains: HashSet<ChainId>,
-
-    // Inclusive lower bound if is Some for block number
-    #[serde(default)]
-    pub from_block: Bound,
-    // Inclusive upper bound if is Some for block number
-    #[serde(default)]
-    pub to_block: Bound,
-
-    #[serde(default)]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub pool_address__in: HashSet<H256>,
+block_range_request! {
+    pub struct GetMiraPoolsRequest, GetMiraPoolsRequestBuilder {
+        pub pool_address__in: HashSet<H256>,
 
-    #[serde(default)]
-    #[serde(alias = "asset0__in", serialize_with = "serialize_comma_separated")]
-    pub asset0_address__in: HashSet<H256>,
+        #[serde(alias = "asset0__in")]
+        pub asset0_address__in: HashSet<H256>,
 
-    #[serde(default)]
-    #[serde(alias = "asset1__in", serialize_with = "serialize_comma_separated")]
-    pub asset1_address__in: HashSet<H256>,
+        #[serde(alias = "asset1__in")]
+        pub asset1_address__in: HashSet<H256>,
 
-    #[serde(default)]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub assets__in: HashSet<H256>,
+        pub assets__in: HashSet<H256>,
+    }
 }
 
-#[derive(Clone, Deserialize, Serialize, Debug)]
-#[allow(non_snake_case)]
-pub struct GetMiraLiquidityRequest {
-    #[serde(default = "default_chains")]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub chains: HashSet<ChainId>,
+block_range_request! {
+    pub struct GetMiraLiquidityRequest, GetMiraLiquidityRequestBuilder {
+        pub pool_address__in: HashSet<H256>,
 
-    // Inclusive lower bound if is Some for block number
-    #[serde(default)]
-    pub from_block: Bound,
-    // Inclusive upper bound if is Some for block number
-    #[serde(default)]
-    pub to_block: Bound,
+        #[serde(alias = "asset0__in")]
+        pub asset0_address__in: HashSet<H256>,
 
-    #[serde(default)]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub pool_address__in: HashSet<H256>,
+        #[serde(alias = "asset1__in")]
+        pub asset1_address__in: HashSet<H256>,
 
-    #[serde(default)]
-    #[serde(alias = "asset0__in", serialize_with = "serialize_comma_separated")]
-    pub asset0_address__in: HashSet<H256>,
-
-    #[serde(default)]
-    #[serde(alias = "asset1__in", serialize_with = "serialize_comma_separated")]
-    pub asset1_address__in: HashSet<H256>,
-
-    #[serde(default)]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub assets__in: HashSet<H256>,
+        pub assets__in: HashSet<H256>,
+    }
 }
 
-#[derive(Clone, Deserialize, Serialize, Debug)]
-#[allow(non_snake_case)]
-pub struct GetMiraSwapsRequest {
-    #[serde(default = "default_chains")]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub chains: HashSet<ChainId>,
-
-    // Inclusive lower bound if is Some for block number
-    #[serde(default)]
-    pub from_block: Bound,
-    // Inclusive upper bound if is Some for block number
-    #[serde(default)]
-    pub to_block: Bound,
+block_range_request! {
+    pub struct GetMiraSwapsRequest, GetMiraSwapsRequestBuilder {
+        pub pool_address__in: HashSet<H256>,
 
-    #[serde(default)]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub pool_address__in: HashSet<H256>,
+        #[serde(alias = "asset0__in")]
+        pub asset0_address__in: HashSet<H256>,
 
-    #[serde(default)]
-    #[serde(alias = "asset0__in", serialize_with = "serialize_comma_separated")]
-    pub asset0_address__in: HashSet<H256>,
+        #[serde(alias = "asset1__in")]
+        pub asset1_address__in: HashSet<H256>,
 
-    #[serde(default)]
-    #[serde(alias = "asset1__in", serialize_with = "serialize_comma_separated")]
-    pub asset1_address__in: HashSet<H256>,
+        pub assets__in: HashSet<H256>,
+    }
+}
 
-    #[serde(default)]
-    #[serde(serialize_with = "serialize_comma_separated")]
-    pub assets__in: HashSet<H256>,
+macro_rules! impl_pair {
+    ($($request:ty),* $(,)?) => {
+        $(
+            impl $request {
+                /// Filters by the pools of `asset_a` and `asset_b` in either
+                /// order, by setting both as `asset0_address__in` and
+                /// `asset1_address__in`.
+                ///
+                /// Fails with [`Error::InvalidRequest`](crate::Error::InvalidRequest)
+                /// if either filter is set already, e.g. by another pair, as
+                /// it would also match pools of an asset of each pair.
+                pub fn pair(mut self, asset_a: H256, asset_b: H256) -> crate::Result<Self> {
+                    if !self.asset0_address__in.is_empty() || !self.asset1_address__in.is_empty() {
+                        return Err(crate::Error::InvalidRequest(
+                            "a pair cannot be added to other asset filters".into(),
+                        ));
+                    }
+                    self.asset0_address__in.extend([asset_a, asset_b]);
+                    self.asset1_address__in.extend([asset_a, asset_b]);
+                    Ok(self)
+                }
+            }
+        )*
+    };
 }
+
+impl_pair!(
+    GetMiraPoolsRequest,
+    GetMiraLiquidityRequest,
+    GetMiraSwapsRequest
+);
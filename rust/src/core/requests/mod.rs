@@ -1,3 +1,6 @@
+#[macro_use]
+mod macros;
+
 pub mod blocks;
 pub mod btc;
 pub mod curve;
@@ -9,3 +12,272 @@ pub mod transfers;
 pub mod txs;
 pub mod uniswap_v2;
 pub mod uniswap_v3;
+
+use std::collections::HashSet;
+
+use crate::core::{
+    error::{Error, Result},
+    types::{
+        query::{Bound, SortOrder, TimeBound},
+        ChainId,
+    },
+};
+
+/// Requests over a range of blocks.
+///
+/// Used to resume a request from where a dropped connection left off.
+pub trait HasBlockBounds {
+    /// The `from_block` and `to_block` of the request
+    fn block_bounds(&self) -> (Bound, Bound);
+    /// Sets `from_block`, and clears `from_time` which it replaces
+    fn set_from_block(&mut self, from_block: Bound);
+    /// The chains the request is scoped to
+    fn chains(&self) -> &HashSet<ChainId>;
+
+    /// Whether `chains` must not be empty. Datasets of a single chain, such as
+    /// Fuel, use the chain of the server when it is.
+    fn requires_chains(&self) -> bool {
+        true
+    }
+
+    /// The `from_time` and `to_time` of the request, for requests that have
+    /// them
+    fn time_bounds(&self) -> (Option<&TimeBound>, Option<&TimeBound>) {
+        (None, None)
+    }
+
+    /// The order of the records by block, for requests that have one
+    fn order(&self) -> Option<SortOrder> {
+        None
+    }
+
+    /// The most records to return, for requests that have a limit
+    fn limit(&self) -> Option<u64> {
+        None
+    }
+
+    /// Sets the most records to return, for requests that have a limit
+    fn set_limit(&mut self, _limit: Option<u64>) {}
+
+    /// Fails with [`Error::InvalidBounds`] if a side of the range is bounded
+    /// by both a block other than the default and a time, or a time bound is
+    /// malformed, and as [`check_filters`](Self::check_filters) does
+    fn check_bounds(&self) -> Result<()> {
+        let (from_block, to_block) = self.block_bounds();
+        let (from_time, to_time) = self.time_bounds();
+        for (side, block, time) in [("from", from_block, from_time), ("to", to_block, to_time)] {
+            let Some(time) = time else {
+                continue;
+            };
+            if block != Bound::default() {
+                return Err(Error::InvalidBounds(format!(
+                    "{side}_block and {side}_time are both set"
+                )));
+            }
+            time.check().map_err(Error::InvalidBounds)?;
+        }
+        self.check_filters()
+    }
+
+    /// Checks the filters of the request other than its range, for
+    /// [`check_bounds`](Self::check_bounds). Fails with
+    /// [`Error::InvalidRequest`] if a filter cannot match any record, and with
+    /// [`Error::InvalidBounds`] if it contradicts the range.
+    fn check_filters(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Fails with [`Error::InvalidRequest`] if a contract filter has the zero
+    /// address, for [`validate`](Self::validate)
+    fn check_contracts(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Checks the request before it is sent, which the [`Client`](crate::Client)
+    /// does for every request.
+    ///
+    /// Fails with [`Error::InvalidRequest`] if it has no chains but
+    /// [`requires_chains`](Self::requires_chains), its range
+    /// ends before it starts, or a contract filter has the zero address, and
+    /// as [`check_bounds`](Self::check_bounds) does.
+    fn validate(&self) -> Result<()> {
+        if self.requires_chains() && self.chains().is_empty() {
+            return Err(Error::InvalidRequest(
+                "no chains are set in `chains`".into(),
+            ));
+        }
+        if let (Bound::Exact(from), Bound::Exact(to)) = self.block_bounds() {
+            if from > to {
+                return Err(Error::InvalidRequest(format!(
+                    "from_block {from} is after to_block {to}"
+                )));
+            }
+        }
+        self.check_contracts()?;
+        self.check_bounds()
+    }
+}
+
+/// Fails with [`Error::InvalidRequest`] if the contract filter `field` has the
+/// zero address, which no contract has
+pub(crate) fn reject_zero_address<T: Default + Eq + std::hash::Hash>(
+    field: &str,
+    contracts: &HashSet<T>,
+) -> Result<()> {
+    if contracts.contains(&T::default()) {
+        return Err(Error::InvalidRequest(format!(
+            "{field} has the zero address"
+        )));
+    }
+    Ok(())
+}
+
+macro_rules! impl_has_block_bounds {
+    (@contracts $($contracts:ident),*) => {
+        fn check_contracts(&self) -> Result<()> {
+            $(reject_zero_address(stringify!($contracts), &self.$contracts)?;)*
+            Ok(())
+        }
+    };
+    (@time) => {
+        fn set_from_block(&mut self, from_block: Bound) {
+            self.from_block = from_block;
+            self.from_time = None;
+        }
+
+        fn time_bounds(&self) -> (Option<&TimeBound>, Option<&TimeBound>) {
+            (self.from_time.as_ref(), self.to_time.as_ref())
+        }
+    };
+    (@filters) => {};
+    (@filters $filters:ident) => {
+        fn check_filters(&self) -> Result<()> {
+            self.$filters()
+        }
+    };
+    (@limit) => {
+        fn order(&self) -> Option<SortOrder> {
+            self.order
+        }
+
+        fn limit(&self) -> Option<u64> {
+            self.limit
+        }
+
+        fn set_limit(&mut self, limit: Option<u64>) {
+            self.limit = limit;
+        }
+    };
+    (time, limit: $($request:ty $([$($contracts:ident),*])? $(=> $filters:ident)?),* $(,)?) => {
+        $(
+            impl HasBlockBounds for $request {
+                fn block_bounds(&self) -> (Bound, Bound) {
+                    (self.from_block, self.to_block)
+                }
+
+                fn chains(&self) -> &HashSet<ChainId> {
+                    &self.chains
+                }
+
+                impl_has_block_bounds!(@time);
+                impl_has_block_bounds!(@limit);
+                impl_has_block_bounds!(@filters $($filters)?);
+                impl_has_block_bounds!(@contracts $($($contracts),*)?);
+            }
+        )*
+    };
+    (time: $($request:ty $([$($contracts:ident),*])?),* $(,)?) => {
+        $(
+            impl HasBlockBounds for $request {
+                fn block_bounds(&self) -> (Bound, Bound) {
+                    (self.from_block, self.to_block)
+                }
+
+                fn chains(&self) -> &HashSet<ChainId> {
+                    &self.chains
+                }
+
+                impl_has_block_bounds!(@time);
+                impl_has_block_bounds!(@contracts $($($contracts),*)?);
+            }
+        )*
+    };
+    (single_chain: $($request:ty),* $(,)?) => {
+        $(
+            impl HasBlockBounds for $request {
+                fn block_bounds(&self) -> (Bound, Bound) {
+                    (self.from_block, self.to_block)
+                }
+
+                fn set_from_block(&mut self, from_block: Bound) {
+                    self.from_block = from_block;
+                }
+
+                fn chains(&self) -> &HashSet<ChainId> {
+                    &self.chains
+                }
+
+                fn requires_chains(&self) -> bool {
+                    false
+                }
+            }
+        )*
+    };
+    ($($request:ty $([$($contracts:ident),*])?),* $(,)?) => {
+        $(
+            impl HasBlockBounds for $request {
+                fn block_bounds(&self) -> (Bound, Bound) {
+                    (self.from_block, self.to_block)
+                }
+
+                fn set_from_block(&mut self, from_block: Bound) {
+                    self.from_block = from_block;
+                }
+
+                fn chains(&self) -> &HashSet<ChainId> {
+                    &self.chains
+                }
+
+                impl_has_block_bounds!(@contracts $($($contracts),*)?);
+            }
+        )*
+    };
+}
+
+impl_has_block_bounds!(
+    time: erc20::GetErc20Request [address__in],
+    erc20::GetErc20ApprovalsRequest [address__in],
+    uniswap_v2::GetPairsRequest [pair_address__in],
+    uniswap_v3::GetFeesRequest [pool_address__in],
+    uniswap_v3::GetPoolsRequest [pool_address__in],
+    uniswap_v3::GetPositionsRequest [pool_address__in],
+);
+
+impl_has_block_bounds!(
+    time, limit: logs::GetLogsRequest [address__in],
+    erc20::GetErc20TransfersRequest [address__in] => check_amounts,
+    transfers::GetTransfersRequest,
+    txs::GetTxsRequest,
+    uniswap_v2::GetPricesRequest [pair_address__in],
+    uniswap_v3::GetPricesRequest [pool_address__in],
+);
+
+impl_has_block_bounds!(
+    curve::GetCrvTokenRequest [address__in, pool_address__in],
+    curve::GetCrvPoolRequest [pool_address__in],
+    curve::GetCrvPriceRequest [pool_address__in],
+);
+
+impl_has_block_bounds!(
+    single_chain: btc::GetBtcBlocksRequest,
+    btc::GetBtcTxsRequest,
+    fuel::GetFuelBlocksRequest,
+    fuel::GetFuelLogsRequest,
+    fuel::GetFuelTxsRequest,
+    fuel::GetFuelReceiptsRequest,
+    fuel::GetFuelMessagesRequest,
+    fuel::GetSparkMarketRequest,
+    fuel::GetUtxoRequest,
+    fuel::GetSrc20,
+    fuel::GetSrc7,
+);
@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     core::types::{default_chains, ChainId},
-    query::Bound,
-    utils::serialize_comma_separated,
+    query::{Bound, SortOrder, TimeBound},
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -15,6 +15,7 @@ pub struct GetTransfersRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -26,23 +27,51 @@ pub struct GetTransfersRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
+    /// Transfers from or to any of the addresses.
+    ///
+    /// The address filters all apply: with `address__in` and `to__in` set, a
+    /// transfer is returned if it goes to an address of `to__in`, and either
+    /// side is an address of `address__in`. Neither takes precedence, so
+    /// filters on a side narrow `address__in` down rather than replace it.
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub address__in: HashSet<Address>,
 
-    #[serde(default)]
+    /// Transfers to any of the addresses
+    #[serde(default, alias = "to_address__in")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub to__in: HashSet<Address>,
 
-    #[serde(default)]
+    /// Transfers from any of the addresses
+    #[serde(default, alias = "from_address__in")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub from__in: HashSet<Address>,
@@ -52,6 +81,27 @@ pub struct GetTransfersRequest {
 
     #[serde(default)]
     pub value__gte: Option<U256>,
+
+    /// The columns to return, see [`select_columns`](crate::arrow::select_columns)
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub columns: Vec<String>,
+
+    /// The most records to return, all of them if `None`. The typed methods
+    /// of [`Client`](crate::Client) stop after this many records even if the
+    /// server ignores it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+
+    /// The order of the records by block, ascending if `None`. Resumed
+    /// WebSocket streams continue from the last block received, so only
+    /// ascending streams are resumed correctly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
 }
 
 impl Default for GetTransfersRequest {
@@ -60,11 +110,16 @@ impl Default for GetTransfersRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             address__in: HashSet::default(),
             to__in: HashSet::default(),
             from__in: HashSet::default(),
             value__lte: None,
             value__gte: None,
+            columns: Vec::new(),
+            limit: None,
+            order: None,
         }
     }
 }
@@ -6,8 +6,8 @@ use ethers_core::types::{Address, U256};
 
 use crate::{
     core::types::{default_chains, ChainId},
-    query::Bound,
-    utils::serialize_comma_separated,
+    query::{Bound, SortOrder, TimeBound},
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -16,6 +16,7 @@ pub struct GetTxsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -27,19 +28,42 @@ pub struct GetTxsRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub from__in: HashSet<Address>,
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub to__in: HashSet<Address>,
 
+    /// Whether the transactions succeeded, all of them by default
+    #[serde(default, skip_serializing_if = "TxStatus::is_all")]
+    pub status: TxStatus,
+
     #[serde(default)]
     pub value__gte: Option<U256>,
     #[serde(default)]
@@ -64,6 +88,27 @@ pub struct GetTxsRequest {
     pub max_priority_fee_per_gas__gte: Option<U256>,
     #[serde(default)]
     pub max_priority_fee_per_gas__lte: Option<U256>,
+
+    /// The columns to return, see [`select_columns`](crate::arrow::select_columns)
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub columns: Vec<String>,
+
+    /// The most records to return, all of them if `None`. The typed methods
+    /// of [`Client`](crate::Client) stop after this many records even if the
+    /// server ignores it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+
+    /// The order of the records by block, ascending if `None`. Resumed
+    /// WebSocket streams continue from the last block received, so only
+    /// ascending streams are resumed correctly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
 }
 
 impl Default for GetTxsRequest {
@@ -72,8 +117,11 @@ impl Default for GetTxsRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             from__in: HashSet::new(),
             to__in: HashSet::new(),
+            status: TxStatus::All,
             value__gte: None,
             value__lte: None,
             gas_price__gte: None,
@@ -84,6 +132,55 @@ impl Default for GetTxsRequest {
             max_fee_per_gas__lte: None,
             max_priority_fee_per_gas__gte: None,
             max_priority_fee_per_gas__lte: None,
+            columns: Vec::new(),
+            limit: None,
+            order: None,
+        }
+    }
+}
+
+/// The receipt status of the transactions of a [`GetTxsRequest`], sent as the
+/// status code of the receipt
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TxStatus {
+    Success,
+    Failed,
+    /// Both successful and failed transactions
+    #[default]
+    All,
+}
+
+impl TxStatus {
+    fn is_all(&self) -> bool {
+        *self == Self::All
+    }
+}
+
+impl Serialize for TxStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Success => serializer.serialize_u8(1),
+            Self::Failed => serializer.serialize_u8(0),
+            Self::All => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TxStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match Option::<u8>::deserialize(deserializer)? {
+            Some(1) => Ok(Self::Success),
+            Some(0) => Ok(Self::Failed),
+            None => Ok(Self::All),
+            Some(status) => Err(serde::de::Error::custom(format!(
+                "invalid transaction status {status}, expected 0 or 1"
+            ))),
         }
     }
 }
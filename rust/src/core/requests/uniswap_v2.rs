@@ -6,8 +6,8 @@ use ethers_core::types::{Address, U128};
 
 use crate::{
     core::types::{default_chains, uniswap_v2::ReserveEvent, ChainId},
-    query::Bound,
-    utils::serialize_comma_separated,
+    query::{Bound, SortOrder, TimeBound},
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -16,6 +16,7 @@ pub struct GetPairsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -27,9 +28,27 @@ pub struct GetPairsRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pair_address__in: HashSet<Address>,
@@ -37,6 +56,7 @@ pub struct GetPairsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub factory_address__in: HashSet<Address>,
@@ -44,6 +64,7 @@ pub struct GetPairsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token0__in: HashSet<Address>,
@@ -51,6 +72,7 @@ pub struct GetPairsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token1__in: HashSet<Address>,
@@ -58,6 +80,7 @@ pub struct GetPairsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub tokens__in: HashSet<Address>,
@@ -69,6 +92,8 @@ impl Default for GetPairsRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             pair_address__in: HashSet::new(),
             factory_address__in: HashSet::new(),
             token0__in: HashSet::new(),
@@ -84,6 +109,7 @@ pub struct GetPricesRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -95,9 +121,27 @@ pub struct GetPricesRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pair_address__in: HashSet<Address>,
@@ -105,6 +149,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pair_factory_address__in: HashSet<Address>,
@@ -112,6 +157,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub event__in: HashSet<ReserveEvent>,
@@ -137,6 +183,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub sender__in: HashSet<Address>,
@@ -144,6 +191,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub receiver__in: HashSet<Address>,
@@ -175,6 +223,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token0_address__in: HashSet<Address>,
@@ -182,6 +231,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token0_symbol__in: HashSet<String>,
@@ -189,6 +239,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token1_address__in: HashSet<Address>,
@@ -196,6 +247,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token1_symbol__in: HashSet<String>,
@@ -203,6 +255,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub tokens_address__in: HashSet<Address>,
@@ -210,9 +263,22 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub tokens_symbol__in: HashSet<String>,
+
+    /// The most records to return, all of them if `None`. The typed methods
+    /// of [`Client`](crate::Client) stop after this many records even if the
+    /// server ignores it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+
+    /// The order of the records by block, ascending if `None`. Resumed
+    /// WebSocket streams continue from the last block received, so only
+    /// ascending streams are resumed correctly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
 }
 
 impl Default for GetPricesRequest {
@@ -221,6 +287,8 @@ impl Default for GetPricesRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             pair_address__in: HashSet::new(),
             pair_factory_address__in: HashSet::new(),
             event__in: HashSet::new(),
@@ -246,6 +314,8 @@ impl Default for GetPricesRequest {
             token1_symbol__in: HashSet::new(),
             tokens_address__in: HashSet::new(),
             tokens_symbol__in: HashSet::new(),
+            limit: None,
+            order: None,
         }
     }
 }
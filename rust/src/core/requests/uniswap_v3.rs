@@ -5,8 +5,8 @@ use ethers_core::types::Address;
 
 use crate::{
     core::types::{default_chains, ChainId},
-    query::Bound,
-    utils::serialize_comma_separated,
+    query::{Bound, SortOrder, TimeBound},
+    utils::{deserialize_comma_separated, serialize_comma_separated},
 };
 #[derive(Clone, Deserialize, Serialize, Debug)]
 #[allow(non_snake_case)]
@@ -14,6 +14,7 @@ pub struct GetFeesRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -25,9 +26,27 @@ pub struct GetFeesRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pool_address__in: HashSet<Address>,
@@ -35,6 +54,7 @@ pub struct GetFeesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub sender__in: HashSet<Address>,
@@ -42,6 +62,7 @@ pub struct GetFeesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub recipient__in: HashSet<Address>,
@@ -83,6 +104,8 @@ impl Default for GetFeesRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             pool_address__in: HashSet::new(),
             sender__in: HashSet::new(),
             recipient__in: HashSet::new(),
@@ -106,6 +129,7 @@ pub struct GetPoolsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -117,9 +141,27 @@ pub struct GetPoolsRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pool_address__in: HashSet<Address>,
@@ -127,6 +169,7 @@ pub struct GetPoolsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub factory_address__in: HashSet<Address>,
@@ -134,6 +177,7 @@ pub struct GetPoolsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token0__in: HashSet<Address>,
@@ -141,6 +185,7 @@ pub struct GetPoolsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token1__in: HashSet<Address>,
@@ -148,6 +193,7 @@ pub struct GetPoolsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub tokens__in: HashSet<Address>,
@@ -179,6 +225,8 @@ impl Default for GetPoolsRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             pool_address__in: HashSet::default(),
             factory_address__in: HashSet::default(),
             token0__in: HashSet::default(),
@@ -201,6 +249,7 @@ pub struct GetPositionsRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -212,9 +261,27 @@ pub struct GetPositionsRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pool_address__in: HashSet<Address>,
@@ -222,6 +289,7 @@ pub struct GetPositionsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub sender__in: HashSet<Address>,
@@ -229,6 +297,7 @@ pub struct GetPositionsRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub recipient__in: HashSet<Address>,
@@ -270,6 +339,8 @@ impl Default for GetPositionsRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             pool_address__in: HashSet::new(),
             sender__in: HashSet::new(),
             recipient__in: HashSet::new(),
@@ -293,6 +364,7 @@ pub struct GetPricesRequest {
     #[serde(default = "default_chains")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub chains: HashSet<ChainId>,
@@ -304,9 +376,27 @@ pub struct GetPricesRequest {
     #[serde(default)]
     pub to_block: Bound,
 
+    // Inclusive lower bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "from_timestamp",
+        alias = "from_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub from_time: Option<TimeBound>,
+    // Exclusive upper bound if is Some for block timestamp
+    #[serde(
+        default,
+        rename = "to_timestamp",
+        alias = "to_time",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to_time: Option<TimeBound>,
+
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pool_address__in: HashSet<Address>,
@@ -314,10 +404,21 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub pool_factory_address__in: HashSet<Address>,
 
+    /// The fee tiers of the pools in hundredths of a bip, e.g. 500, 3000 and
+    /// 10000
+    #[serde(default)]
+    #[serde(
+        serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
+        skip_serializing_if = "HashSet::is_empty"
+    )]
+    pub fee__in: HashSet<u32>,
+
     #[serde(default)]
     pub virtual0__gte: Option<f64>,
 
@@ -339,6 +440,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub sender__in: HashSet<Address>,
@@ -346,6 +448,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub receiver__in: HashSet<Address>,
@@ -374,9 +477,10 @@ pub struct GetPricesRequest {
     #[serde(default)]
     pub tick__lte: Option<i32>,
 
-    #[serde(default)]
+    #[serde(default, alias = "token0__in")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token0_address__in: HashSet<Address>,
@@ -384,13 +488,15 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token0_symbol__in: HashSet<String>,
 
-    #[serde(default)]
+    #[serde(default, alias = "token1__in")]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token1_address__in: HashSet<Address>,
@@ -398,6 +504,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub token1_symbol__in: HashSet<String>,
@@ -405,6 +512,7 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub tokens_address__in: HashSet<Address>,
@@ -412,9 +520,22 @@ pub struct GetPricesRequest {
     #[serde(default)]
     #[serde(
         serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated",
         skip_serializing_if = "HashSet::is_empty"
     )]
     pub tokens_symbol__in: HashSet<String>,
+
+    /// The most records to return, all of them if `None`. The typed methods
+    /// of [`Client`](crate::Client) stop after this many records even if the
+    /// server ignores it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+
+    /// The order of the records by block, ascending if `None`. Resumed
+    /// WebSocket streams continue from the last block received, so only
+    /// ascending streams are resumed correctly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
 }
 
 impl Default for GetPricesRequest {
@@ -423,6 +544,8 @@ impl Default for GetPricesRequest {
             chains: default_chains(),
             from_block: Bound::default(),
             to_block: Bound::default(),
+            from_time: None,
+            to_time: None,
             price__gte: None,
             price__lte: None,
             sender__in: HashSet::new(),
@@ -439,6 +562,7 @@ impl Default for GetPricesRequest {
             tokens_symbol__in: HashSet::new(),
             pool_address__in: HashSet::new(),
             pool_factory_address__in: HashSet::new(),
+            fee__in: HashSet::new(),
             virtual0__gte: None,
             virtual0__lte: None,
             virtual1__gte: None,
@@ -447,6 +571,8 @@ impl Default for GetPricesRequest {
             liquidity__lte: None,
             tick__gte: None,
             tick__lte: None,
+            limit: None,
+            order: None,
         }
     }
 }
@@ -0,0 +1,193 @@
+//! Batched inserts of typed record streams into ClickHouse
+
+use std::time::Duration;
+
+use ::clickhouse::{Client, Row};
+use futures::StreamExt;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::core::{
+    error::{Error, Result},
+    provider::StreamResponse,
+    types::{blocks::Block, erc20::Erc20Transfer, logs::Log},
+};
+
+/// Records that belong to a block, which is used to report what was not committed
+pub trait BlockRecord {
+    fn block_number(&self) -> u64;
+}
+
+impl BlockRecord for Block {
+    fn block_number(&self) -> u64 {
+        self.block_number.as_u64()
+    }
+}
+
+impl BlockRecord for Log {
+    fn block_number(&self) -> u64 {
+        self.block_number.as_u64()
+    }
+}
+
+impl BlockRecord for Erc20Transfer {
+    fn block_number(&self) -> u64 {
+        self.block_number.as_u64()
+    }
+}
+
+/// Options for [`insert`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InsertOptions {
+    /// The number of rows sent in one `INSERT`
+    pub batch_size: usize,
+    /// How often a failed batch is sent again before giving up
+    pub retries: usize,
+    /// The delay before a failed batch is sent again
+    pub retry_delay: Duration,
+}
+
+impl Default for InsertOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 100_000,
+            retries: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// What [`insert`] committed to ClickHouse
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InsertSummary {
+    pub rows: u64,
+    /// The last committed block, `None` if the stream was empty
+    pub last_block: Option<u64>,
+}
+
+/// Inserts a typed record stream into `table` in batches, using the RowBinary format.
+///
+/// `mapping` converts every record into the row type of the table. A failed batch
+/// is retried as configured in `options`. If it still fails, or the stream fails,
+/// [`Error::UncommittedBlocks`] reports the blocks that were not committed, so the
+/// request can be resumed with `from_block` set to its first block. Batches are
+/// only cut on block boundaries, so a block is either committed as a whole or not.
+pub async fn insert<T, R, F>(
+    stream: StreamResponse<T>,
+    client: &Client,
+    table: &str,
+    mapping: F,
+    options: InsertOptions,
+) -> Result<InsertSummary>
+where
+    T: BlockRecord,
+    R: Row + Serialize,
+    F: Fn(&T) -> R,
+{
+    let mut stream = stream?;
+    let mut summary = InsertSummary::default();
+    let mut batch = Batch {
+        rows: Vec::with_capacity(options.batch_size),
+        blocks: None,
+    };
+
+    while let Some(record) = stream.next().await {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => return Err(batch.uncommitted(&summary, err)),
+        };
+
+        let block = record.block_number();
+        let full = batch.rows.len() >= options.batch_size;
+        if full && batch.blocks.is_some_and(|(_, last)| last != block) {
+            batch.commit(client, table, options, &mut summary).await?;
+        }
+
+        batch.blocks = Some(match batch.blocks {
+            Some((first, _)) => (first, block),
+            None => (block, block),
+        });
+        batch.rows.push(mapping(&record));
+    }
+    batch.commit(client, table, options, &mut summary).await?;
+
+    Ok(summary)
+}
+
+/// The rows not committed yet
+struct Batch<R> {
+    rows: Vec<R>,
+    /// The first and last block of the rows
+    blocks: Option<(u64, u64)>,
+}
+
+impl<R: Row + Serialize> Batch<R> {
+    async fn commit(
+        &mut self,
+        client: &Client,
+        table: &str,
+        options: InsertOptions,
+        summary: &mut InsertSummary,
+    ) -> Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        if let Err(err) = insert_batch(client, table, &self.rows, options).await {
+            return Err(self.uncommitted(summary, err));
+        }
+
+        summary.rows += self.rows.len() as u64;
+        summary.last_block = self.blocks.map(|(_, last)| last);
+        self.rows.clear();
+        self.blocks = None;
+        Ok(())
+    }
+
+    /// Everything from the first block of the batch on was not committed
+    fn uncommitted(&self, summary: &InsertSummary, err: Error) -> Error {
+        let (from_block, to_block) = match self.blocks {
+            Some((first, last)) => (Some(first), Some(last)),
+            None => (summary.last_block.map(|block| block + 1), None),
+        };
+        Error::UncommittedBlocks {
+            from_block,
+            to_block,
+            source: Box::new(err),
+        }
+    }
+}
+
+async fn insert_batch<R>(
+    client: &Client,
+    table: &str,
+    batch: &[R],
+    options: InsertOptions,
+) -> Result<()>
+where
+    R: Row + Serialize,
+{
+    let mut attempt = 0;
+    loop {
+        match try_insert_batch(client, table, batch).await {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt >= options.retries => return Err(err),
+            Err(err) => {
+                warn!("inserting into {table} failed, retrying: {err}");
+                attempt += 1;
+                tokio::time::sleep(options.retry_delay).await;
+            }
+        }
+    }
+}
+
+async fn try_insert_batch<R>(client: &Client, table: &str, batch: &[R]) -> Result<()>
+where
+    R: Row + Serialize,
+{
+    let mut insert = client.insert::<R>(table)?;
+    for row in batch {
+        insert.write(row).await?;
+    }
+    insert.end().await?;
+    Ok(())
+}
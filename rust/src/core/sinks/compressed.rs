@@ -0,0 +1,122 @@
+use std::{path::Path, pin::Pin};
+
+use async_compression::{
+    tokio::write::{GzipEncoder, ZstdEncoder},
+    Level,
+};
+use futures::StreamExt;
+use tokio::{
+    fs::{self, File},
+    io::{AsyncWrite, AsyncWriteExt, BufWriter},
+};
+
+use crate::core::{
+    error::{ResponseError, Result},
+    provider::{ResponseStream, StreamResponse},
+};
+
+/// The number of response bytes after which the encoder is flushed to disk
+const FLUSH_INTERVAL: u64 = 8 * 1024 * 1024;
+
+/// The codec and level used by [`write_compressed`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// Gzip with a level between 0 and 9
+    Gzip(u32),
+    /// Zstandard with a level between 1 and 22
+    Zstd(i32),
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::Zstd(3)
+    }
+}
+
+impl Compression {
+    /// Returns the file extension commonly appended for the codec
+    pub const fn file_extension(&self) -> &'static str {
+        match self {
+            Compression::Gzip(_) => "gz",
+            Compression::Zstd(_) => "zst",
+        }
+    }
+}
+
+/// What [`write_compressed`] wrote to disk
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompressedSummary {
+    /// The size of the response before compression
+    pub raw_bytes: u64,
+    /// The size of the written file
+    pub bytes: u64,
+}
+
+/// Writes a response of any format to a compressed file as it arrives.
+///
+/// The encoder is flushed every few megabytes and the file is synced to disk
+/// once the response ended. If the response or a write fails, the partial
+/// file is removed and the error is returned.
+pub async fn write_compressed(
+    stream: StreamResponse<Vec<u8>>,
+    path: impl AsRef<Path>,
+    compression: Compression,
+) -> Result<CompressedSummary> {
+    let path = path.as_ref();
+    let stream = stream?;
+
+    let raw_bytes = match write(stream, path, compression).await {
+        Ok(raw_bytes) => raw_bytes,
+        Err(err) => {
+            // the error of the response is more useful than a failed cleanup
+            let _ = fs::remove_file(path).await;
+            return Err(err);
+        }
+    };
+
+    Ok(CompressedSummary {
+        raw_bytes,
+        bytes: fs::metadata(path).await?.len(),
+    })
+}
+
+async fn write(
+    stream: ResponseStream<Vec<u8>>,
+    path: &Path,
+    compression: Compression,
+) -> Result<u64> {
+    let file = File::create(path).await?;
+    let sync = file.try_clone().await?;
+    let file = BufWriter::new(file);
+
+    let mut encoder: Pin<Box<dyn AsyncWrite + Send>> = match compression {
+        Compression::Gzip(level) => Box::pin(GzipEncoder::with_quality(
+            file,
+            Level::Precise(level.min(9) as i32),
+        )),
+        Compression::Zstd(level) => {
+            Box::pin(ZstdEncoder::with_quality(file, Level::Precise(level)))
+        }
+    };
+
+    let mut stream = ResponseError::map_stream(stream);
+    let mut raw_bytes = 0;
+    let mut unflushed = 0;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        encoder.write_all(&chunk).await?;
+
+        raw_bytes += chunk.len() as u64;
+        unflushed += chunk.len() as u64;
+        if unflushed >= FLUSH_INTERVAL {
+            encoder.flush().await?;
+            unflushed = 0;
+        }
+    }
+
+    // finishes the compressed stream and flushes all buffers into the file
+    encoder.shutdown().await?;
+    sync.sync_all().await?;
+
+    Ok(raw_bytes)
+}
@@ -0,0 +1,13 @@
+//! Helpers to persist response streams to disk
+
+#[cfg(feature = "clickhouse")]
+pub mod clickhouse;
+#[cfg(feature = "compression")]
+mod compressed;
+#[cfg(feature = "parquet")]
+mod parquet;
+
+#[cfg(feature = "compression")]
+pub use self::compressed::{write_compressed, CompressedSummary, Compression};
+#[cfg(feature = "parquet")]
+pub use self::parquet::{write_parquet, ParquetCompression, ParquetOptions, ParquetSummary};
@@ -0,0 +1,156 @@
+use std::{fs::File, path::Path};
+
+use ::arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
+use futures::StreamExt;
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    file::properties::{WriterProperties, DEFAULT_MAX_ROW_GROUP_SIZE},
+};
+
+use tokio::sync::mpsc;
+
+use crate::core::{
+    arrow::record_batches,
+    error::{Error, Result},
+    provider::StreamResponse,
+};
+
+/// The compression codec used for the Parquet column chunks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParquetCompression {
+    Uncompressed,
+    Snappy,
+    /// Zstandard with the default compression level
+    #[default]
+    Zstd,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+        }
+    }
+}
+
+/// Options for [`write_parquet`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParquetOptions {
+    /// The maximum number of rows per row group, which bounds the rows held in memory
+    pub row_group_size: usize,
+    pub compression: ParquetCompression,
+}
+
+impl Default for ParquetOptions {
+    fn default() -> Self {
+        Self {
+            row_group_size: DEFAULT_MAX_ROW_GROUP_SIZE,
+            compression: ParquetCompression::default(),
+        }
+    }
+}
+
+/// What [`write_parquet`] wrote to disk
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParquetSummary {
+    pub rows: u64,
+    pub bytes: u64,
+}
+
+/// Writes a response requested in [`Format::Arrow`](crate::Format::Arrow) to a Parquet file.
+///
+/// Row groups are flushed to `path` as soon as they are full, so only one row
+/// group is kept in memory at a time. The file is written on a blocking
+/// thread, which is handed the batches as they are decoded.
+pub async fn write_parquet(
+    stream: StreamResponse<Vec<u8>>,
+    path: impl AsRef<Path>,
+    options: ParquetOptions,
+) -> Result<ParquetSummary> {
+    let path = path.as_ref().to_path_buf();
+    let mut batches = record_batches(stream?);
+
+    let properties = WriterProperties::builder()
+        .set_max_row_group_size(options.row_group_size)
+        .set_compression(options.compression.into())
+        .build();
+
+    let (sender, receiver) = mpsc::channel(1);
+    let writer = tokio::task::spawn_blocking(move || write_batches(&path, properties, receiver));
+    // the sender is dropped once the response ends or fails, which stops the writer
+    let sent = async move {
+        while let Some(batch) = batches.next().await {
+            // the writer only hangs up when it failed, and returns why
+            if sender.send(Write::Batch(batch?)).await.is_err() {
+                return Ok(());
+            }
+        }
+        let _ = sender.send(Write::End(batches.schema())).await;
+        Ok::<_, Error>(())
+    }
+    .await;
+
+    let summary = writer
+        .await
+        .map_err(|err| Error::Custom(err.to_string().into()))??;
+    sent?;
+    Ok(summary)
+}
+
+/// What the blocking writer of [`write_parquet`] is handed
+enum Write {
+    Batch(RecordBatch),
+    /// The response ended, with its schema if it sent one
+    End(Option<SchemaRef>),
+}
+
+/// Writes the batches received to `path`, until the response ends
+fn write_batches(
+    path: &Path,
+    properties: WriterProperties,
+    mut receiver: mpsc::Receiver<Write>,
+) -> Result<ParquetSummary> {
+    let mut writer = None;
+    let mut rows = 0;
+    let schema = loop {
+        match receiver.blocking_recv() {
+            Some(Write::Batch(batch)) => {
+                let writer = match writer.as_mut() {
+                    Some(writer) => writer,
+                    None => writer.insert(ArrowWriter::try_new(
+                        File::create(path)?,
+                        batch.schema(),
+                        Some(properties.clone()),
+                    )?),
+                };
+                writer.write(&batch)?;
+                rows += batch.num_rows() as u64;
+            }
+            Some(Write::End(schema)) => break schema,
+            // the response failed, and its error is returned instead
+            None => return Ok(ParquetSummary::default()),
+        }
+    };
+
+    // an empty response still yields a valid file, as long as its schema was sent
+    let writer = match (writer, schema) {
+        (Some(writer), _) => writer,
+        (None, Some(schema)) => {
+            ArrowWriter::try_new(File::create(path)?, schema, Some(properties))?
+        }
+        (None, None) => {
+            return Err(Error::Custom(
+                "the response did not contain an arrow schema".into(),
+            ))
+        }
+    };
+    writer.close()?;
+
+    Ok(ParquetSummary {
+        rows,
+        bytes: std::fs::metadata(path)?.len(),
+    })
+}
@@ -0,0 +1,42 @@
+use ethers_core::types::{Address, Bloom, Bytes, H256, H64, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use super::ChainId;
+
+/// A block record as delivered by the `blocks` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Block {
+    pub chain: ChainId,
+    pub block_number: U64,
+    pub hash: H256,
+    pub parent_hash: H256,
+    #[serde(default)]
+    pub uncles_hash: H256,
+    pub miner: Address,
+    #[serde(default)]
+    pub state_root: H256,
+    #[serde(default)]
+    pub transactions_root: H256,
+    #[serde(default)]
+    pub receipts_root: H256,
+    pub gas_used: U256,
+    pub gas_limit: U256,
+    /// Only present for blocks after the London hard fork
+    #[serde(default)]
+    pub base_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub extra_data: Bytes,
+    #[serde(default)]
+    pub logs_bloom: Bloom,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub difficulty: U256,
+    #[serde(default)]
+    pub total_difficulty: Option<U256>,
+    #[serde(default)]
+    pub size: Option<U256>,
+    #[serde(default)]
+    pub mix_hash: H256,
+    #[serde(default)]
+    pub nonce: H64,
+}
@@ -0,0 +1,90 @@
+use ethers_core::types::{Bytes, H256};
+use serde::{Deserialize, Serialize};
+
+use super::ChainId;
+
+/// A block as delivered by the `btc/blocks` dataset
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
+pub struct BtcBlock {
+    pub chain: ChainId,
+    #[serde(alias = "block_number")]
+    pub height: u64,
+    #[serde(alias = "block_hash")]
+    pub hash: H256,
+    #[serde(alias = "previousblockhash")]
+    pub prev_hash: H256,
+    #[serde(alias = "merkleroot")]
+    pub merkle_root: H256,
+    #[serde(alias = "timestamp")]
+    pub time: u64,
+    pub difficulty: f64,
+    #[serde(alias = "n_tx")]
+    pub tx_count: u64,
+}
+
+/// A transaction as delivered by the `btc/txs` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct BtcTransaction {
+    pub chain: ChainId,
+    pub block_number: u64,
+    pub txid: H256,
+    #[serde(alias = "lock_time")]
+    pub locktime: u64,
+    #[serde(alias = "vin")]
+    pub inputs: Vec<BtcInput>,
+    #[serde(alias = "vout")]
+    pub outputs: Vec<BtcOutput>,
+}
+
+impl BtcTransaction {
+    /// Returns true if this is the coinbase transaction of its block
+    pub fn is_coinbase(&self) -> bool {
+        self.inputs.first().is_some_and(BtcInput::is_coinbase)
+    }
+
+    /// Returns true if any input carries witness data
+    pub fn is_segwit(&self) -> bool {
+        self.inputs.iter().any(|input| !input.witness.is_empty())
+    }
+
+    /// Returns the sum of all output values in satoshis
+    pub fn output_value(&self) -> u64 {
+        self.outputs.iter().map(|output| output.value).sum()
+    }
+}
+
+/// A transaction input, coinbase inputs have no previous output
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct BtcInput {
+    /// The transaction holding the spent output
+    #[serde(default)]
+    pub txid: Option<H256>,
+    /// The index of the spent output
+    #[serde(default)]
+    pub vout: Option<u32>,
+    #[serde(default)]
+    pub coinbase: Option<Bytes>,
+    #[serde(default)]
+    pub script_sig: Bytes,
+    #[serde(default, alias = "txinwitness")]
+    pub witness: Vec<Bytes>,
+    pub sequence: u32,
+}
+
+impl BtcInput {
+    pub fn is_coinbase(&self) -> bool {
+        self.coinbase.is_some() || self.txid.is_none()
+    }
+}
+
+/// A transaction output
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct BtcOutput {
+    #[serde(alias = "index")]
+    pub n: u32,
+    /// The output value in satoshis
+    pub value: u64,
+    pub script_pubkey: Bytes,
+    #[serde(default)]
+    pub address: Option<String>,
+}
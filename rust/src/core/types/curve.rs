@@ -0,0 +1,115 @@
+use ethers_core::types::{Address, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use super::ChainId;
+use crate::utils::{deserialize_lossy_string, deserialize_u256, u256_to_f64};
+
+/// A token record as delivered by the `curve/tokens` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct CrvToken {
+    pub chain: ChainId,
+    pub block_number: U64,
+    pub address: Address,
+    #[serde(default, deserialize_with = "deserialize_lossy_string")]
+    pub symbol: String,
+    #[serde(default, deserialize_with = "deserialize_lossy_string")]
+    pub name: String,
+    pub decimals: u8,
+    /// The pools this token is a coin of
+    #[serde(default, alias = "pool_address")]
+    pub pools: Vec<Address>,
+}
+
+/// The flavour of a Curve pool
+#[derive(Clone, Copy, Debug, Default, Hash, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolType {
+    /// A stableswap pool over plain coins
+    #[default]
+    Plain,
+    /// A stableswap pool pairing a coin with the LP token of a base pool
+    Meta,
+    /// A cryptoswap pool for volatile assets
+    Crypto,
+    /// A pool type not known to this version of the client
+    #[serde(other)]
+    Unknown,
+}
+
+/// A pool record as delivered by the `curve/pools` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct CrvPool {
+    pub chain: ChainId,
+    pub block_number: U64,
+    pub pool_address: Address,
+    /// The LP token of the pool
+    pub token: Address,
+    #[serde(default)]
+    pub owner: Address,
+    #[serde(default)]
+    pub pool_type: PoolType,
+    /// Pools hold between 2 and 8 coins
+    pub coins: Vec<Address>,
+    /// The base pool of a metapool
+    #[serde(default)]
+    pub base_pool: Option<Address>,
+    /// The coins of the base pool of a metapool
+    #[serde(default)]
+    pub base_coins: Vec<Address>,
+    /// The amplification coefficient
+    #[serde(alias = "a", deserialize_with = "deserialize_u256")]
+    pub initial_a: U256,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub fee: U256,
+    #[serde(default, deserialize_with = "deserialize_u256")]
+    pub admin_fee: U256,
+}
+
+impl CrvPool {
+    /// Returns true if the pool trades against the LP token of a base pool
+    pub fn is_metapool(&self) -> bool {
+        self.base_pool.is_some()
+    }
+}
+
+/// A token exchange as delivered by the `curve/prices` dataset
+///
+/// Shares the naming of the Uniswap V2 and V3 price records.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
+pub struct CrvPrice {
+    pub chain: ChainId,
+    pub block_number: U64,
+    pub timestamp: u64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    #[serde(default)]
+    pub log_index: U64,
+    pub pool_address: Address,
+    #[serde(default)]
+    pub buyer: Address,
+    /// The token sold into the pool
+    #[serde(alias = "sold_address")]
+    pub token_in: Address,
+    /// The token bought from the pool
+    #[serde(alias = "bought_address")]
+    pub token_out: Address,
+    #[serde(default)]
+    pub tokens_sold: f64,
+    #[serde(default)]
+    pub tokens_bought: f64,
+    /// The price of `token_in` denominated in `token_out`
+    pub price: f64,
+    /// The virtual price of the pool LP token, scaled by `1e18`
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub virtual_price: U256,
+}
+
+impl CrvPrice {
+    /// The virtual price of the pool LP token as a float
+    pub fn virtual_price_f64(&self) -> f64 {
+        let scale = U256::exp10(18);
+        let (int, frac) = self.virtual_price.div_mod(scale);
+        // the fraction is below 10^18, the integer part may not fit a u128
+        u256_to_f64(int) + frac.as_u128() as f64 / 1e18
+    }
+}
@@ -0,0 +1,60 @@
+use ethers_core::types::{Address, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use super::ChainId;
+use crate::utils::deserialize_u256;
+
+/// An approval as delivered by the `erc20/approvals` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Approval {
+    pub chain: ChainId,
+    pub block_number: U64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    pub log_index: U64,
+    /// The ERC-20 token contract
+    #[serde(alias = "address")]
+    pub token: Address,
+    pub owner: Address,
+    pub spender: Address,
+    /// The approved amount in base units, `U256::MAX` for infinite approvals
+    #[serde(alias = "value", deserialize_with = "deserialize_u256")]
+    pub amount: U256,
+}
+
+impl Approval {
+    /// Returns true if the spender was granted an unlimited allowance
+    pub fn is_infinite(&self) -> bool {
+        self.amount == U256::MAX
+    }
+}
+
+/// A token transfer as delivered by the `erc20/transfers` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Erc20Transfer {
+    pub chain: ChainId,
+    pub block_number: U64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    pub log_index: U64,
+    /// The ERC-20 token contract
+    #[serde(alias = "address")]
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    /// The transferred amount in base units
+    #[serde(alias = "value", deserialize_with = "deserialize_u256")]
+    pub amount: U256,
+}
+
+impl Erc20Transfer {
+    /// Returns true if the tokens were minted, i.e. sent from the zero address
+    pub fn is_mint(&self) -> bool {
+        self.from.is_zero()
+    }
+
+    /// Returns true if the tokens were burned, i.e. sent to the zero address
+    pub fn is_burn(&self) -> bool {
+        self.to.is_zero()
+    }
+}
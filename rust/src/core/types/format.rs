@@ -1,5 +1,7 @@
-#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
-#[serde(rename_all = "snake_case")]
+/// The format of a response, sent as the `format` query parameter
+///
+/// Options of a format are sent as separate query parameters, see [`Format::options`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Format {
     /// Plain JSON
     Json,
@@ -11,4 +13,178 @@ pub enum Format {
     Arrow,
     /// Arrow IPC Stream format
     ArrowStream,
+    /// Comma separated values, or another delimiter if set in the options
+    Csv(CsvOptions),
+    /// Apache Parquet, for bulk downloads
+    Parquet,
+    /// Apache Avro object container
+    Avro,
+    /// Length delimited protobuf messages
+    Proto,
+}
+
+impl Format {
+    /// Returns the value of the `format` query parameter
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::JsonStream => "json_stream",
+            Format::Arrow => "arrow",
+            Format::ArrowStream => "arrow_stream",
+            Format::Csv(_) => "csv",
+            Format::Parquet => "parquet",
+            Format::Avro => "avro",
+            Format::Proto => "proto",
+        }
+    }
+
+    /// Returns the query parameters for the options of the format, which are
+    /// only sent if they differ from the defaults of the server
+    pub fn options(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Format::Csv(options) => options.query_params(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the media type of a response in this format
+    pub const fn content_type(&self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::JsonStream => "application/x-ndjson",
+            Format::Arrow => "application/vnd.apache.arrow.file",
+            Format::ArrowStream => "application/vnd.apache.arrow.stream",
+            Format::Csv(CsvOptions {
+                delimiter: b'\t', ..
+            }) => "text/tab-separated-values",
+            Format::Csv(_) => "text/csv",
+            Format::Parquet => "application/vnd.apache.parquet",
+            Format::Avro => "application/avro",
+            Format::Proto => "application/x-protobuf",
+        }
+    }
+
+    /// Returns the usual file extension for this format, without the leading dot
+    pub const fn file_extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::JsonStream => "jsonl",
+            Format::Arrow => "arrow",
+            Format::ArrowStream => "arrows",
+            Format::Csv(CsvOptions {
+                delimiter: b'\t', ..
+            }) => "tsv",
+            Format::Csv(_) => "csv",
+            Format::Parquet => "parquet",
+            Format::Avro => "avro",
+            Format::Proto => "pb",
+        }
+    }
+}
+
+impl serde::Serialize for Format {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Format {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let format = String::deserialize(deserializer)?;
+        match format.as_str() {
+            "json" => Ok(Format::Json),
+            "json_stream" => Ok(Format::JsonStream),
+            "arrow" => Ok(Format::Arrow),
+            "arrow_stream" => Ok(Format::ArrowStream),
+            "csv" => Ok(Format::Csv(CsvOptions::new())),
+            "parquet" => Ok(Format::Parquet),
+            "avro" => Ok(Format::Avro),
+            "proto" => Ok(Format::Proto),
+            _ => Err(serde::de::Error::unknown_variant(
+                &format,
+                &[
+                    "json",
+                    "json_stream",
+                    "arrow",
+                    "arrow_stream",
+                    "csv",
+                    "parquet",
+                    "avro",
+                    "proto",
+                ],
+            )),
+        }
+    }
+}
+
+/// Options of [`Format::Csv`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The field delimiter, `,` by default
+    pub delimiter: u8,
+    /// The quote character, `"` by default
+    pub quote: u8,
+    /// Whether the first row holds the column names, true by default
+    pub header: bool,
+}
+
+impl CsvOptions {
+    /// Returns the defaults of the server, which produce plain CSV
+    pub const fn new() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            header: true,
+        }
+    }
+
+    /// Returns options for tab separated values
+    pub const fn tsv() -> Self {
+        Self {
+            delimiter: b'\t',
+            ..Self::new()
+        }
+    }
+
+    pub const fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub const fn quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub const fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    fn query_params(&self) -> Vec<(&'static str, String)> {
+        let defaults = Self::new();
+        let mut params = Vec::new();
+        if self.delimiter != defaults.delimiter {
+            params.push(("delimiter", char::from(self.delimiter).to_string()));
+        }
+        if self.quote != defaults.quote {
+            params.push(("quote", char::from(self.quote).to_string()));
+        }
+        if self.header != defaults.header {
+            params.push(("header", self.header.to_string()));
+        }
+        params
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -1,6 +1,14 @@
-use serde::{Deserialize, Serialize};
+use ethers_core::types::{Bytes, H256};
+use serde::{
+    de::{self, DeserializeOwned},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
 use strum::AsRefStr;
 
+use super::ChainId;
+use crate::{utils::deserialize_lossy_string, Result};
+
 #[derive(AsRefStr, Clone, Copy, Debug, Default, Hash, Deserialize, Serialize, PartialEq, Eq)]
 pub enum TransactionType {
     #[default]
@@ -56,7 +64,11 @@ pub enum MarketEventType {
     Unregister = 0,
 }
 
+/// The status of a Spark order
+///
+/// The indexer may add event types, so this enum may grow in minor releases.
 #[derive(AsRefStr, Clone, Copy, Debug, Default, Hash, Deserialize, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum OrderEventType {
     #[default]
     #[serde(alias = "open")]
@@ -65,6 +77,10 @@ pub enum OrderEventType {
     Cancel = 1,
     #[serde(alias = "trade")]
     Trade = 2,
+    /// An event type introduced by the indexer after this client was released,
+    /// only found in responses
+    #[serde(other)]
+    Unknown = -1,
 }
 
 #[derive(AsRefStr, Clone, Copy, Debug, Default, Hash, Deserialize, Serialize, PartialEq, Eq)]
@@ -75,16 +91,29 @@ pub enum LimitType {
     FOK = 2,
 }
 
+/// The side of a Spark order
+///
+/// The indexer may add order types, so this enum may grow in minor releases.
 #[derive(AsRefStr, Clone, Copy, Debug, Default, Hash, Deserialize, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum OrderType {
     #[default]
     #[serde(alias = "buy")]
     Buy = 0,
     #[serde(alias = "sell")]
     Sell = 1,
+    /// An order type introduced by the indexer after this client was released,
+    /// only found in responses
+    #[serde(other)]
+    Unknown = -1,
 }
 
+/// The kind of a Fuel receipt, sent by name in
+/// [`GetFuelReceiptsRequest::receipt_type__in`](crate::requests::fuel::GetFuelReceiptsRequest::receipt_type__in)
+///
+/// Fuel may add receipt kinds, so this enum may grow in minor releases.
 #[derive(AsRefStr, Clone, Copy, Debug, Default, Hash, Deserialize, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ReceiptType {
     #[default]
     Call = 0,
@@ -108,3 +137,341 @@ pub enum MessageType {
     Incoming = 0,
     Outgoing = 1,
 }
+
+/// A block as delivered by the `fuel/blocks` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct FuelBlock {
+    pub chain: ChainId,
+    #[serde(alias = "block_number")]
+    pub height: u64,
+    #[serde(alias = "block_hash")]
+    pub id: H256,
+    /// The last Ethereum block processed by the block producer
+    #[serde(alias = "da_block_number")]
+    pub da_height: u64,
+    pub transactions_root: H256,
+    pub prev_root: H256,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub producer: Option<H256>,
+}
+
+/// An ABI-decoded log as delivered by the `fuel/logs/decoded` dataset
+///
+/// The envelope is typed, the shape of `data` depends on the emitting contract's ABI
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct DecodedLog {
+    pub chain: ChainId,
+    pub block_number: u64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_id: H256,
+    pub receipt_index: u64,
+    #[serde(alias = "id")]
+    pub contract_id: H256,
+    #[serde(alias = "name")]
+    pub event_name: String,
+    #[serde(default)]
+    pub data: Value,
+}
+
+impl DecodedLog {
+    /// Maps the decoded body into `T`, for callers that know the emitting contract's ABI
+    pub fn decode_into<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(T::deserialize(&self.data)?)
+    }
+}
+
+/// An order event as delivered by the `fuel/spark/orders` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct SparkOrder {
+    pub chain: ChainId,
+    pub block_number: u64,
+    pub timestamp: u64,
+    #[serde(rename = "market_id")]
+    pub market: H256,
+    pub order_id: H256,
+    #[serde(rename = "user")]
+    pub trader: H256,
+    #[serde(rename = "order_type")]
+    pub side: OrderType,
+    #[serde(rename = "event_type")]
+    pub status: OrderEventType,
+    #[serde(default)]
+    pub limit_type: Option<LimitType>,
+    #[serde(default)]
+    pub asset: Option<H256>,
+    pub price: u64,
+    #[serde(rename = "amount")]
+    pub size: u64,
+}
+
+/// An asset as delivered by the `fuel/src20` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Src20Asset {
+    pub chain: ChainId,
+    pub block_number: u64,
+    pub asset_id: H256,
+    pub contract_id: H256,
+    #[serde(default, deserialize_with = "deserialize_lossy_string")]
+    pub symbol: String,
+    #[serde(default, deserialize_with = "deserialize_lossy_string")]
+    pub name: String,
+    pub decimals: u8,
+    /// Absent until the contract has reported a supply
+    #[serde(default)]
+    pub total_supply: Option<u64>,
+}
+
+/// Fields shared by every receipt delivered by the `fuel/receipts` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct ReceiptHeader {
+    pub chain: ChainId,
+    pub block_number: u64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_id: H256,
+    pub receipt_index: u64,
+}
+
+/// A Fuel receipt, keyed on its `receipt_type`
+///
+/// Receipt kinds this client does not know about yet are kept as [`Receipt::Other`].
+/// A receipt of a known kind that does not match it fails to deserialize.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "receipt_type")]
+pub enum Receipt {
+    Call(CallReceipt),
+    Return(ReturnReceipt),
+    ReturnData(ReturnDataReceipt),
+    Panic(PanicReceipt),
+    Revert(RevertReceipt),
+    Log(LogReceipt),
+    LogData(LogDataReceipt),
+    Transfer(TransferReceipt),
+    TransferOut(TransferOutReceipt),
+    ScriptResult(ScriptResultReceipt),
+    MessageOut(MessageOutReceipt),
+    Mint(MintReceipt),
+    Burn(BurnReceipt),
+    #[serde(untagged)]
+    Other(Value),
+}
+
+impl Receipt {
+    /// Returns the shared receipt fields, or `None` for unknown receipt kinds
+    pub fn header(&self) -> Option<&ReceiptHeader> {
+        match self {
+            Receipt::Call(r) => Some(&r.header),
+            Receipt::Return(r) => Some(&r.header),
+            Receipt::ReturnData(r) => Some(&r.header),
+            Receipt::Panic(r) => Some(&r.header),
+            Receipt::Revert(r) => Some(&r.header),
+            Receipt::Log(r) => Some(&r.header),
+            Receipt::LogData(r) => Some(&r.header),
+            Receipt::Transfer(r) => Some(&r.header),
+            Receipt::TransferOut(r) => Some(&r.header),
+            Receipt::ScriptResult(r) => Some(&r.header),
+            Receipt::MessageOut(r) => Some(&r.header),
+            Receipt::Mint(r) => Some(&r.header),
+            Receipt::Burn(r) => Some(&r.header),
+            Receipt::Other(_) => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Receipt {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        fn known<T, D>(value: Value) -> std::result::Result<T, D>
+        where
+            T: DeserializeOwned,
+            D: de::Error,
+        {
+            serde_json::from_value(value).map_err(D::custom)
+        }
+
+        let value = Value::deserialize(deserializer)?;
+        // only a kind that is not a `ReceiptType` falls back to `Other`
+        let kind = match value.get("receipt_type").map(ReceiptType::deserialize) {
+            Some(Ok(kind)) => kind,
+            Some(Err(_)) => return Ok(Receipt::Other(value)),
+            None => return Err(de::Error::missing_field("receipt_type")),
+        };
+        Ok(match kind {
+            ReceiptType::Call => Receipt::Call(known(value)?),
+            ReceiptType::Return => Receipt::Return(known(value)?),
+            ReceiptType::ReturnData => Receipt::ReturnData(known(value)?),
+            ReceiptType::Panic => Receipt::Panic(known(value)?),
+            ReceiptType::Revert => Receipt::Revert(known(value)?),
+            ReceiptType::Log => Receipt::Log(known(value)?),
+            ReceiptType::LogData => Receipt::LogData(known(value)?),
+            ReceiptType::Transfer => Receipt::Transfer(known(value)?),
+            ReceiptType::TransferOut => Receipt::TransferOut(known(value)?),
+            ReceiptType::ScriptResult => Receipt::ScriptResult(known(value)?),
+            ReceiptType::MessageOut => Receipt::MessageOut(known(value)?),
+            ReceiptType::Mint => Receipt::Mint(known(value)?),
+            ReceiptType::Burn => Receipt::Burn(known(value)?),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct CallReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    pub to: H256,
+    pub amount: u64,
+    pub asset_id: H256,
+    pub gas: u64,
+    pub param1: u64,
+    pub param2: u64,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct ReturnReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    pub val: u64,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct ReturnDataReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    pub ptr: u64,
+    pub len: u64,
+    pub digest: H256,
+    #[serde(default)]
+    pub data: Option<Bytes>,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct PanicReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    pub reason: u64,
+    pub pc: u64,
+    pub is: u64,
+    #[serde(default)]
+    pub contract_id: Option<H256>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct RevertReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    pub ra: u64,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct LogReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    pub ra: u64,
+    pub rb: u64,
+    pub rc: u64,
+    pub rd: u64,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct LogDataReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    pub ra: u64,
+    pub rb: u64,
+    pub ptr: u64,
+    pub len: u64,
+    pub digest: H256,
+    #[serde(default)]
+    pub data: Option<Bytes>,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct TransferReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    /// The receiving contract
+    pub to: H256,
+    pub amount: u64,
+    pub asset_id: H256,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct TransferOutReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub id: H256,
+    /// The receiving address
+    pub to: H256,
+    pub amount: u64,
+    pub asset_id: H256,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct ScriptResultReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub result: u64,
+    pub gas_used: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct MessageOutReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub sender: H256,
+    pub recipient: H256,
+    pub amount: u64,
+    pub nonce: H256,
+    pub len: u64,
+    pub digest: H256,
+    #[serde(default)]
+    pub data: Option<Bytes>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct MintReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub sub_id: H256,
+    pub contract_id: H256,
+    pub val: u64,
+    pub pc: u64,
+    pub is: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct BurnReceipt {
+    #[serde(flatten)]
+    pub header: ReceiptHeader,
+    pub sub_id: H256,
+    pub contract_id: H256,
+    pub val: u64,
+    pub pc: u64,
+    pub is: u64,
+}
@@ -0,0 +1,23 @@
+use ethers_core::types::{Address, Bytes, H256, U64};
+use serde::{Deserialize, Serialize};
+
+use super::ChainId;
+
+/// A log record as delivered by the `logs` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Log {
+    pub chain: ChainId,
+    pub block_number: U64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    pub log_index: U64,
+    pub address: Address,
+    #[serde(default)]
+    pub topics: Vec<H256>,
+    /// Unindexed event data, empty for events without unindexed arguments
+    #[serde(default)]
+    pub data: Bytes,
+    /// Set if the log was removed due to a chain reorganization
+    #[serde(default)]
+    pub removed: bool,
+}
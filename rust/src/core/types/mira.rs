@@ -1,5 +1,8 @@
+use ethers_core::types::H256;
 use serde::{Deserialize, Serialize};
 
+use super::ChainId;
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum EventType {
     Mint,
@@ -56,3 +59,35 @@ impl TryFrom<i32> for Side {
         }
     }
 }
+
+/// A swap as delivered by the `mira/v1/swaps` dataset
+///
+/// Amounts are u64 on Fuel today, they are widened to u128 to stay safe against future assets
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct MiraSwap {
+    pub chain: ChainId,
+    pub block_number: u64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_id: H256,
+    pub pool_address: H256,
+    #[serde(rename = "asset0_address")]
+    pub asset0: H256,
+    #[serde(rename = "asset1_address")]
+    pub asset1: H256,
+    pub amount0_in: u128,
+    pub amount1_in: u128,
+    pub amount0_out: u128,
+    pub amount1_out: u128,
+    /// True if asset0 was bought with asset1
+    pub is_buy: bool,
+    #[serde(alias = "sender")]
+    pub trader: H256,
+    pub recipient: H256,
+}
+
+impl MiraSwap {
+    /// Returns the pool this swap went through, as `(pool, asset0, asset1)`
+    pub fn pool_id(&self) -> (H256, H256, H256) {
+        (self.pool_address, self.asset0, self.asset1)
+    }
+}
@@ -8,12 +8,21 @@ use serde::{
 };
 use strum::{AsRefStr, EnumString};
 
+pub mod blocks;
+pub mod btc;
+pub mod curve;
+pub mod erc20;
 pub mod format;
 pub mod fuel;
+pub mod logs;
 pub mod mira;
+pub mod page;
 pub mod query;
 pub mod status;
+pub mod transfers;
+pub mod txs;
 pub mod uniswap_v2;
+pub mod uniswap_v3;
 
 #[derive(
     AsRefStr, EnumString, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, FromPrimitiveDerive,
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// Bounds a response to a page of records, sent as query parameters. See
+/// [`Client::get_logs_page`](crate::Client::get_logs_page).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct PageOptions {
+    /// The most records in the page, the default of the server if `None`.
+    /// Conflicts with a limit set on the request itself.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    /// Continues after the page that returned the cursor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Cursor>,
+    /// Skips this many records, for endpoints that page by offset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+impl PageOptions {
+    /// The page of at most `limit` records after `cursor`, or the first one
+    pub fn after(cursor: Option<Cursor>, limit: u64) -> Self {
+        Self {
+            limit: Some(limit),
+            cursor,
+            offset: None,
+        }
+    }
+}
+
+/// An opaque token the server returns to continue after a page
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Cursor(String);
+
+impl Cursor {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
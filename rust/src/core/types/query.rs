@@ -1,5 +1,6 @@
 use std::ops::Mul;
 
+use lazy_static::lazy_static;
 use regex::Regex;
 
 #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -9,14 +10,21 @@ pub enum Bound {
     /// The range should start/end at the latest block height
     #[default]
     Latest,
-    // The bound is defined from the latest block height
+    /// The range starts/ends this many blocks before the latest block height,
+    /// e.g. `FromLatest(1000)` for the last 1000 blocks. Sent as `-1000`.
+    ///
+    /// Resumed requests continue from the concrete block they got to, rather
+    /// than from the latest height at the time.
     FromLatest(u64),
+    /// The range starts/ends at the first block the server has
+    Earliest,
     /// Real-time
     Subscribe,
 }
 
 impl Bound {
     const LATEST: &'static str = "latest";
+    const EARLIEST: &'static str = "earliest";
     const NONE: &'static str = "none";
 
     pub const fn none() -> Self {
@@ -29,6 +37,9 @@ impl PartialOrd for Bound {
         use std::cmp::Ordering;
 
         match (self, other) {
+            (Self::Earliest, Self::Earliest) => Some(Ordering::Equal),
+            (Self::Earliest, _) => Some(Ordering::Less),
+            (_, Self::Earliest) => Some(Ordering::Greater),
             (Self::Exact(lhs), Self::Exact(rhs)) => lhs.partial_cmp(rhs),
             (Self::FromLatest(lhs), Self::FromLatest(rhs)) => lhs.partial_cmp(rhs),
             (Self::Latest, Self::Latest) => Some(Ordering::Equal),
@@ -48,6 +59,7 @@ impl serde::Serialize for Bound {
             Self::Exact(n) => serializer.serialize_i64(*n),
             Self::FromLatest(n) => serializer.serialize_i64(-(*n as i64)),
             Self::Latest => serializer.serialize_str(Self::LATEST),
+            Self::Earliest => serializer.serialize_str(Self::EARLIEST),
             Self::Subscribe => serializer.serialize_str(Self::NONE),
         }
     }
@@ -75,6 +87,7 @@ impl<'de> serde::Deserialize<'de> for Bound {
 
                 match v {
                     Bound::LATEST => return Ok(Bound::Latest),
+                    Bound::EARLIEST => return Ok(Bound::Earliest),
                     Bound::NONE => return Ok(Bound::Subscribe),
                     _ => {}
                 }
@@ -87,14 +100,9 @@ impl<'de> serde::Deserialize<'de> for Bound {
                     }
                 }
 
-                if let Ok(exact) = v.parse::<i64>() {
-                    return Ok(Bound::Exact(exact));
-                }
-
-                if let Ok(exact) = v.parse::<i64>() {
-                    if exact < 0 {
-                        return Ok(Bound::FromLatest(exact.unsigned_abs()));
-                    }
+                // query parameters are strings, including a serialized `FromLatest`
+                if let Ok(block) = v.parse::<i64>() {
+                    return self.visit_i64(block);
                 }
 
                 Err(E::invalid_value(serde::de::Unexpected::Str(v), &self))
@@ -110,6 +118,15 @@ impl<'de> serde::Deserialize<'de> for Bound {
                     Ok(Bound::Exact(v))
                 }
             }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i64::try_from(v)
+                    .map(Bound::Exact)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+            }
         }
 
         deserializer.deserialize_any(Visitor)
@@ -122,7 +139,114 @@ impl std::fmt::Debug for Bound {
             Self::Exact(n) => std::fmt::Debug::fmt(n, f),
             Self::FromLatest(n) => std::fmt::Debug::fmt(&(*n as i64).mul(-1), f),
             Self::Latest => f.write_str(Self::LATEST),
+            Self::Earliest => f.write_str(Self::EARLIEST),
             Self::Subscribe => f.write_str(Self::NONE),
         }
     }
 }
+
+lazy_static! {
+    static ref RFC3339: Regex =
+        Regex::new(r"^\d{4}-\d{2}-\d{2}[Tt ]\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:\d{2})$")
+            .unwrap();
+}
+
+/// A bound of a range by the time of its blocks rather than their height, sent
+/// as `from_timestamp` and `to_timestamp`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TimeBound {
+    /// Seconds since the Unix epoch
+    Timestamp(i64),
+    /// A date and time in RFC 3339, e.g. `2024-03-01T00:00:00Z`
+    Rfc3339(String),
+}
+
+impl TimeBound {
+    /// Fails if an RFC 3339 bound is malformed
+    pub(crate) fn check(&self) -> Result<(), String> {
+        match self {
+            Self::Rfc3339(time) if !RFC3339.is_match(time) => {
+                Err(format!("{time:?} is not an RFC 3339 date and time"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<i64> for TimeBound {
+    fn from(timestamp: i64) -> Self {
+        Self::Timestamp(timestamp)
+    }
+}
+
+impl serde::Serialize for TimeBound {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Timestamp(timestamp) => serializer.serialize_i64(*timestamp),
+            Self::Rfc3339(time) => serializer.serialize_str(time),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TimeBound {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = TimeBound;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("A Unix timestamp or an RFC 3339 date and time")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                if let Ok(timestamp) = v.parse::<i64>() {
+                    return Ok(TimeBound::Timestamp(timestamp));
+                }
+                if RFC3339.is_match(v) {
+                    return Ok(TimeBound::Rfc3339(v.to_string()));
+                }
+                Err(E::invalid_value(serde::de::Unexpected::Str(v), &self))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(TimeBound::Timestamp(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i64::try_from(v)
+                    .map(TimeBound::Timestamp)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// The order records are returned in, by block
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Oldest first, the default of the server
+    #[default]
+    Asc,
+    /// Newest first, e.g. with a `limit` for the most recent records
+    Desc,
+}
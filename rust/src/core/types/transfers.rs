@@ -0,0 +1,41 @@
+use ethers_core::types::{Address, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use super::ChainId;
+use crate::utils::deserialize_u256;
+
+/// The origin of a native value transfer
+#[derive(Clone, Copy, Debug, Default, Hash, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferKind {
+    /// A top level transaction transferring value
+    #[default]
+    Transaction,
+    /// A value transfer from within a contract call
+    Internal,
+    /// A contract sending its balance on self destruction
+    Selfdestruct,
+    /// A block or uncle reward
+    Reward,
+    /// A withdrawal from the beacon chain
+    Withdrawal,
+    /// A kind not known to this version of the client
+    #[serde(other)]
+    Unknown,
+}
+
+/// A native value transfer as delivered by the `transfers` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Transfer {
+    pub chain: ChainId,
+    pub block_number: U64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    pub from: Address,
+    pub to: Address,
+    /// The transferred amount in wei, sent either as decimal or hex string
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub value: U256,
+    #[serde(default, alias = "transfer_type")]
+    pub kind: TransferKind,
+}
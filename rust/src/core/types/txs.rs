@@ -0,0 +1,45 @@
+use ethers_core::types::{Address, Bytes, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use super::ChainId;
+
+/// A transaction record as delivered by the `transactions` dataset
+///
+/// Legacy transactions carry `gas_price`, while EIP-1559 transactions carry
+/// `max_fee_per_gas` and `max_priority_fee_per_gas` instead.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Transaction {
+    pub chain: ChainId,
+    pub block_number: U64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    #[serde(default)]
+    pub transaction_index: U64,
+    #[serde(default)]
+    pub transaction_type: Option<U64>,
+    pub nonce: U256,
+    pub from: Address,
+    /// `None` for contract creation transactions
+    #[serde(default)]
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas: U256,
+    #[serde(default)]
+    pub gas_price: Option<U256>,
+    #[serde(default)]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(default)]
+    pub input: Bytes,
+    /// `1` for successful and `0` for reverted transactions
+    #[serde(default)]
+    pub status: Option<U64>,
+}
+
+impl Transaction {
+    /// Returns true if the transaction uses EIP-1559 fee fields
+    pub fn is_eip1559(&self) -> bool {
+        self.max_fee_per_gas.is_some()
+    }
+}
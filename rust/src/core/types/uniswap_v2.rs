@@ -1,6 +1,10 @@
+use ethers_core::types::{Address, H256, U256, U64};
 use serde::{Deserialize, Serialize};
 use strum::AsRefStr;
 
+use super::ChainId;
+use crate::utils::deserialize_u256;
+
 /// LpEvent is the event type of uniswap v2
 #[derive(AsRefStr, Clone, Copy, Debug, Default, Hash, Deserialize, Serialize, PartialEq, Eq)]
 pub enum ReserveEvent {
@@ -39,3 +43,46 @@ impl TryFrom<i32> for ReserveEvent {
         }
     }
 }
+
+/// A pair record as delivered by the `uniswap/v2/pairs` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Pair {
+    pub chain: ChainId,
+    /// The block the pair was created in
+    pub block_number: U64,
+    pub pair_address: Address,
+    pub factory_address: Address,
+    pub token0: Address,
+    pub token1: Address,
+    #[serde(default)]
+    pub token0_decimals: Option<u8>,
+    #[serde(default)]
+    pub token1_decimals: Option<u8>,
+}
+
+/// A reserve update as delivered by the `uniswap/v2/prices` dataset
+///
+/// `price` is derived from the reserves for convenience, the raw reserves are
+/// kept as integers so no precision is lost for tokens with few decimals.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
+pub struct Price {
+    pub chain: ChainId,
+    pub block_number: U64,
+    pub timestamp: u64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    #[serde(default)]
+    pub log_index: U64,
+    pub pair_address: Address,
+    #[serde(default)]
+    pub event: ReserveEvent,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub reserve0: U256,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub reserve1: U256,
+    pub price: f64,
+    #[serde(default)]
+    pub token0_address: Address,
+    #[serde(default)]
+    pub token1_address: Address,
+}
@@ -0,0 +1,103 @@
+use ethers_core::types::{Address, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use super::ChainId;
+use crate::utils::{deserialize_u256, u256_to_f64};
+
+/// A pool record as delivered by the `uniswap/v3/pools` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Pool {
+    pub chain: ChainId,
+    /// The block the pool was deployed in
+    pub block_number: U64,
+    pub pool_address: Address,
+    pub factory_address: Address,
+    pub token0: Address,
+    pub token1: Address,
+    /// The fee tier in hundredths of a bip, e.g. `3000` for 0.3%
+    pub fee: u32,
+    pub tick_spacing: i32,
+}
+
+/// A swap/price update as delivered by the `uniswap/v3/prices` dataset
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, Default)]
+pub struct PriceRecord {
+    pub chain: ChainId,
+    pub block_number: U64,
+    pub timestamp: u64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    #[serde(default)]
+    pub log_index: U64,
+    pub pool_address: Address,
+    #[serde(default)]
+    pub sender: Address,
+    #[serde(default)]
+    pub receiver: Address,
+    #[serde(default)]
+    pub amount0: f64,
+    #[serde(default)]
+    pub amount1: f64,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub sqrt_price_x96: U256,
+    pub tick: i32,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub liquidity: U256,
+    /// The price of token0 denominated in token1 as computed by the server
+    pub price: f64,
+}
+
+impl PriceRecord {
+    /// Converts `sqrt_price_x96` into the price of token0 denominated in token1,
+    /// adjusted for the decimals of both tokens
+    ///
+    /// The raw pool price is `(sqrt_price_x96 / 2^96)^2` in base units of the
+    /// tokens, which is then scaled by `10^(decimals0 - decimals1)`.
+    pub fn price_from_sqrt(&self, decimals0: u8, decimals1: u8) -> f64 {
+        let sqrt_price = u256_to_f64(self.sqrt_price_x96) / 2f64.powi(96);
+        sqrt_price * sqrt_price * 10f64.powi(i32::from(decimals0) - i32::from(decimals1))
+    }
+}
+
+/// A fee accounting update as delivered by the `uniswap/v3/fees` dataset
+///
+/// Fee growth accumulators are Q128.128 fixed point values that regularly
+/// exceed `u128`, so they are kept as [`U256`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct FeeRecord {
+    pub chain: ChainId,
+    pub block_number: U64,
+    #[serde(default)]
+    pub timestamp: u64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    pub pool_address: Address,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub fee_growth_global0_x128: U256,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub fee_growth_global1_x128: U256,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub protocol_fees_token0: U256,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub protocol_fees_token1: U256,
+}
+
+/// A liquidity position update as delivered by the `uniswap/v3/positions` dataset
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, Default)]
+pub struct Position {
+    pub chain: ChainId,
+    pub block_number: U64,
+    #[serde(alias = "transaction_hash")]
+    pub tx_hash: H256,
+    pub pool_address: Address,
+    pub owner: Address,
+    /// Ticks are signed, positions below price 1.0 have negative ticks
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    #[serde(deserialize_with = "deserialize_u256")]
+    pub liquidity: U256,
+    #[serde(default, deserialize_with = "deserialize_u256")]
+    pub tokens_owed0: U256,
+    #[serde(default, deserialize_with = "deserialize_u256")]
+    pub tokens_owed1: U256,
+}
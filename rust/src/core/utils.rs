@@ -1,32 +1,168 @@
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeSet, HashSet, VecDeque},
+    fmt,
+};
 
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use futures::StreamExt;
+use serde::{de, de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
 
-use ethers_core::types::Address;
+use ethers_core::types::{Address, U256};
 
-pub fn serialize_comma_separated<S, T, I>(value: T, serializer: S) -> Result<S::Ok, S::Error>
+use super::{
+    error::{Error, ResponseError, Result},
+    provider::{ResponseStream, StreamResponse},
+};
+
+/// The longest line [`json_lines`] buffers before giving up on the response
+pub const DEFAULT_MAX_LINE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Serializes a collection as a single comma-separated value, e.g.
+/// `ETH,ARB`, or as none if it is empty. See [`CommaSeparatedItems`] for the
+/// order of the items.
+pub fn serialize_comma_separated<S, C>(value: &C, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
-    T: IntoIterator<Item = I>,
-    I: Serialize,
+    C: CommaSeparatedItems + ?Sized,
 {
-    let mut buffer = String::new();
-    for (i, item) in value.into_iter().enumerate() {
-        if i > 0 {
-            buffer.push(',');
-        }
-        buffer.push_str(
-            serde_json::to_string(&item)
-                .unwrap()
-                .trim_start_matches('"')
-                .trim_end_matches('"'),
-        );
+    let items = value.comma_separated_items();
+    if items.is_empty() {
+        return serializer.serialize_none();
+    }
+    serializer.serialize_str(&items.join(","))
+}
+
+/// Deserializes a collection from a comma-separated value as sent by
+/// [`serialize_comma_separated`], or from a sequence
+pub fn deserialize_comma_separated<'de, D, C>(deserializer: D) -> Result<C, D::Error>
+where
+    D: Deserializer<'de>,
+    C: IntoIterator + FromIterator<<C as IntoIterator>::Item>,
+    C::Item: DeserializeOwned,
+{
+    CommaSeparated::deserialize(deserializer).map(|CommaSeparated(items)| items)
+}
+
+/// Collections that are sent as a single comma-separated value.
+///
+/// The order is stable, so identical requests have identical query strings:
+/// `Vec`s and slices keep the order of their items, and sets are sorted.
+pub trait CommaSeparatedItems {
+    /// The items as they are sent, without the quotes of strings
+    fn comma_separated_items(&self) -> Vec<String>;
+}
+
+fn comma_separated_item(item: &impl Serialize) -> String {
+    let item = serde_json::to_string(item).unwrap();
+    item.trim_start_matches('"')
+        .trim_end_matches('"')
+        .to_string()
+}
+
+impl<T: Serialize> CommaSeparatedItems for [T] {
+    fn comma_separated_items(&self) -> Vec<String> {
+        self.iter().map(comma_separated_item).collect()
     }
-    if !buffer.is_empty() {
-        return serializer.serialize_str(&buffer);
+}
+
+impl<T: Serialize> CommaSeparatedItems for Vec<T> {
+    fn comma_separated_items(&self) -> Vec<String> {
+        self.as_slice().comma_separated_items()
+    }
+}
+
+impl<T: Serialize> CommaSeparatedItems for BTreeSet<T> {
+    fn comma_separated_items(&self) -> Vec<String> {
+        self.iter().map(comma_separated_item).collect()
+    }
+}
+
+impl<T: Serialize, H> CommaSeparatedItems for HashSet<T, H> {
+    fn comma_separated_items(&self) -> Vec<String> {
+        let mut items: Vec<_> = self.iter().map(comma_separated_item).collect();
+        items.sort_unstable();
+        items
+    }
+}
+
+impl<C: CommaSeparatedItems + ?Sized> CommaSeparatedItems for &C {
+    fn comma_separated_items(&self) -> Vec<String> {
+        (**self).comma_separated_items()
+    }
+}
+
+/// A collection that is sent as a single comma-separated value, see
+/// [`serialize_comma_separated`] and [`deserialize_comma_separated`].
+///
+/// Items that contain a comma do not survive the round trip. The filters of
+/// the request structs stay public `HashSet`s with these functions as
+/// `serialize_with` and `deserialize_with`, which sends them in the same
+/// sorted order without changing their types.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CommaSeparated<C>(pub C);
+
+impl<C: CommaSeparatedItems> Serialize for CommaSeparated<C> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_comma_separated(&self.0, serializer)
     }
+}
+
+impl<'de, C> Deserialize<'de> for CommaSeparated<C>
+where
+    C: IntoIterator + FromIterator<<C as IntoIterator>::Item>,
+    C::Item: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor<C>(std::marker::PhantomData<C>);
+
+        impl<'de, C> de::Visitor<'de> for Visitor<C>
+        where
+            C: IntoIterator + FromIterator<<C as IntoIterator>::Item>,
+            C::Item: DeserializeOwned,
+        {
+            type Value = C;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a comma-separated string or a sequence")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<C, E> {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| {
+                        // strings as they are, anything else such as numbers
+                        // as JSON
+                        let string = serde_json::Value::String(item.to_string());
+                        serde_json::from_value(string)
+                            .or_else(|_| serde_json::from_str(item))
+                            .map_err(|err| E::custom(format!("invalid item {item:?}: {err}")))
+                    })
+                    .collect()
+            }
 
-    serializer.serialize_none()
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<C, A::Error> {
+                std::iter::from_fn(|| seq.next_element().transpose()).collect()
+            }
+
+            fn visit_none<E: de::Error>(self) -> Result<C, E> {
+                Ok(std::iter::empty().collect())
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<C, E> {
+                self.visit_none()
+            }
+        }
+
+        deserializer
+            .deserialize_any(Visitor(std::marker::PhantomData))
+            .map(CommaSeparated)
+    }
 }
 
 pub fn deserialize_addresses<'de, D>(deserializer: D) -> Result<HashSet<Address>, D::Error>
@@ -40,3 +176,266 @@ where
     }
     Ok(addresses)
 }
+
+/// Deserializes a [`U256`] from a JSON number, a decimal string or a `0x`
+/// prefixed hex string
+pub fn deserialize_u256<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(u64),
+        String(String),
+    }
+
+    match Repr::deserialize(deserializer)? {
+        Repr::Number(n) => Ok(U256::from(n)),
+        Repr::String(s) => parse_u256(&s).map_err(de::Error::custom),
+    }
+}
+
+/// Deserializes an optional [`U256`] like [`deserialize_u256`]
+pub fn deserialize_optional_u256<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(deserialize_with = "deserialize_u256")] U256);
+
+    Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(value)| value))
+}
+
+/// Serializes an optional [`U256`] as a decimal string, which unlike a number
+/// keeps values beyond 64 bits exact
+pub fn serialize_optional_decimal<S>(value: &Option<U256>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => serializer.serialize_str(&value.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn parse_u256(s: &str) -> Result<U256, String> {
+    let s = s.trim();
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(|err| err.to_string()),
+        None => U256::from_dec_str(s).map_err(|err| err.to_string()),
+    };
+    parsed.map_err(|err| format!("invalid U256 value {s:?}: {err}"))
+}
+
+/// Deserializes a string, replacing invalid UTF-8 sequences with
+/// [`U+FFFD`](std::char::REPLACEMENT_CHARACTER) instead of failing
+///
+/// On-chain metadata such as token symbols is not guaranteed to be valid UTF-8.
+pub fn deserialize_lossy_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct LossyString;
+
+    impl de::Visitor<'_> for LossyString {
+        type Value = String;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(v.to_owned())
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(v)
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(String::from_utf8_lossy(v).into_owned())
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(String::new())
+        }
+    }
+
+    deserializer.deserialize_bytes(LossyString)
+}
+
+/// Frames a [`Format::JsonStream`](crate::Format::JsonStream) response into
+/// newline separated records and deserializes each one of them.
+///
+/// Chunks are not guaranteed to end on a record boundary, so incomplete lines are
+/// buffered until the rest of the record arrives, up to [`DEFAULT_MAX_LINE_SIZE`].
+/// A record that fails to deserialize is yielded as an error without terminating
+/// the stream.
+pub fn json_lines<T>(stream: ResponseStream<Vec<u8>>) -> StreamResponse<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    json_lines_with_max_line_size(stream, DEFAULT_MAX_LINE_SIZE)
+}
+
+/// Like [`json_lines`], but ends the stream with [`Error::MaxLineSizeExceeded`]
+/// once a line grows beyond `max_line_size` bytes.
+pub fn json_lines_with_max_line_size<T>(
+    stream: ResponseStream<Vec<u8>>,
+    max_line_size: usize,
+) -> StreamResponse<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    frame_lines(stream, max_line_size, |line| {
+        serde_json::from_slice(line).map_err(Into::into)
+    })
+}
+
+/// Frames a [`Format::JsonStream`](crate::Format::JsonStream) response like
+/// [`json_lines`] and yields every record as a [`serde_json::Value`].
+///
+/// Useful to explore a dataset before writing a struct for it. A malformed
+/// record is yielded as [`Error::MalformedJsonLine`], which holds the line.
+pub fn json_values(stream: ResponseStream<Vec<u8>>) -> StreamResponse<serde_json::Value> {
+    frame_lines(stream, DEFAULT_MAX_LINE_SIZE, |line| {
+        serde_json::from_slice(line).map_err(|source| Error::MalformedJsonLine {
+            line: String::from_utf8_lossy(line).into_owned(),
+            source,
+        })
+    })
+}
+
+fn frame_lines<T>(
+    stream: ResponseStream<Vec<u8>>,
+    max_line_size: usize,
+    parse: fn(&[u8]) -> Result<T>,
+) -> StreamResponse<T>
+where
+    T: Send + 'static,
+{
+    struct State<T> {
+        buffer: Vec<u8>,
+        records: VecDeque<Result<T>>,
+        max_line_size: usize,
+        parse: fn(&[u8]) -> Result<T>,
+        done: bool,
+    }
+
+    impl<T> State<T> {
+        fn push_line(&mut self, line: &[u8]) {
+            if line.len() > self.max_line_size {
+                self.fail();
+                return;
+            }
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.iter().all(u8::is_ascii_whitespace) {
+                return;
+            }
+            self.records.push_back((self.parse)(line));
+        }
+
+        fn push_chunk(&mut self, chunk: &[u8]) {
+            self.buffer.extend_from_slice(chunk);
+            let Some(end) = self.buffer.iter().rposition(|b| *b == b'\n') else {
+                if self.buffer.len() > self.max_line_size {
+                    self.fail();
+                }
+                return;
+            };
+            let rest = self.buffer.split_off(end + 1);
+            let complete = std::mem::replace(&mut self.buffer, rest);
+            for line in complete.split(|b| *b == b'\n') {
+                if self.done {
+                    return;
+                }
+                self.push_line(line);
+            }
+            if self.buffer.len() > self.max_line_size {
+                self.fail();
+            }
+        }
+
+        fn finish(&mut self) {
+            self.done = true;
+            let rest = std::mem::take(&mut self.buffer);
+            self.push_line(&rest);
+        }
+
+        fn fail(&mut self) {
+            self.done = true;
+            self.buffer = Vec::new();
+            self.records
+                .push_back(Err(Error::MaxLineSizeExceeded(self.max_line_size)));
+        }
+    }
+
+    let state = State {
+        buffer: Vec::new(),
+        records: VecDeque::new(),
+        max_line_size,
+        parse,
+        done: false,
+    };
+
+    let records = futures::stream::unfold(
+        (ResponseError::map_stream(stream), state),
+        |(mut stream, mut state)| async move {
+            loop {
+                if let Some(record) = state.records.pop_front() {
+                    return Some((record, (stream, state)));
+                }
+                if state.done {
+                    return None;
+                }
+                match stream.next().await {
+                    Some(Ok(chunk)) => state.push_chunk(&chunk),
+                    Some(Err(err)) => state.records.push_back(Err(err)),
+                    None => state.finish(),
+                }
+            }
+        },
+    );
+
+    Ok(records.boxed())
+}
+
+/// The nearest float to `value`, which may lose precision but never overflows
+pub(crate) fn u256_to_f64(value: U256) -> f64 {
+    value
+        .0
+        .iter()
+        .rev()
+        .fold(0.0, |acc, limb| acc * 2f64.powi(64) + *limb as f64)
+}
+
+/// The block of a JSON record, given as a number or a decimal or hex string
+pub(crate) fn block_number(line: &[u8]) -> Option<i64> {
+    #[derive(Deserialize)]
+    struct Record {
+        block_number: serde_json::Value,
+    }
+
+    let record = serde_json::from_slice::<Record>(line).ok()?;
+    match record.block_number {
+        serde_json::Value::Number(number) => number.as_i64(),
+        serde_json::Value::String(number) => match number.strip_prefix("0x") {
+            Some(hex) => i64::from_str_radix(hex, 16).ok(),
+            None => number.parse().ok(),
+        },
+        _ => None,
+    }
+}
@@ -13,14 +13,47 @@ pub use ::{futures, reqwest, tokio, tokio_tungstenite, tungstenite, url};
 
 pub use ethers_core::types::Address;
 
+#[cfg(feature = "avro")]
+#[doc(inline)]
+pub use crate::core::avro;
+#[cfg(feature = "datafusion")]
+#[doc(inline)]
+pub use crate::core::datafusion;
+#[cfg(feature = "parquet")]
+#[doc(inline)]
+pub use crate::core::parquet;
+#[cfg(feature = "proto")]
+#[doc(inline)]
+pub use crate::core::proto;
+#[cfg(any(feature = "clickhouse", feature = "compression", feature = "parquet"))]
+#[doc(inline)]
+pub use crate::core::sinks;
 #[doc(inline)]
 pub use crate::core::{
+    arrow,
     builder::ClientBuilder,
     client::Client,
+    csv,
+    download::{Checkpoint, DownloadSummary, FileCheckpoint, ResumableRequest},
     error::{Error, Result},
+    ext::StreamResponseExt,
     provider, requests,
-    types::{format::Format, query, ChainId},
+    types::{
+        format::{CsvOptions, Format},
+        page::{Cursor, PageOptions},
+        query, ChainId,
+    },
     utils,
 };
 #[doc(inline)]
-pub use crate::providers::{http::HttpProvider, ws::Operation, ws::WsProvider};
+pub use crate::providers::{
+    http::{
+        HttpConfig, HttpProvider, RedirectPolicy, RequestMethod, RetryAfterPolicy, RetryOn,
+        RetryPolicy,
+    },
+    ws::{
+        ConnectionEvent, CredentialsProvider, FlushPolicy, Operation, OverflowPolicy,
+        ReconnectPolicy, Subscription, SubscriptionStats, WsConfig, WsProvider,
+    },
+    Failback, Identity, Proxy, RateLimitConfig, RateLimitInfo, TlsBackend, TlsConfig,
+};
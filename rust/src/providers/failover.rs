@@ -0,0 +1,88 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// When a provider that failed over to a fallback endpoint goes back to the
+/// primary one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Failback {
+    /// Stays on the fallback until it fails as well
+    Sticky,
+    /// Tries the primary again once this long has passed since failing over,
+    /// the default with 5 minutes
+    After(Duration),
+}
+
+impl Default for Failback {
+    fn default() -> Self {
+        Self::After(Duration::from_secs(300))
+    }
+}
+
+/// The endpoints of a provider in order of preference, and which one requests
+/// go to. Shared by the clones of the provider.
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    endpoints: Vec<String>,
+    failback: Failback,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    current: usize,
+    /// When the provider left the primary endpoint
+    failed_over_at: Option<Instant>,
+}
+
+impl Endpoints {
+    pub(crate) fn new(primary: String, fallbacks: Vec<String>, failback: Failback) -> Self {
+        Self {
+            endpoints: std::iter::once(primary).chain(fallbacks).collect(),
+            failback,
+            state: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub(crate) fn get(&self, index: usize) -> &str {
+        &self.endpoints[index]
+    }
+
+    /// The endpoint the last request or connection went to
+    pub(crate) fn active(&self) -> &str {
+        self.get(self.state.lock().unwrap().current)
+    }
+
+    /// The index of the endpoint the next request goes to, the primary again
+    /// once the [`Failback`] is due
+    pub(crate) fn next(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        if let (Failback::After(after), Some(failed_over_at)) =
+            (self.failback, state.failed_over_at)
+        {
+            if failed_over_at.elapsed() >= after {
+                *state = State::default();
+            }
+        }
+        state.current
+    }
+
+    /// Moves on from the endpoint at `index` after it failed, unless a
+    /// concurrent request did so already, and returns the next one
+    pub(crate) fn failed(&self, index: usize) -> usize {
+        let mut state = self.state.lock().unwrap();
+        if state.current == index {
+            state.current = (index + 1) % self.endpoints.len();
+            state.failed_over_at = match state.current {
+                0 => None,
+                _ => state.failed_over_at.or(Some(Instant::now())),
+            };
+        }
+        state.current
+    }
+}
@@ -1,33 +1,459 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use futures::{StreamExt, TryStreamExt};
+use futures::StreamExt;
 use reqwest::header;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, warn, Span};
 
 use crate::{
     core::{
-        error::{Error, Result},
-        types::format::Format,
+        error::{Error, ResponseError, Result},
+        types::{
+            format::Format,
+            page::{Cursor, PageOptions},
+        },
     },
     provider::{
         BtcProvider, ChainProvider, CurveProvider, Erc20Provider, FuelProvider, Provider,
-        StreamResponse, UniswapV2Provider, UniswapV3Provider,
+        ResponseStream, StreamResponse, UniswapV2Provider, UniswapV3Provider,
     },
     requests::{
         blocks, btc, curve, erc20, fuel, logs, mira, transfers, txs, uniswap_v2, uniswap_v3,
+        HasBlockBounds,
     },
     ChainId,
 };
 
+use super::{
+    failover::Endpoints,
+    rate_limit::{retry_after, RateLimitConfig, RateLimitInfo},
+    Failback, Proxy, TlsConfig,
+};
+
 const API_PATH: &str = "v1/api/";
+/// The response header with the cursor of the next page of a paged request
+const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// How [`HttpProvider`] retries a request that failed before its response
+/// started.
+///
+/// The delay before a retry doubles from `initial_delay` up to `max_delay`, less
+/// a random part of up to half of it. A response that fails after it started is
+/// not retried, as its data would be delivered twice, see
+/// [`Error::StreamInterrupted`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How often a request is sent, including the first time
+    pub max_attempts: usize,
+    /// The delay before the first retry
+    pub initial_delay: Duration,
+    /// The longest delay between retries
+    pub max_delay: Duration,
+    /// Which failures are retried
+    pub retry_on: RetryOn,
+}
+
+impl RetryPolicy {
+    /// The delay before the given retry, counting from 1
+    pub fn delay(&self, retry: usize) -> Duration {
+        super::backoff(self.initial_delay, self.max_delay, retry)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            retry_on: RetryOn::default(),
+        }
+    }
+}
+
+/// How [`HttpProvider`] waits out a `429` or `503` response with a
+/// `Retry-After` header, before the response started and independent of the
+/// [`RetryPolicy`]. Only a delay in seconds is understood, not a date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryAfterPolicy {
+    /// How often a request is sent again after the server asked to wait
+    pub max_retries: usize,
+    /// The longest total wait for a request. A server asking for longer fails
+    /// the request right away.
+    pub max_wait: Duration,
+}
+
+impl Default for RetryAfterPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            max_wait: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryAfterPolicy {
+    /// How long to wait before sending the request again, if at all
+    fn wait(
+        &self,
+        response: &reqwest::Response,
+        retries: usize,
+        waited: Duration,
+    ) -> Option<Duration> {
+        let status = response.status();
+        if status != reqwest::StatusCode::TOO_MANY_REQUESTS
+            && status != reqwest::StatusCode::SERVICE_UNAVAILABLE
+        {
+            return None;
+        }
+        let wait = retry_after(response.headers())?;
+        (retries < self.max_retries && waited + wait <= self.max_wait).then_some(wait)
+    }
+}
+
+/// Which redirects [`HttpProvider`] follows. A redirected request is sent again
+/// with the credentials, so it is only followed to the same host or to a
+/// trusted one, and never from `https` to `http`. Any other redirect fails with
+/// [`Error::Redirect`] rather than being refused by the next host for lack of
+/// credentials. A `303 See Other` is followed with a `GET` without the body.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RedirectPolicy {
+    /// The most redirects followed for a request, 10 by default. `0` fails on
+    /// any redirect.
+    pub max_redirects: usize,
+    /// Other hosts that redirects are followed to, e.g. the regional hosts of a
+    /// gateway. None by default.
+    pub trusted_hosts: Vec<String>,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self {
+            max_redirects: 10,
+            trusted_hosts: Vec::new(),
+        }
+    }
+}
+
+impl RedirectPolicy {
+    /// Why a redirect from `from` to `to` is not followed, if it is not
+    fn refuse(&self, from: &reqwest::Url, to: &reqwest::Url, redirects: usize) -> Option<String> {
+        if redirects >= self.max_redirects {
+            return Some(format!("more than {} redirects", self.max_redirects));
+        }
+        if from.scheme() == "https" && to.scheme() != "https" {
+            return Some(format!("the redirect leaves https for {}", to.scheme()));
+        }
+        let host = to.host_str().unwrap_or_default();
+        let same_host = from.host_str() == to.host_str()
+            && from.port_or_known_default() == to.port_or_known_default();
+        let trusted = self
+            .trusted_hosts
+            .iter()
+            .any(|trusted| trusted.eq_ignore_ascii_case(host));
+        (!same_host && !trusted).then(|| format!("{host} is not a trusted host"))
+    }
+}
+
+/// The classes of failures a [`RetryPolicy`] retries, all by default
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryOn {
+    /// No connection was established, e.g. the name did not resolve
+    pub connect: bool,
+    /// The request timed out
+    pub timeout: bool,
+    /// The connection failed while sending the request or waiting for the
+    /// response, e.g. because it was reset
+    pub request: bool,
+    /// The server answered with a 5xx status or with 429 Too Many Requests
+    pub server_error: bool,
+}
+
+impl Default for RetryOn {
+    fn default() -> Self {
+        Self {
+            connect: true,
+            timeout: true,
+            request: true,
+            server_error: true,
+        }
+    }
+}
+
+impl RetryOn {
+    fn error(&self, err: &reqwest::Error) -> bool {
+        (self.connect && err.is_connect())
+            || (self.timeout && err.is_timeout())
+            || (self.request && err.is_request() && !err.is_connect() && !err.is_timeout())
+    }
+
+    fn status(&self, status: reqwest::StatusCode) -> bool {
+        self.server_error
+            && (status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+    }
+}
+
+/// How the parameters of a request are sent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RequestMethod {
+    /// As the query of a `GET`, the default
+    #[default]
+    Get,
+    /// As the JSON body of a `POST`
+    Post,
+    /// As the JSON body of a `POST` if the query would be longer than this many
+    /// bytes, e.g. for filters with thousands of addresses, which proxies
+    /// refuse with `414 URI Too Long` or truncate
+    PostAbove(usize),
+}
+
+/// The configuration of an [`HttpProvider`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpConfig {
+    /// How failed requests are retried, not at all by default. See
+    /// [`HttpProvider::with_retry`] to override it for some requests.
+    pub retry: Option<RetryPolicy>,
+    /// How long establishing a connection may take, before the request fails
+    /// with [`Error::ConnectTimeout`]
+    pub connect_timeout: Duration,
+    /// How long to wait for the response to start, before the request fails
+    /// with [`Error::Timeout`]. Unlimited by default, as a backfill may take the
+    /// server a while to start.
+    pub first_byte_timeout: Option<Duration>,
+    /// How long to wait for the next chunk of a response, before the stream
+    /// fails with [`Error::Stalled`]. Unlimited by default.
+    pub idle_timeout: Option<Duration>,
+    /// The proxy requests are sent through, none by default. Use
+    /// [`Proxy::Env`] to honour the `HTTPS_PROXY` environment variables.
+    pub proxy: Proxy,
+    /// Client certificates, additional root certificates and the TLS backend
+    pub tls: TlsConfig,
+    /// Headers sent with every request, e.g. to identify the caller in audit
+    /// logs. `Authorization` is refused with [`Error::InvalidHeader`] when the
+    /// provider has credentials, as it would replace them.
+    pub headers: Vec<(String, String)>,
+    /// The `User-Agent` sent with every request, none by default
+    pub user_agent: Option<String>,
+    /// How many idle connections to the server are kept for reuse, unlimited
+    /// by default. Backfills that run many requests at once should keep at
+    /// least as many as they run, so connections are not reopened between
+    /// requests.
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle connection is kept for reuse, 90 seconds by default.
+    /// Keep it below the keep-alive timeout of any load balancer in between,
+    /// or requests may be sent on connections it is closing, and fail with
+    /// `connection closed before message completed`. `None` keeps them forever.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Offers HTTP/2 when negotiating TLS, which multiplexes concurrent requests
+    /// on a single connection, and falls back to HTTP/1.1 if the server does
+    /// not take it. Plain `http` stays on HTTP/1.1 unless
+    /// [`http2_prior_knowledge`](Self::http2_prior_knowledge) is set. Off by
+    /// default.
+    pub http2: bool,
+    /// Speaks HTTP/2 without negotiating it first, which multiplexes all
+    /// requests on a single connection. Only for servers known to support it.
+    pub http2_prior_knowledge: bool,
+    /// How the parameters of a request are sent. The format and paging stay in
+    /// the query either way.
+    pub method: RequestMethod,
+    /// How the rate limit is read from responses, see
+    /// [`HttpProvider::rate_limit`]
+    pub rate_limit: RateLimitConfig,
+    /// How a `Retry-After` of the server is waited out. Off by default, so
+    /// that a busy server fails requests rather than holds them for a while,
+    /// with [`Error::RateLimited`] for a `429`.
+    pub retry_after: Option<RetryAfterPolicy>,
+    /// Endpoints in other regions, in the order requests fail over to them
+    /// when a connection cannot be established or the server answers with a
+    /// 5xx status. The endpoint that served the last request is
+    /// [`HttpProvider::endpoint`]. None by default.
+    pub fallback_endpoints: Vec<String>,
+    /// When requests go back to the primary endpoint after failing over
+    pub failback: Failback,
+    /// Sends `If-None-Match` and `If-Modified-Since` with a request identical to
+    /// an earlier one that returned an `ETag` or `Last-Modified`, and fails with
+    /// [`Error::NotModified`] when the server answers `304 Not Modified`. For
+    /// polling datasets that change slowly, e.g. curve tokens. Off by default.
+    pub conditional_requests: bool,
+    /// Which redirects are followed, only those to the same host by default
+    pub redirect: RedirectPolicy,
+}
 
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            retry: None,
+            connect_timeout: Duration::from_secs(10),
+            first_byte_timeout: None,
+            idle_timeout: None,
+            proxy: Proxy::None,
+            tls: TlsConfig::default(),
+            headers: Vec::new(),
+            user_agent: None,
+            pool_max_idle_per_host: usize::MAX,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            http2: false,
+            http2_prior_knowledge: false,
+            method: RequestMethod::Get,
+            rate_limit: RateLimitConfig::default(),
+            retry_after: None,
+            fallback_endpoints: Vec::new(),
+            failback: Failback::default(),
+            conditional_requests: false,
+            redirect: RedirectPolicy::default(),
+        }
+    }
+}
+
+/// The most responses whose validators are kept for conditional requests
+const MAX_VALIDATORS: usize = 256;
+
+/// What identifies a request for conditional requests, its URL and body
+type RequestKey = (String, Option<Vec<u8>>);
+
+/// The `ETag` and `Last-Modified` of a response
+#[derive(Clone, Debug, Default)]
+struct Validators {
+    etag: Option<header::HeaderValue>,
+    last_modified: Option<header::HeaderValue>,
+}
+
+#[derive(Clone)]
 pub struct HttpProvider {
     inner: reqwest::Client,
-    base_url: reqwest::Url,
+    /// The URL under which the API is served by each endpoint, the primary one
+    /// first
+    base_urls: Vec<reqwest::Url>,
+    endpoints: Arc<Endpoints>,
+    config: HttpConfig,
+    rate_limit: Arc<Mutex<Option<RateLimitInfo>>>,
+    /// The validators of earlier responses, see
+    /// [`HttpConfig::conditional_requests`]
+    validators: Arc<Mutex<HashMap<RequestKey, Validators>>>,
 }
 
 impl HttpProvider {
+    /// Connects like [`Provider::try_new`], with the given retry policy,
+    /// timeouts, proxy and headers
+    pub async fn try_new_with_config(
+        endpoint: String,
+        is_secure: bool,
+        username: Option<String>,
+        password: Option<String>,
+        config: HttpConfig,
+    ) -> Result<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let (Some(username), Some(password)) = (username, password) {
+            let auth = format!("{username}:{password}");
+            let encoded = BASE64.encode(auth);
+            headers.insert(
+                header::AUTHORIZATION,
+                header::HeaderValue::from_str(&format!("Basic {encoded}"))
+                    .expect("Only non-ascii chars result in an error"),
+            );
+        }
+
+        // decompressed as the response arrives, see `decompress`
+        #[cfg(feature = "compression")]
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            header::HeaderValue::from_static("gzip, zstd"),
+        );
+
+        let invalid = |name: &str, reason: String| Error::InvalidHeader {
+            name: name.to_string(),
+            reason,
+        };
+        for (name, value) in &config.headers {
+            let key =
+                header::HeaderName::from_str(name).map_err(|err| invalid(name, err.to_string()))?;
+            if key == header::AUTHORIZATION && headers.contains_key(header::AUTHORIZATION) {
+                return Err(invalid(
+                    name,
+                    "it would replace the credentials of the provider".into(),
+                ));
+            }
+            let value = header::HeaderValue::from_str(value)
+                .map_err(|err| invalid(name, err.to_string()))?;
+            headers.insert(key, value);
+        }
+        if let Some(user_agent) = &config.user_agent {
+            let value = header::HeaderValue::from_str(user_agent)
+                .map_err(|err| invalid("user-agent", err.to_string()))?;
+            headers.insert(header::USER_AGENT, value);
+        }
+
+        let scheme = if is_secure { "https" } else { "http" };
+        let base_urls = std::iter::once(&endpoint)
+            .chain(&config.fallback_endpoints)
+            .map(|endpoint| {
+                reqwest::Url::from_str(&super::endpoint_url(scheme, endpoint, API_PATH))
+            })
+            .collect::<std::result::Result<_, _>>()?;
+        let endpoints =
+            Endpoints::new(endpoint, config.fallback_endpoints.clone(), config.failback);
+
+        let builder = reqwest::ClientBuilder::new()
+            .default_headers(headers)
+            .https_only(is_secure)
+            .connect_timeout(config.connect_timeout)
+            // followed by `execute`, which knows where the credentials may go
+            .redirect(reqwest::redirect::Policy::none())
+            .pool_max_idle_per_host(config.pool_max_idle_per_host)
+            .pool_idle_timeout(config.pool_idle_timeout);
+        // the window grows with the throughput, so large responses are not
+        // held back by flow control
+        let builder = match (config.http2, config.http2_prior_knowledge) {
+            (_, true) => builder.http2_prior_knowledge().http2_adaptive_window(true),
+            (true, false) => builder.http2_adaptive_window(true),
+            (false, false) => builder.http1_only(),
+        };
+        let builder = config.tls.apply(builder)?;
+        let inner = config.proxy.apply(builder)?.build()?;
+
+        Ok(Self {
+            inner,
+            base_urls,
+            endpoints: Arc::new(endpoints),
+            config,
+            rate_limit: Arc::default(),
+            validators: Arc::default(),
+        })
+    }
+
+    /// Returns a provider on the same client that retries requests as `policy`
+    /// says, e.g. for long backfills, or `None` to not retry them
+    pub fn with_retry(&self, policy: Option<RetryPolicy>) -> Self {
+        let mut provider = self.clone();
+        provider.config.retry = policy;
+        provider
+    }
+
+    /// Returns a provider on the same client with other
+    /// [`first_byte_timeout`](HttpConfig::first_byte_timeout) and
+    /// [`idle_timeout`](HttpConfig::idle_timeout), e.g. a short one for live
+    /// polling. The connect timeout is shared by the client.
+    pub fn with_timeouts(
+        &self,
+        first_byte_timeout: Option<Duration>,
+        idle_timeout: Option<Duration>,
+    ) -> Self {
+        let mut provider = self.clone();
+        provider.config.first_byte_timeout = first_byte_timeout;
+        provider.config.idle_timeout = idle_timeout;
+        provider
+    }
+
     async fn request<R>(
         &self,
         url: reqwest::Url,
@@ -37,25 +463,656 @@ impl HttpProvider {
     where
         R: serde::Serialize,
     {
-        let raw_data_stream = self
-            .inner
-            .get(url)
-            .query(&request)
-            .query(&[("format", format)])
-            .send()
-            .await?
-            // .error_for_status()?
+        let (raw_data_stream, _) = self
+            .request_page(url, request, format, &PageOptions::default())
+            .await?;
+        Ok(raw_data_stream)
+    }
+
+    /// Sends a request over a range of blocks, see
+    /// [`HasBlockBounds::check_bounds`]
+    async fn request_range<R>(
+        &self,
+        url: reqwest::Url,
+        request: R,
+        format: Format,
+    ) -> StreamResponse<Vec<u8>>
+    where
+        R: HasBlockBounds + serde::Serialize,
+    {
+        request.check_bounds()?;
+        self.request(url, request, format).await
+    }
+
+    /// Sends a request like `request` for a page of the response, and returns
+    /// the cursor of the next page from the [`NEXT_CURSOR_HEADER`], if any
+    #[instrument(
+        level = "debug",
+        name = "request",
+        skip_all,
+        fields(
+            dataset = %dataset(&self.base_urls[0], &url),
+            format = format.as_str(),
+            endpoint = tracing::field::Empty,
+        ),
+    )]
+    async fn request_page<R>(
+        &self,
+        url: reqwest::Url,
+        request: R,
+        format: Format,
+        page: &PageOptions,
+    ) -> Result<(ResponseStream<Vec<u8>>, Option<Cursor>)>
+    where
+        R: serde::Serialize,
+    {
+        let policy = self.config.retry.unwrap_or(RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        });
+
+        let post = match self.config.method {
+            RequestMethod::Get => false,
+            RequestMethod::Post => true,
+            RequestMethod::PostAbove(limit) => {
+                let query = self.inner.get(url.clone()).query(&request).build()?;
+                query.url().query().map_or(0, str::len) > limit
+            }
+        };
+        let body = match post {
+            true => Some(serde_json::to_vec(&request)?),
+            false => None,
+        };
+
+        let mut attempt = 1;
+        // the retries asked for with a `Retry-After`, and how long they waited
+        let (mut waits, mut waited) = (0, Duration::ZERO);
+        // the endpoint the request is sent to, and how often the current attempt
+        // failed over to the next one
+        let (mut index, mut failovers) = (self.endpoints.next(), 0);
+        let mut key = None;
+        let response = loop {
+            Span::current().record("endpoint", self.endpoints.get(index));
+            let url = self.rebase(&url, index);
+            let builder = match &body {
+                Some(body) => self
+                    .inner
+                    .post(url.clone())
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(body.clone()),
+                None => self.inner.get(url.clone()).query(&request),
+            };
+            let mut sent = builder
+                .query(page)
+                .query(&[("format", format)])
+                .query(&format.options())
+                // proxies may strip the query parameter, but keep the header
+                .header(header::ACCEPT, format.content_type())
+                .build()?;
+            if self.config.conditional_requests {
+                let request_key = (sent.url().to_string(), body.clone());
+                self.add_validators(&mut sent, &request_key);
+                key = Some(request_key);
+            }
+            if attempt == 1 {
+                let body = body.as_deref().map(String::from_utf8_lossy);
+                debug!(
+                    method = %sent.method(),
+                    url = %redact(sent.url()),
+                    body = body.as_deref().unwrap_or_default(),
+                    "sending request"
+                );
+            }
+            self.throttle().await;
+            let sent = self.execute(sent);
+            let response = match self.config.first_byte_timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, sent).await {
+                    Ok(response) => response,
+                    Err(_) => Err(Error::Timeout(timeout)),
+                },
+                None => sent.await,
+            };
+            if let Ok(response) = &response {
+                if let Some(info) = self.config.rate_limit.parse(response.headers()) {
+                    *self.rate_limit.lock().unwrap() = Some(info);
+                }
+                let wait = self
+                    .config
+                    .retry_after
+                    .and_then(|policy| policy.wait(response, waits, waited));
+                if let Some(wait) = wait {
+                    warn!("{url} answered {}, retrying in {wait:?}", response.status());
+                    tokio::time::sleep(wait).await;
+                    waits += 1;
+                    waited += wait;
+                    continue;
+                }
+            }
+            let endpoint_failed = match &response {
+                Ok(response) => response.status().is_server_error(),
+                Err(Error::Reqwest(err)) => err.is_connect(),
+                Err(_) => false,
+            };
+            if endpoint_failed {
+                let next = self.endpoints.failed(index);
+                if failovers + 1 < self.endpoints.len() {
+                    warn!(
+                        "{} failed, failing over to {}",
+                        self.endpoints.get(index),
+                        self.endpoints.get(next)
+                    );
+                    (index, failovers) = (next, failovers + 1);
+                    continue;
+                }
+                index = next;
+            }
+
+            let retry = match &response {
+                Ok(response) => policy.retry_on.status(response.status()),
+                Err(Error::Reqwest(err)) => policy.retry_on.error(err),
+                Err(Error::Timeout(_)) => policy.retry_on.timeout,
+                Err(_) => false,
+            };
+            if !retry || attempt >= policy.max_attempts {
+                break response.map_err(|err| match err {
+                    Error::Reqwest(err) if err.is_connect() && err.is_timeout() => {
+                        Error::ConnectTimeout(self.config.connect_timeout)
+                    }
+                    err => err,
+                })?;
+            }
+
+            let delay = match &response {
+                // the server knows better when it accepts requests again
+                Ok(response) if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                    let retry_after = retry_after(response.headers()).unwrap_or_default();
+                    policy.delay(attempt).max(retry_after)
+                }
+                _ => policy.delay(attempt),
+            };
+            match &response {
+                Ok(response) => warn!(
+                    "{url} answered {}, retrying in {delay:?}",
+                    response.status()
+                ),
+                Err(err) => warn!("Request to {url} failed, retrying in {delay:?}: {err}"),
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+            failovers = 0;
+        };
+        debug!(status = response.status().as_u16(), "received response");
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Err(Error::NotModified);
+        }
+        let response = error_for_status(response).await?;
+        if let Some(key) = key {
+            self.keep_validators(key, &response);
+        }
+        check_content_type(&response, format)?;
+        let next = response
+            .headers()
+            .get(NEXT_CURSOR_HEADER)
+            .and_then(|cursor| cursor.to_str().ok())
+            .filter(|cursor| !cursor.is_empty())
+            .map(Cursor::new);
+
+        #[cfg(feature = "compression")]
+        let encoding = response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .map(|encoding| String::from_utf8_lossy(encoding.as_bytes()).to_ascii_lowercase());
+        let chunks = response
             .bytes_stream()
-            .map_err(Error::from)
-            .map_ok(|bytes| bytes.to_vec())
+            .map(|chunk| chunk.map(|bytes| bytes.to_vec()).map_err(Error::from))
             .boxed();
+        #[cfg(feature = "compression")]
+        let chunks = decompress(chunks, encoding.as_deref())?;
+        let chunks = match self.config.idle_timeout {
+            Some(timeout) => idle_timeout(chunks, timeout),
+            None => chunks,
+        };
 
-        Ok(raw_data_stream)
+        // the bytes delivered so far are reported with a failure, to resume after
+        let delivered = Arc::new(Delivered {
+            bytes: AtomicU64::new(0),
+            span: Span::current(),
+        });
+        let raw_data_stream = chunks
+            .map(move |chunk| match chunk {
+                Ok(bytes) => {
+                    delivered
+                        .bytes
+                        .fetch_add(bytes.len() as u64, Ordering::Relaxed);
+                    Ok(bytes)
+                }
+                Err(err) => Err(Error::StreamInterrupted {
+                    bytes: delivered.bytes.load(Ordering::Relaxed),
+                    source: Box::new(err),
+                }),
+            })
+            .boxed();
+
+        Ok((raw_data_stream, next))
+    }
+
+    /// Holds back a request while the rate limit is nearly exhausted, see
+    /// [`RateLimitConfig::throttle_below`]
+    async fn throttle(&self) {
+        let Some(threshold) = self.config.rate_limit.throttle_below else {
+            return;
+        };
+        let delay = self
+            .rate_limit
+            .lock()
+            .unwrap()
+            .and_then(|info| info.throttle(threshold));
+        if let Some(delay) = delay {
+            debug!(?delay, "throttling until the rate limit resets");
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Sends `request`, and follows the redirects the [`RedirectPolicy`] allows
+    async fn execute(&self, mut request: reqwest::Request) -> Result<reqwest::Response> {
+        let mut redirects = 0;
+        loop {
+            let next = request.try_clone();
+            let response = self.inner.execute(request).await?;
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok());
+            let (Some(location), Some(mut next)) = (location, next) else {
+                return Ok(response);
+            };
+            if !matches!(response.status().as_u16(), 301 | 302 | 303 | 307 | 308) {
+                return Ok(response);
+            }
+
+            let to = next.url().join(location)?;
+            if let Some(reason) = self.config.redirect.refuse(next.url(), &to, redirects) {
+                return Err(Error::Redirect {
+                    location: redact(&to).to_string(),
+                    reason,
+                });
+            }
+            debug!(status = response.status().as_u16(), location = %redact(&to), "following redirect");
+            if response.status() == reqwest::StatusCode::SEE_OTHER
+                && next.method() != reqwest::Method::HEAD
+            {
+                *next.method_mut() = reqwest::Method::GET;
+                *next.body_mut() = None;
+                let headers = next.headers_mut();
+                headers.remove(header::CONTENT_TYPE);
+                headers.remove(header::CONTENT_LENGTH);
+            }
+            *next.url_mut() = to;
+            request = next;
+            redirects += 1;
+        }
+    }
+
+    /// Asks the server to answer `304 Not Modified` if the response to `key`
+    /// did not change
+    fn add_validators(&self, request: &mut reqwest::Request, key: &RequestKey) {
+        let validators = self.validators.lock().unwrap().get(key).cloned();
+        let Some(validators) = validators else {
+            return;
+        };
+        let headers = request.headers_mut();
+        if let Some(etag) = validators.etag {
+            headers.insert(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = validators.last_modified {
+            headers.insert(header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    /// Keeps the validators of the response to `key` for the next identical
+    /// request
+    fn keep_validators(&self, key: RequestKey, response: &reqwest::Response) {
+        let validators = Validators {
+            etag: response.headers().get(header::ETAG).cloned(),
+            last_modified: response.headers().get(header::LAST_MODIFIED).cloned(),
+        };
+        let mut cache = self.validators.lock().unwrap();
+        if validators.etag.is_none() && validators.last_modified.is_none() {
+            cache.remove(&key);
+            return;
+        }
+        if cache.len() >= MAX_VALIDATORS && !cache.contains_key(&key) {
+            // any entry makes room, the cache is only for a few polled requests
+            if let Some(evicted) = cache.keys().next().cloned() {
+                cache.remove(&evicted);
+            }
+        }
+        cache.insert(key, validators);
+    }
+
+    /// The rate limit the server reported with its last response, `None` if it
+    /// sent none yet. Shared by the clones of the provider.
+    pub fn rate_limit(&self) -> Option<RateLimitInfo> {
+        *self.rate_limit.lock().unwrap()
+    }
+
+    /// The endpoint the last request went to, e.g. for logging, see
+    /// [`HttpConfig::fallback_endpoints`]
+    pub fn endpoint(&self) -> &str {
+        self.endpoints.active()
     }
 
     fn url(&self, path: &str) -> Result<reqwest::Url> {
-        self.base_url.join(path).map_err(Error::from)
+        self.base_urls[0].join(path).map_err(Error::from)
+    }
+
+    /// `url` of the primary endpoint on the endpoint at `index`
+    fn rebase(&self, url: &reqwest::Url, index: usize) -> reqwest::Url {
+        let relative = url.as_str().strip_prefix(self.base_urls[0].as_str());
+        match (index, relative) {
+            (0, _) | (_, None) => url.clone(),
+            (_, Some(relative)) => self.base_urls[index]
+                .join(relative)
+                .unwrap_or_else(|_| url.clone()),
+        }
+    }
+
+    /// Sends a request to an endpoint under `v1/api/` by its path, e.g. one
+    /// this crate does not know yet, and returns the raw response.
+    ///
+    /// Fails with [`Error::InvalidPath`] if the path is absolute or contains a
+    /// `..` segment.
+    pub async fn raw_get(
+        &self,
+        path: &str,
+        params: impl serde::Serialize,
+        format: Format,
+    ) -> StreamResponse<Vec<u8>> {
+        let invalid = || Error::InvalidPath(path.to_string());
+
+        let traverses = path
+            .split(['/', '\\'])
+            .any(|segment| segment.to_ascii_lowercase().replace("%2e", ".") == "..");
+        if traverses || path.starts_with('/') {
+            return Err(invalid());
+        }
+        // a path with a scheme or host replaces the base url when joined
+        let url = self.url(path).map_err(|_| invalid())?;
+        if !url.as_str().starts_with(self.base_urls[0].as_str()) {
+            return Err(invalid());
+        }
+
+        self.request(url, params, format).await
+    }
+
+    /// Requests a page of logs, and returns it with the cursor of the next page
+    /// if the server sent one in the `x-next-cursor` header. See
+    /// [`Client::get_logs_page`](crate::Client::get_logs_page).
+    pub async fn get_logs_page_by_format(
+        &self,
+        request: logs::GetLogsRequest,
+        format: Format,
+        page: &PageOptions,
+    ) -> Result<(ResponseStream<Vec<u8>>, Option<Cursor>)> {
+        request.check_bounds()?;
+        // both would be sent as `limit=`, and the server would pick one
+        if request.limit.is_some() && page.limit.is_some() {
+            return Err(Error::InvalidRequest(
+                "limit is set on both the request and the page".to_string(),
+            ));
+        }
+        let url = self.url(ETHEREUM_LOGS_PATH)?;
+        self.request_page(url, request, format, page).await
+    }
+
+    /// Asks for the number of logs `request` would return, which may be an
+    /// estimate for large ranges, by sending it with `count_only=true`.
+    ///
+    /// Fails with [`Error::Unsupported`] if the server cannot count the
+    /// dataset.
+    pub async fn get_logs_count(&self, request: logs::GetLogsRequest) -> Result<u64> {
+        self.count(ETHEREUM_LOGS_PATH, request).await
+    }
+
+    /// Asks for the number of transfers `request` would return, like
+    /// [`HttpProvider::get_logs_count`]
+    pub async fn get_transfers_count(
+        &self,
+        request: transfers::GetTransfersRequest,
+    ) -> Result<u64> {
+        self.count(ETHEREUM_TRANSFERS_PATH, request).await
+    }
+
+    /// Asks for the number of ERC-20 transfers `request` would return, like
+    /// [`HttpProvider::get_logs_count`]
+    pub async fn get_erc20_transfers_count(
+        &self,
+        request: erc20::GetErc20TransfersRequest,
+    ) -> Result<u64> {
+        self.count(ERC20_TRANSFERS_PATH, request).await
     }
+
+    /// Sends `request` to the dataset at `path` with `count_only=true`, and
+    /// returns the count the server answers with, either as `{"count": n}` or
+    /// a bare number. Anything else, e.g. the records themselves because the
+    /// server ignored the parameter, means it cannot count the dataset.
+    async fn count<R>(&self, path: &'static str, request: R) -> Result<u64>
+    where
+        R: HasBlockBounds + Serialize,
+    {
+        request.check_bounds()?;
+        #[derive(Serialize)]
+        struct CountOnly<R> {
+            #[serde(flatten)]
+            request: R,
+            count_only: bool,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Count {
+            Object { count: u64 },
+            Number(u64),
+        }
+
+        let unsupported = || Error::Unsupported {
+            dataset: path,
+            operation: "counting",
+        };
+
+        let url = self.url(path)?;
+        let request = CountOnly {
+            request,
+            count_only: true,
+        };
+        let mut chunks = self.request(url, request, Format::Json).await?;
+        let mut body = Vec::new();
+        while let Some(chunk) = chunks.next().await {
+            body.extend_from_slice(&chunk?);
+            // records rather than a count, which are not read to the end
+            if body.len() > MAX_COUNT_BODY {
+                return Err(unsupported());
+            }
+        }
+
+        match serde_json::from_slice(&body) {
+            Ok(Count::Object { count } | Count::Number(count)) => Ok(count),
+            Err(_) => Err(unsupported()),
+        }
+    }
+}
+
+/// The longest response to a count request, longer ones are records
+const MAX_COUNT_BODY: usize = 1024;
+
+/// The bytes of a response delivered so far, which are logged once its stream
+/// is dropped
+struct Delivered {
+    bytes: AtomicU64,
+    span: Span,
+}
+
+impl Drop for Delivered {
+    fn drop(&mut self) {
+        let bytes = self.bytes.load(Ordering::Relaxed);
+        debug!(parent: &self.span, bytes, "response ended");
+    }
+}
+
+/// The path of `url` under the base url, e.g. `logs`
+fn dataset(base_url: &reqwest::Url, url: &reqwest::Url) -> String {
+    let path = url.path();
+    path.strip_prefix(base_url.path())
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// `url` without the credentials it may contain
+fn redact(url: &reqwest::Url) -> reqwest::Url {
+    let mut url = url.clone();
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
+    url
+}
+
+/// The size of the chunks a compressed response is decompressed into
+#[cfg(feature = "compression")]
+const DECOMPRESSED_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Decompresses a response with a `Content-Encoding` as it arrives
+#[cfg(feature = "compression")]
+fn decompress(
+    chunks: crate::provider::ResponseStream<Vec<u8>>,
+    encoding: Option<&str>,
+) -> StreamResponse<Vec<u8>> {
+    use async_compression::futures::bufread::{GzipDecoder, ZstdDecoder};
+    use futures::{io::AsyncRead, AsyncReadExt, TryStreamExt};
+
+    let encoding = match encoding {
+        None | Some("identity") => return Ok(chunks),
+        Some(encoding @ ("gzip" | "zstd")) => encoding.to_string(),
+        Some(encoding) => {
+            return Err(Error::Decompression {
+                encoding: encoding.to_string(),
+                reason: "the encoding is not supported".to_string(),
+            })
+        }
+    };
+
+    let reader = chunks.map_err(std::io::Error::other).into_async_read();
+    let decoder: Box<dyn AsyncRead + Send + Unpin> = if encoding == "gzip" {
+        let mut decoder = GzipDecoder::new(reader);
+        decoder.multiple_members(true);
+        Box::new(decoder)
+    } else {
+        Box::new(ZstdDecoder::new(reader))
+    };
+
+    let stream = futures::stream::unfold(Some(decoder), move |decoder| {
+        let encoding = encoding.clone();
+        async move {
+            let mut decoder = decoder?;
+            let mut chunk = vec![0; DECOMPRESSED_CHUNK_SIZE];
+            match decoder.read(&mut chunk).await {
+                Ok(0) => None,
+                Ok(len) => {
+                    chunk.truncate(len);
+                    Some((Ok(chunk), Some(decoder)))
+                }
+                // a failure of the response itself is passed on as it is
+                Err(err) => {
+                    let reason = err.to_string();
+                    let err = match err.into_inner().map(|err| err.downcast::<Error>()) {
+                        Some(Ok(err)) => *err,
+                        _ => Error::Decompression { encoding, reason },
+                    };
+                    Some((Err(err), None))
+                }
+            }
+        }
+    });
+    Ok(stream.boxed())
+}
+
+/// Ends the stream with [`Error::Stalled`] if no chunk arrives within `timeout`
+fn idle_timeout(
+    chunks: crate::provider::ResponseStream<Vec<u8>>,
+    timeout: Duration,
+) -> crate::provider::ResponseStream<Vec<u8>> {
+    futures::stream::unfold(Some(chunks), move |chunks| async move {
+        let mut chunks = chunks?;
+        match tokio::time::timeout(timeout, chunks.next()).await {
+            Ok(Some(chunk)) => Some((chunk, Some(chunks))),
+            Ok(None) => None,
+            Err(_) => Some((Err(Error::Stalled(timeout)), None)),
+        }
+    })
+    .boxed()
+}
+
+/// The longest error body that is read from a failed response
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Fails if the response has no 2xx status, with the error in the body if the
+/// server sent one
+async fn error_for_status(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    match status.as_u16() {
+        401 | 403 => return Err(Error::Unauthorized),
+        404 => return Err(Error::NotFound),
+        429 => {
+            return Err(Error::RateLimited {
+                retry_after: retry_after(response.headers()),
+            })
+        }
+        _ => {}
+    }
+
+    let mut body = Vec::new();
+    let mut chunks = response.bytes_stream();
+    while let Some(chunk) = chunks.next().await {
+        body.extend_from_slice(&chunk?);
+        if body.len() >= MAX_ERROR_BODY {
+            body.truncate(MAX_ERROR_BODY);
+            break;
+        }
+    }
+
+    Err(match serde_json::from_slice::<ResponseError>(&body) {
+        Ok(err) => Error::ErrorResponse(err),
+        Err(_) => Error::Http {
+            status: status.as_u16(),
+            body: String::from_utf8_lossy(&body).into_owned(),
+        },
+    })
+}
+
+/// Fails if a successful response is not in the requested format, e.g. because
+/// the format was dropped on the way to the server
+///
+/// Responses without a content type and JSON error messages are passed on, the
+/// latter are reported by [`ResponseError::map_stream`](crate::core::error::ResponseError::map_stream).
+fn check_content_type(response: &reqwest::Response, format: Format) -> Result<()> {
+    let Some(content_type) = response.headers().get(header::CONTENT_TYPE) else {
+        return Ok(());
+    };
+    let got = String::from_utf8_lossy(content_type.as_bytes());
+    let media_type = got.split(';').next().unwrap_or_default().trim();
+
+    let expected = format.content_type();
+    if media_type.eq_ignore_ascii_case(expected)
+        || media_type.eq_ignore_ascii_case(Format::Json.content_type())
+    {
+        return Ok(());
+    }
+
+    Err(Error::UnexpectedContentType {
+        expected,
+        got: got.into_owned(),
+    })
 }
 
 const STATUS_PATH: &str = "status";
@@ -68,28 +1125,14 @@ impl Provider for HttpProvider {
         username: Option<String>,
         password: Option<String>,
     ) -> Result<Self> {
-        let mut headers = reqwest::header::HeaderMap::new();
-        if let (Some(username), Some(password)) = (username, password) {
-            let auth = format!("{username}:{password}");
-            let encoded = BASE64.encode(auth);
-            headers.insert(
-                header::AUTHORIZATION,
-                header::HeaderValue::from_str(&format!("Basic {encoded}"))
-                    .expect("Only non-ascii chars result in an error"),
-            );
-        }
-
-        let base_url = reqwest::Url::from_str(&format!(
-            "{}://{endpoint}/{API_PATH}",
-            if is_secure { "https" } else { "http" }
-        ))?;
-
-        let inner = reqwest::ClientBuilder::new()
-            .default_headers(headers)
-            .https_only(is_secure)
-            .build()?;
-
-        Ok(Self { inner, base_url })
+        Self::try_new_with_config(
+            endpoint,
+            is_secure,
+            username,
+            password,
+            HttpConfig::default(),
+        )
+        .await
     }
 
     async fn get_status_by_format(&self, format: Format) -> StreamResponse<Vec<u8>> {
@@ -112,7 +1155,7 @@ impl ChainProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(ETHEREUM_BLOCKS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_logs_by_format(
@@ -122,7 +1165,7 @@ impl ChainProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(ETHEREUM_LOGS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_txs_by_format(
@@ -132,7 +1175,7 @@ impl ChainProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(ETHEREUM_TRANSACTIONS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_transfers_by_format(
@@ -142,7 +1185,7 @@ impl ChainProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(ETHEREUM_TRANSFERS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 }
 
@@ -158,7 +1201,7 @@ impl UniswapV2Provider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(UNISWAP_V2_PAIRS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_prices_by_format(
@@ -168,7 +1211,7 @@ impl UniswapV2Provider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(UNISWAP_V2_PRICES_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 }
 
@@ -186,7 +1229,7 @@ impl UniswapV3Provider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(UNISWAP_V3_FEES_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_pools_by_format(
@@ -196,7 +1239,7 @@ impl UniswapV3Provider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(UNISWAP_V3_POOLS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_prices_by_format(
@@ -206,7 +1249,7 @@ impl UniswapV3Provider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(UNISWAP_V3_PRICES_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_positions_by_format(
@@ -216,7 +1259,7 @@ impl UniswapV3Provider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(UNISWAP_V3_POSITIONS)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 }
 
@@ -233,7 +1276,7 @@ impl CurveProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(CURVE_TOKENS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_pools_by_format(
@@ -243,7 +1286,7 @@ impl CurveProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(CURVE_POOLS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_prices_by_format(
@@ -253,7 +1296,7 @@ impl CurveProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(CURVE_PRICES_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 }
 
@@ -270,7 +1313,7 @@ impl Erc20Provider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(ERC20_TOKENS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_erc20_approval_by_format(
@@ -280,17 +1323,17 @@ impl Erc20Provider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(ERC20_APPROVALS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_erc20_transfers_by_format(
         &self,
-        request: erc20::GetErc20TransferssRequest,
+        request: erc20::GetErc20TransfersRequest,
         format: Format,
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(ERC20_TRANSFERS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 }
 
@@ -318,7 +1361,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_BLOCKS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_logs_by_format(
@@ -328,7 +1371,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_LOGS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_logs_decoded_by_format(
@@ -338,7 +1381,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_LOGS_DECODED_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_txs_by_format(
@@ -348,7 +1391,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_TRANSACTIONS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_receipts_by_format(
@@ -358,7 +1401,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_RECEIPTS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_messages_by_format(
@@ -368,7 +1411,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_MESSAGES_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_unspent_utxos_by_format(
@@ -378,7 +1421,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_UNSPENT_UTXOS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_spark_markets_by_format(
@@ -388,7 +1431,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_SPARK_MARKET_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_spark_orders_by_format(
@@ -398,7 +1441,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_SPARK_ORDER_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_src20_by_format(
@@ -408,7 +1451,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_SRC20_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_src7_by_format(
@@ -418,7 +1461,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_SRC7_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_mira_v1_pools_by_format(
@@ -428,7 +1471,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_MIRA_POOLS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_mira_v1_liquidity_by_format(
@@ -438,7 +1481,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_MIRA_LIQUIDITY_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_fuel_mira_v1_swaps_by_format(
@@ -448,7 +1491,7 @@ impl FuelProvider for HttpProvider {
         _: bool,
     ) -> StreamResponse<Vec<u8>> {
         let url = self.url(FUEL_MIRA_SWAPS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 }
 
@@ -464,7 +1507,7 @@ impl BtcProvider for HttpProvider {
     ) -> StreamResponse<Vec<u8>> {
         request.chains = HashSet::from_iter(vec![ChainId::BTC]);
         let url = self.url(BTC_BLOCKS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 
     async fn get_btc_txs_by_format(
@@ -475,6 +1518,6 @@ impl BtcProvider for HttpProvider {
     ) -> StreamResponse<Vec<u8>> {
         request.chains = HashSet::from_iter(vec![ChainId::BTC]);
         let url = self.url(BTC_TRANSACTIONS_PATH)?;
-        self.request(url, request, format).await
+        self.request_range(url, request, format).await
     }
 }
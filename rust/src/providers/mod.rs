@@ -1,2 +1,32 @@
+use std::time::Duration;
+
+use uuid::Uuid;
+
+mod failover;
 pub mod http;
+mod proxy;
+mod rate_limit;
+mod tls;
 pub mod ws;
+
+pub use failover::Failback;
+pub use proxy::Proxy;
+pub use rate_limit::{RateLimitConfig, RateLimitInfo};
+pub use tls::{Identity, TlsBackend, TlsConfig};
+
+/// The URL of `path` on `endpoint`, a host with an optional port and path
+/// prefix, e.g. `ingress.internal/pangea` for an API under `/pangea/v1/api/`
+fn endpoint_url(scheme: &str, endpoint: &str, path: &str) -> String {
+    format!("{scheme}://{}/{path}", endpoint.trim_end_matches('/'))
+}
+
+/// The delay before the given attempt, counting from 1, which doubles from
+/// `initial_delay` up to `max_delay` less a random part of up to half of it
+fn backoff(initial_delay: Duration, max_delay: Duration, attempt: usize) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(31) as u32;
+    let delay = initial_delay.saturating_mul(1 << exponent).min(max_delay);
+
+    // uuids are random enough for jitter and save a dependency on `rand`
+    let random = (Uuid::new_v4().as_u128() as u64) as f64 / u64::MAX as f64;
+    delay.mul_f64(1.0 - random / 2.0)
+}
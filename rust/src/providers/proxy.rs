@@ -0,0 +1,148 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::core::error::{Error, Result};
+
+/// The longest response head accepted from a proxy
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// The proxy that connections are established through
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Proxy {
+    /// Connects directly, without a proxy, the default
+    #[default]
+    None,
+    /// Connects through the HTTP proxy at `url`, e.g. `http://proxy:3128`
+    Http {
+        url: String,
+        username: Option<String>,
+        password: Option<String>,
+    },
+    /// Connects through the proxy in the `HTTPS_PROXY` or `HTTP_PROXY`
+    /// environment variables, except to the hosts in `NO_PROXY`
+    Env,
+}
+
+impl Proxy {
+    /// Configures the HTTP client, whose requests are sent through the proxy
+    pub(crate) fn apply(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        Ok(match self {
+            Proxy::None => builder.no_proxy(),
+            Proxy::Http {
+                url,
+                username,
+                password,
+            } => {
+                let mut proxy = reqwest::Proxy::all(url.as_str())?;
+                if let (Some(username), Some(password)) = (username, password) {
+                    proxy = proxy.basic_auth(username, password);
+                }
+                builder.proxy(proxy)
+            }
+            // the client reads the environment variables itself
+            Proxy::Env => builder,
+        })
+    }
+
+    /// Connects to `host` through a tunnel of the proxy, or directly if there is
+    /// none for the host
+    pub(crate) async fn connect(
+        &self,
+        host: &str,
+        port: u16,
+        is_secure: bool,
+    ) -> Result<TcpStream> {
+        let (url, username, password) = match self {
+            Proxy::None => return Ok(TcpStream::connect((host, port)).await?),
+            Proxy::Http {
+                url,
+                username,
+                password,
+            } => (url.clone(), username.clone(), password.clone()),
+            Proxy::Env => match from_env(host, is_secure) {
+                Some(url) => (url, None, None),
+                None => return Ok(TcpStream::connect((host, port)).await?),
+            },
+        };
+
+        let url = url::Url::parse(&url)?;
+        let (username, password) = match (username, password) {
+            (Some(username), Some(password)) => (Some(username), Some(password)),
+            _ if !url.username().is_empty() => (
+                Some(url.username().to_string()),
+                url.password().map(str::to_string),
+            ),
+            _ => (None, None),
+        };
+        let proxy_host = url
+            .host_str()
+            .ok_or_else(|| Error::Proxy(format!("the proxy url {url} has no host")))?;
+        let proxy_port = url.port_or_known_default().unwrap_or(80);
+
+        let mut socket = TcpStream::connect((proxy_host, proxy_port)).await?;
+        let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+        if let Some(username) = username {
+            let auth = format!("{username}:{}", password.unwrap_or_default());
+            request.push_str(&format!(
+                "Proxy-Authorization: Basic {}\r\n",
+                BASE64.encode(auth)
+            ));
+        }
+        request.push_str("\r\n");
+        socket.write_all(request.as_bytes()).await?;
+
+        // the head is read byte by byte, so nothing of the tunnel is consumed
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            if head.len() >= MAX_HEAD_SIZE {
+                return Err(Error::Proxy("the response of the proxy is too long".into()));
+            }
+            head.push(socket.read_u8().await?);
+        }
+        let head = String::from_utf8_lossy(&head);
+        let status = head.lines().next().unwrap_or_default();
+        match status.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(socket),
+            _ => Err(Error::Proxy(format!(
+                "the proxy refused the tunnel: {status}"
+            ))),
+        }
+    }
+}
+
+/// The proxy for `host` in the environment variables, if any
+fn from_env(host: &str, is_secure: bool) -> Option<String> {
+    let var = |name: &str| {
+        std::env::var(name.to_ascii_uppercase())
+            .or_else(|_| std::env::var(name.to_ascii_lowercase()))
+            .ok()
+            .filter(|value| !value.is_empty())
+    };
+
+    if let Some(no_proxy) = var("no_proxy") {
+        let host = host.to_ascii_lowercase();
+        let excluded = no_proxy
+            .split(',')
+            .map(|pattern| pattern.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|pattern| !pattern.is_empty())
+            .any(|pattern| {
+                pattern == "*"
+                    || host == pattern
+                    || host
+                        .strip_suffix(&pattern)
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            });
+        if excluded {
+            return None;
+        }
+    }
+
+    match is_secure {
+        true => var("https_proxy"),
+        false => var("http_proxy"),
+    }
+    .or_else(|| var("all_proxy"))
+}
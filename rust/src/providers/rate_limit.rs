@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{self, HeaderMap};
+
+/// Values of a reset header above this are Unix timestamps rather than seconds
+const MIN_TIMESTAMP: u64 = 1_000_000_000;
+
+/// How the rate limit of the server is read from its responses, and whether
+/// requests are held back before it is exhausted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// The header with the number of requests allowed per window
+    pub limit_header: String,
+    /// The header with the number of requests left in the window
+    pub remaining_header: String,
+    /// The header with the seconds until the window resets, or the Unix time it
+    /// resets at
+    pub reset_header: String,
+    /// Holds back requests until the window resets once fewer than this many
+    /// are left, not at all by default
+    pub throttle_below: Option<u64>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            limit_header: "x-ratelimit-limit".into(),
+            remaining_header: "x-ratelimit-remaining".into(),
+            reset_header: "x-ratelimit-reset".into(),
+            throttle_below: None,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Reads the rate limit from the headers of a response, `None` if the
+    /// server sent none
+    pub(crate) fn parse(&self, headers: &HeaderMap) -> Option<RateLimitInfo> {
+        let limit = number(headers, &self.limit_header);
+        let remaining = number(headers, &self.remaining_header);
+        let reset_at = number(headers, &self.reset_header).map(reset_at);
+        if limit.is_none() && remaining.is_none() && reset_at.is_none() {
+            return None;
+        }
+        Some(RateLimitInfo {
+            limit,
+            remaining,
+            reset_at,
+            received_at: Instant::now(),
+        })
+    }
+}
+
+/// The rate limit the server reported with its last response, see
+/// [`HttpProvider::rate_limit`](crate::HttpProvider::rate_limit)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// The number of requests allowed per window
+    pub limit: Option<u64>,
+    /// The number of requests left in the window
+    pub remaining: Option<u64>,
+    /// When the window resets
+    pub reset_at: Option<Instant>,
+    /// When the response was received
+    pub received_at: Instant,
+}
+
+impl RateLimitInfo {
+    /// How long to hold back the next request, if fewer than `threshold`
+    /// requests are left until the window resets
+    pub(crate) fn throttle(&self, threshold: u64) -> Option<Duration> {
+        if self.remaining? >= threshold {
+            return None;
+        }
+        let delay = self.reset_at?.saturating_duration_since(Instant::now());
+        (!delay.is_zero()).then_some(delay)
+    }
+}
+
+/// How long the server asks to wait in the `Retry-After` header of a `429`,
+/// given in seconds
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    number(headers, header::RETRY_AFTER.as_str()).map(Duration::from_secs)
+}
+
+fn number(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+fn reset_at(reset: u64) -> Instant {
+    let seconds = match reset >= MIN_TIMESTAMP {
+        true => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            reset.saturating_sub(now)
+        }
+        false => reset,
+    };
+    Instant::now() + Duration::from_secs(seconds)
+}
@@ -0,0 +1,137 @@
+use std::fmt;
+
+use tokio_tungstenite::Connector;
+
+use crate::core::error::{Error, Result};
+
+/// The TLS library connections are encrypted with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TlsBackend {
+    /// The TLS library of the OS, the default
+    #[default]
+    NativeTls,
+    /// rustls, with the root certificates of the OS
+    #[cfg(feature = "rustls")]
+    Rustls,
+}
+
+/// A client certificate and its private key, both PEM encoded, which the
+/// client authenticates itself with. The key must be in PKCS#8.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Identity {
+    certificate: Vec<u8>,
+    key: Vec<u8>,
+}
+
+impl Identity {
+    pub fn from_pem(certificate: impl Into<Vec<u8>>, key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            certificate: certificate.into(),
+            key: key.into(),
+        }
+    }
+}
+
+impl fmt::Debug for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Identity")
+            .field("certificate", &String::from_utf8_lossy(&self.certificate))
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+/// How connections are encrypted, e.g. through a gateway that requires client
+/// certificates and uses a private CA.
+///
+/// Certificates are parsed when the provider is created, which fails with
+/// [`Error::Tls`] if one is invalid.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// The client certificate, none by default
+    pub identity: Option<Identity>,
+    /// PEM encoded certificates trusted in addition to the roots of the OS
+    pub root_certificates: Vec<Vec<u8>>,
+    pub backend: TlsBackend,
+}
+
+impl TlsConfig {
+    /// Configures the HTTP client to encrypt its connections as configured
+    pub(crate) fn apply(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        let mut builder = match self.backend {
+            TlsBackend::NativeTls => builder.use_native_tls(),
+            #[cfg(feature = "rustls")]
+            TlsBackend::Rustls => builder.use_rustls_tls(),
+        };
+        for pem in &self.root_certificates {
+            builder =
+                builder.add_root_certificate(reqwest::Certificate::from_pem(pem).map_err(invalid)?);
+        }
+        if let Some(identity) = &self.identity {
+            let identity = match self.backend {
+                TlsBackend::NativeTls => {
+                    reqwest::Identity::from_pkcs8_pem(&identity.certificate, &identity.key)
+                }
+                #[cfg(feature = "rustls")]
+                TlsBackend::Rustls => reqwest::Identity::from_pem(
+                    &[&identity.key[..], &identity.certificate].concat(),
+                ),
+            };
+            builder = builder.identity(identity.map_err(invalid)?);
+        }
+        Ok(builder)
+    }
+
+    /// The connector for WebSocket connections, `None` for the default one
+    pub(crate) fn connector(&self) -> Result<Option<Connector>> {
+        match self.backend {
+            TlsBackend::NativeTls if *self == Self::default() => Ok(None),
+            TlsBackend::NativeTls => self.native_tls().map(Some),
+            #[cfg(feature = "rustls")]
+            TlsBackend::Rustls => self.rustls().map(Some),
+        }
+    }
+
+    fn native_tls(&self) -> Result<Connector> {
+        let mut builder = native_tls::TlsConnector::builder();
+        for pem in &self.root_certificates {
+            builder.add_root_certificate(native_tls::Certificate::from_pem(pem).map_err(invalid)?);
+        }
+        if let Some(identity) = &self.identity {
+            let identity = native_tls::Identity::from_pkcs8(&identity.certificate, &identity.key)
+                .map_err(invalid)?;
+            builder.identity(identity);
+        }
+        Ok(Connector::NativeTls(builder.build().map_err(invalid)?))
+    }
+
+    #[cfg(feature = "rustls")]
+    fn rustls(&self) -> Result<Connector> {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add_parsable_certificates(rustls_native_certs::load_native_certs().map_err(invalid)?);
+        for pem in &self.root_certificates {
+            for certificate in rustls_pemfile::certs(&mut pem.as_slice()) {
+                roots.add(certificate.map_err(invalid)?).map_err(invalid)?;
+            }
+        }
+
+        let builder = rustls::ClientConfig::builder().with_root_certificates(roots);
+        let config = match &self.identity {
+            Some(identity) => {
+                let chain = rustls_pemfile::certs(&mut identity.certificate.as_slice())
+                    .collect::<std::io::Result<Vec<_>>>()
+                    .map_err(invalid)?;
+                let key = rustls_pemfile::private_key(&mut identity.key.as_slice())
+                    .map_err(invalid)?
+                    .ok_or_else(|| Error::Tls("the identity has no private key".into()))?;
+                builder.with_client_auth_cert(chain, key).map_err(invalid)?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        Ok(Connector::Rustls(std::sync::Arc::new(config)))
+    }
+}
+
+fn invalid(err: impl fmt::Display) -> Error {
+    Error::Tls(err.to_string())
+}
@@ -1,18 +1,26 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     fmt::Debug,
-    time::{Duration, Instant},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant, SystemTime},
 };
 
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use futures::{select_biased, stream::Fuse, FutureExt, SinkExt, StreamExt, TryStreamExt};
+use futures::{select_biased, stream::Fuse, FutureExt, SinkExt, Stream, StreamExt, TryStreamExt};
 use http::header;
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
-use tokio_tungstenite::{connect_async_with_config, MaybeTlsStream, WebSocketStream};
-use tracing::{debug, error, warn};
+use socket2::{SockRef, TcpKeepalive};
+use tokio::sync::{broadcast, mpsc, oneshot, Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::{net::TcpStream, runtime::Handle};
+use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
+use tokio_tungstenite::{client_async_tls_with_config, Connector, MaybeTlsStream, WebSocketStream};
+use tracing::{debug, debug_span, error, warn, Span};
 use tungstenite::{client::IntoClientRequest, protocol::WebSocketConfig, Message};
 use uuid::Uuid;
 
@@ -20,25 +28,300 @@ use crate::{
     core::{
         error::{Error, ResponseError, Result},
         types::format::Format,
+        utils::block_number,
     },
     provider::{
         BtcProvider, ChainProvider, CurveProvider, Erc20Provider, FuelProvider, Provider,
-        StreamResponse, UniswapV2Provider, UniswapV3Provider,
+        ResponseStream, StreamResponse, UniswapV2Provider, UniswapV3Provider,
     },
+    query::{Bound, SortOrder},
     requests::{
         blocks, btc, curve, erc20, fuel, logs, mira, transfers, txs, uniswap_v2, uniswap_v3,
+        HasBlockBounds,
     },
     ChainId,
 };
 
+use super::{failover::Endpoints, Failback, Proxy, TlsConfig};
+
 const WS_PATH: &str = "v1/websocket";
+/// How long a closing connection waits for the server to answer the close
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
 
 type WsResult = Result<Vec<u8>>;
-type OperationMsg = (Request, mpsc::Sender<WsResult>);
+type Params = HashMap<String, serde_json::Value>;
+/// Builds the params of a request that resumes at a block, after the given
+/// number of records before it were delivered
+type Resume = Arc<dyn Fn(i64, u64) -> Result<Params> + Send + Sync>;
+
+/// How [`WsProvider`] re-establishes a dropped connection.
+///
+/// The delay before an attempt doubles from `initial_delay` up to `max_delay`,
+/// and a random part of up to half of it is subtracted so that clients dropped
+/// at once do not reconnect at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// How often a connection is attempted before the streams fail
+    pub max_attempts: usize,
+    /// The delay before the first attempt
+    pub initial_delay: Duration,
+    /// The longest delay between attempts
+    pub max_delay: Duration,
+}
+
+impl ReconnectPolicy {
+    /// The delay before the given attempt, counting from 1
+    pub fn delay(&self, attempt: usize) -> Duration {
+        super::backoff(self.initial_delay, self.max_delay, attempt)
+    }
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+/// The configuration of a [`WsProvider`] connection
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WsConfig {
+    /// How often the connection is pinged
+    pub ping_interval: Duration,
+    /// How long to wait for any message after a ping, before the connection is
+    /// considered stale. Connections through a NAT can die without being closed.
+    pub pong_timeout: Duration,
+    /// How a dropped or stale connection is re-established, not at all by default
+    pub reconnect: Option<ReconnectPolicy>,
+    /// How many requests may run on the connection at once, unlimited by default.
+    /// A request over the limit fails with [`Error::TooManySubscriptions`].
+    pub max_subscriptions: Option<usize>,
+    /// How many messages are buffered for a stream that is not read
+    pub buffer_size: usize,
+    /// What happens when the buffer of a stream is full
+    pub overflow: OverflowPolicy,
+    /// How long to wait for the first message of a response, before the request
+    /// fails with [`Error::Timeout`]. See [`WsProvider::with_handshake_timeout`]
+    /// to override it for some requests.
+    pub handshake_timeout: Duration,
+    /// The largest message accepted from the server, `None` for no limit. A
+    /// larger message ends the connection with [`Error::MessageTooLarge`],
+    /// which is not reconnected as the message would be sent again.
+    pub max_message_size: Option<usize>,
+    /// The largest frame accepted from the server, `None` for no limit
+    pub max_frame_size: Option<usize>,
+    /// How many times in a row the credentials of a [`CredentialsProvider`] may
+    /// be refused while reconnecting, before the streams end with
+    /// [`Error::Unauthorized`]. Fixed credentials are not retried.
+    pub max_auth_failures: usize,
+    /// How long establishing a connection may take, including the TLS and
+    /// WebSocket handshakes, before it fails with [`Error::ConnectTimeout`].
+    /// Applies to every reconnect attempt as well.
+    pub connect_timeout: Duration,
+    /// The idle time before TCP keepalive probes are sent, and the interval
+    /// between them. `None` leaves keepalive to the OS.
+    pub tcp_keepalive: Option<Duration>,
+    /// Sends small writes right away instead of batching them in the kernel,
+    /// see `TCP_NODELAY`
+    pub tcp_nodelay: bool,
+    /// When requests and other messages to the server are flushed
+    pub flush: FlushPolicy,
+    /// The proxy the connection is tunnelled through with `CONNECT`, none by
+    /// default. Use [`Proxy::Env`] to honour the `HTTPS_PROXY` environment
+    /// variables.
+    pub proxy: Proxy,
+    /// Client certificates, additional root certificates and the TLS backend
+    pub tls: TlsConfig,
+    /// Endpoints in other regions, in the order the connection fails over to
+    /// them when it cannot be established, see [`WsProvider::endpoint`]. None
+    /// by default.
+    pub fallback_endpoints: Vec<String>,
+    /// When the connection goes back to the primary endpoint after failing
+    /// over. Only checked when it is re-established, a connection to a fallback
+    /// is not closed for it.
+    pub failback: Failback,
+}
 
+/// When the messages written to the connection are flushed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Flushes every message, for the lowest latency
+    #[default]
+    Immediate,
+    /// Flushes the messages written within the duration at once, which saves
+    /// writes when many requests are sent together but delays each by up to
+    /// the duration
+    Coalesce(Duration),
+}
+
+/// Supplies the credentials of a [`WsProvider`], which are fetched again for
+/// every connection, e.g. when they are short-lived
+#[async_trait]
+pub trait CredentialsProvider: Send + Sync {
+    /// Returns the username and password
+    async fn get(&self) -> Result<(String, String)>;
+}
+
+/// What happens when a stream does not keep up with its response
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Stops reading from the connection until the stream catches up. This
+    /// propagates the backpressure to the server, and slows down all requests
+    /// on the connection.
+    #[default]
+    Block,
+    /// Drops the oldest buffered message of the stream
+    DropOldest,
+    /// Ends the stream with [`Error::BufferOverflow`] and cancels its request
+    Error,
+}
+
+impl Default for WsConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval: Duration::from_secs(5),
+            pong_timeout: Duration::from_secs(4),
+            reconnect: None,
+            max_subscriptions: None,
+            buffer_size: 5,
+            overflow: OverflowPolicy::Block,
+            handshake_timeout: Duration::from_secs(30),
+            max_message_size: Some(64 << 20),
+            max_frame_size: Some(64 << 20),
+            max_auth_failures: 3,
+            connect_timeout: Duration::from_secs(10),
+            tcp_keepalive: None,
+            tcp_nodelay: false,
+            flush: FlushPolicy::Immediate,
+            proxy: Proxy::None,
+            tls: TlsConfig::default(),
+            fallback_endpoints: Vec::new(),
+            failback: Failback::default(),
+        }
+    }
+}
+
+/// The lifecycle of the connection of a [`WsProvider`], see [`WsProvider::events`]
+#[derive(Clone, Debug)]
+pub enum ConnectionEvent {
+    /// A connection was established and authenticated
+    Connected,
+    /// The server refused the credentials while reconnecting, which is only
+    /// retried with credentials from a [`CredentialsProvider`]
+    AuthFailed { reason: String },
+    /// The connection dropped
+    Disconnected { reason: String },
+    /// A connection is attempted after `delay`
+    Reconnecting { attempt: usize, delay: Duration },
+    /// The connection is back and the active subscriptions were sent again
+    Reconnected {
+        attempt: usize,
+        subscriptions: usize,
+    },
+    /// All attempts failed, the active streams end with an error
+    GaveUp { attempts: usize, reason: String },
+}
+
+/// What a stream received so far, see [`WsProvider::stats`]
+#[derive(Clone, Debug)]
+pub struct SubscriptionStats {
+    /// The id the request was sent with
+    pub id: Uuid,
+    pub operation: Operation,
+    /// The number of messages with data, counted when they arrive rather than
+    /// when they are read from the stream
+    pub messages: u64,
+    pub bytes: u64,
+    /// The block of the last record, for [`Format::JsonStream`] responses only
+    pub last_block: Option<i64>,
+    pub last_message_at: Option<Instant>,
+}
+
+/// The stream of a request sent with [`WsProvider::subscribe`], along with what
+/// it was sent with.
+///
+/// Dropping it, or calling [`cancel`](Self::cancel), cancels the request.
+pub struct Subscription {
+    id: Uuid,
+    operation: Operation,
+    params: serde_json::Value,
+    created_at: SystemTime,
+    stats: Arc<Mutex<SubscriptionStats>>,
+    stream: ResponseStream<Vec<u8>>,
+}
+
+impl Subscription {
+    /// The id the request was sent with
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn operation(&self) -> &Operation {
+        &self.operation
+    }
+
+    /// The parameters of the request, including the options of its format
+    pub fn params(&self) -> &serde_json::Value {
+        &self.params
+    }
+
+    /// When the request was sent
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    /// Returns what the stream received so far, like [`WsProvider::stats`]
+    pub fn stats(&self) -> SubscriptionStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Cancels the request, the same as dropping it
+    pub fn cancel(self) {}
+}
+
+impl Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .field("operation", &self.operation)
+            .field("params", &self.params)
+            .field("created_at", &self.created_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Stream for Subscription {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+/// A provider that multiplexes all requests over one WebSocket connection.
+///
+/// Clones share the connection, and requests can be sent concurrently from any
+/// of them. Every request gets its own id, by which the responses are passed to
+/// its stream.
 #[derive(Clone, Debug)]
 pub struct WsProvider {
     operations: mpsc::UnboundedSender<OperationMsg>,
+    events: broadcast::Sender<ConnectionEvent>,
+    /// [`WsConfig::max_subscriptions`] and the permits of the running requests
+    subscription_limit: Option<(usize, Arc<Semaphore>)>,
+    buffer_size: usize,
+    overflow: OverflowPolicy,
+    /// The number of messages in the buffers of all streams
+    buffered: Arc<AtomicUsize>,
+    handshake_timeout: Duration,
+    /// Whether [`WsConfig::reconnect`] is set, so that requests are resumed
+    reconnects: bool,
+    /// The stats of the open streams
+    streams: Arc<Mutex<HashMap<Uuid, Arc<Mutex<SubscriptionStats>>>>>,
+    endpoints: Arc<Endpoints>,
 }
 
 impl WsProvider {
@@ -49,76 +332,584 @@ impl WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        let (sink, stream) = mpsc::channel(5);
-        let id = Uuid::new_v4();
+        let subscription = self.subscribe(operation, params, format, deltas).await?;
+        Ok(subscription.boxed())
+    }
+
+    /// Sends a request like [`request`](Self::request), and returns its stream
+    /// along with the id and stats of the request
+    pub async fn subscribe(
+        &self,
+        operation: Operation,
+        params: impl Serialize,
+        format: Format,
+        deltas: bool,
+    ) -> Result<Subscription> {
+        let params = to_params(params, format)?;
+        self.send(operation, params, format, deltas, None).await
+    }
+
+    /// Sends a request like [`request`](Self::request), which resumes after the
+    /// last delivered block when the connection is re-established.
+    ///
+    /// Only [`Format::JsonStream`] responses are tracked, as their records are
+    /// read without decoding the whole response. The request starts again at the
+    /// last delivered block, whose records that were already delivered are
+    /// skipped, since a block can be split across messages. Other formats, and
+    /// records without a `block_number`, are requested again from the start.
+    ///
+    /// The `limit` of a resumed request is lowered by the records delivered
+    /// before. Requests in descending order cannot be resumed, and fail with
+    /// [`Error::InvalidRequest`] if the provider has a reconnect policy.
+    pub async fn request_resumable<R>(
+        &self,
+        operation: Operation,
+        request: R,
+        format: Format,
+        deltas: bool,
+    ) -> StreamResponse<Vec<u8>>
+    where
+        R: HasBlockBounds + Clone + Serialize + Send + Sync + 'static,
+    {
+        let subscription = self
+            .subscribe_resumable(operation, request, format, deltas)
+            .await?;
+        Ok(subscription.boxed())
+    }
+
+    /// Sends a request like [`request_resumable`](Self::request_resumable), and
+    /// returns it like [`subscribe`](Self::subscribe)
+    pub async fn subscribe_resumable<R>(
+        &self,
+        operation: Operation,
+        request: R,
+        format: Format,
+        deltas: bool,
+    ) -> Result<Subscription>
+    where
+        R: HasBlockBounds + Clone + Serialize + Send + Sync + 'static,
+    {
+        request.check_bounds()?;
+        if self.reconnects && request.order() == Some(SortOrder::Desc) {
+            return Err(Error::InvalidRequest(
+                "requests in descending order cannot be resumed".into(),
+            ));
+        }
+        let params = to_params(&request, format)?;
+        let resume: Resume = Arc::new(move |block, delivered| {
+            let mut request = request.clone();
+            let from_block = match request.block_bounds().0 {
+                Bound::Exact(from_block) => Bound::Exact(from_block.max(block)),
+                _ => Bound::Exact(block),
+            };
+            request.set_from_block(from_block);
+            if let Some(limit) = request.limit() {
+                request.set_limit(Some(limit.saturating_sub(delivered)));
+            }
+            to_params(&request, format)
+        });
+        self.send(operation, params, format, deltas, Some(resume))
+            .await
+    }
 
-        let params = serde_json::to_value(params).map_err(Error::from)?;
-        let params = if matches!(params, serde_json::Value::Null) {
-            HashMap::new()
-        } else {
-            serde_json::from_value(params).map_err(Error::from)?
+    async fn send(
+        &self,
+        operation: Operation,
+        params: Params,
+        format: Format,
+        deltas: bool,
+        resume: Option<Resume>,
+    ) -> Result<Subscription> {
+        let permit = match &self.subscription_limit {
+            Some((limit, permits)) => Some(permits.clone().try_acquire_owned().map_err(|_| {
+                Error::TooManySubscriptions {
+                    limit: Some(*limit),
+                }
+            })?),
+            None => None,
         };
+
+        let id = Uuid::new_v4();
+        let (ack, acknowledged) = oneshot::channel::<Result<()>>();
+        let buffer = Arc::new(Buffer {
+            state: Mutex::default(),
+            stats: Arc::new(Mutex::new(SubscriptionStats {
+                id,
+                operation: operation.clone(),
+                messages: 0,
+                bytes: 0,
+                last_block: None,
+                last_message_at: None,
+            })),
+            capacity: self.buffer_size.max(1),
+            overflow: self.overflow,
+            buffered: self.buffered.clone(),
+            readable: Notify::new(),
+            writable: Notify::new(),
+        });
+        let subscription_params = serde_json::to_value(&params)?;
+        // the id is sent to the server, which correlates the logs of both sides
+        let span = debug_span!("request", %id, ?operation, format = format.as_str());
+        debug!(parent: &span, params = %subscription_params, deltas, "sending request");
         let request = Request {
             id,
-            operation,
+            operation: operation.clone(),
             params,
             format,
             deltas,
+            resume,
         };
+        self.streams
+            .lock()
+            .unwrap()
+            .insert(id, buffer.stats.clone());
         self.operations
-            .send((request, sink))
+            .send(OperationMsg::Request(request, buffer.clone(), ack))
             .map_err(|_| Error::BackendShutDown)?;
 
-        let stream = ReceiverStream::new(stream)
-            .map_err(Error::from)
-            .filter_map(|data| async {
-                match data {
-                    Ok(data) if !data.is_empty() => Some(Ok(data)),
-                    Ok(_) => None,
-                    Err(e) => Some(Err(e)),
-                }
-            })
-            .boxed();
+        // a weak sender, so that open streams do not keep the connection open
+        let receiver = Receiver {
+            id,
+            buffer,
+            operations: self.operations.downgrade(),
+            streams: self.streams.clone(),
+            span,
+            _permit: permit,
+        };
+        // the receiver is dropped on a timeout, which cancels the request. If the
+        // worker dropped the sender instead, the stream returns its error.
+        match tokio::time::timeout(self.handshake_timeout, acknowledged).await {
+            Err(_) => return Err(Error::Timeout(self.handshake_timeout)),
+            Ok(Ok(Err(err))) => return Err(err),
+            Ok(_) => {}
+        }
+
+        let stats = receiver.buffer.stats.clone();
+        let stream = futures::stream::unfold(receiver, |receiver| async move {
+            let data = receiver.buffer.pop().await?;
+            Some((data, receiver))
+        })
+        .filter_map(|data| async {
+            match data {
+                Ok(data) if !data.is_empty() => Some(Ok(data)),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+        .boxed();
 
-        Ok(stream)
+        Ok(Subscription {
+            id,
+            operation,
+            params: subscription_params,
+            created_at: SystemTime::now(),
+            stats,
+            stream,
+        })
     }
 
     /// Returns true if the WS connection is active, false otherwise
     pub fn ready(&self) -> bool {
         !self.operations.is_closed()
     }
-}
 
-#[async_trait]
-impl Provider for WsProvider {
-    async fn try_new(
+    /// Closes the connection and waits until it is closed.
+    ///
+    /// The connection is shared by all clones of the provider, whose requests
+    /// fail afterwards. Messages the server sent before it answered the close are
+    /// still passed to their streams, which end after them. Dropping the last
+    /// clone closes the connection the same way, without waiting for it.
+    pub async fn shutdown(self) {
+        let (shutdown, closed) = oneshot::channel();
+        if self
+            .operations
+            .send(OperationMsg::Shutdown(shutdown))
+            .is_ok()
+        {
+            let _ = closed.await;
+        }
+    }
+
+    /// Returns a provider on the same connection, whose requests wait up to
+    /// `timeout` for the first message of the response, e.g. for backfills that
+    /// take the server a while to start
+    pub fn with_handshake_timeout(&self, timeout: Duration) -> Self {
+        Self {
+            handshake_timeout: timeout,
+            ..self.clone()
+        }
+    }
+
+    /// The number of messages received for all streams, but not read from
+    /// them yet, e.g. to export as a metric
+    pub fn buffered(&self) -> usize {
+        self.buffered.load(Ordering::Relaxed)
+    }
+
+    /// Returns what each open stream received so far.
+    ///
+    /// Together with the head of the chain from
+    /// [`Client::get_status`](crate::Client::get_status), the `last_block` of a
+    /// stream tells how far it lags behind. Streams are listed until they are
+    /// read to the end or dropped.
+    pub fn stats(&self) -> Vec<SubscriptionStats> {
+        let streams = self.streams.lock().unwrap();
+        streams
+            .values()
+            .map(|stats| stats.lock().unwrap().clone())
+            .collect()
+    }
+
+    /// Connects like [`Provider::try_new`], and re-establishes the connection
+    /// as configured in `policy` when it drops.
+    ///
+    /// Active subscriptions are sent again on the new connection under the same
+    /// id, so their streams go on after the gap. Requests sent with
+    /// [`request_resumable`](Self::request_resumable) resume after the last
+    /// delivered record, others are sent again as they are.
+    pub async fn try_new_with_reconnect(
         endpoint: String,
         is_secure: bool,
         username: Option<String>,
         password: Option<String>,
+        policy: ReconnectPolicy,
     ) -> Result<Self> {
-        let mut req = format!(
-            "{}://{endpoint}/{WS_PATH}",
-            if is_secure { "wss" } else { "ws" },
-        )
-        .into_client_request()?;
+        let config = WsConfig {
+            reconnect: Some(policy),
+            ..Default::default()
+        };
+        Self::try_new_with_config(endpoint, is_secure, username, password, config).await
+    }
+
+    /// Follows the lifecycle of the connection, independent of the requests on it.
+    ///
+    /// Only events after the call are returned, so the first connection is not.
+    /// A stream that lags behind misses the oldest events.
+    pub fn events(&self) -> impl Stream<Item = ConnectionEvent> + Send + 'static {
+        BroadcastStream::new(self.events.subscribe()).filter_map(|event| async { event.ok() })
+    }
 
+    /// The endpoint of the current connection, or of the last one while it is
+    /// re-established, see [`WsConfig::fallback_endpoints`]
+    pub fn endpoint(&self) -> &str {
+        self.endpoints.active()
+    }
+
+    /// Connects like [`Provider::try_new`] with the given heartbeat and reconnect
+    /// policy.
+    ///
+    /// When the server stops answering pings, the active streams end with
+    /// [`Error::ConnectionStale`], or the connection is re-established if
+    /// `config` has a reconnect policy.
+    pub async fn try_new_with_config(
+        endpoint: String,
+        is_secure: bool,
+        username: Option<String>,
+        password: Option<String>,
+        config: WsConfig,
+    ) -> Result<Self> {
+        let mut headers = header::HeaderMap::new();
         if let (Some(username), Some(password)) = (username, password) {
-            let auth = format!("{username}:{password}");
-            let encoded = BASE64.encode(auth);
+            headers.append(header::AUTHORIZATION, basic_auth(&username, &password));
+        }
+        Self::connect(endpoint, is_secure, headers, None, config, None).await
+    }
 
-            req.headers_mut().append(
-                header::AUTHORIZATION,
-                header::HeaderValue::from_str(&format!("Basic {encoded}"))
-                    .expect("Only non-ascii chars result in an error"),
-            );
+    /// Connects like [`try_new_with_config`](Self::try_new_with_config), on
+    /// `runtime` instead of the current one.
+    ///
+    /// The connection and its background task live on `runtime`, so the
+    /// provider keeps working after the runtime it was created on shuts down,
+    /// and can be dropped from any runtime. The other constructors use the
+    /// runtime they are called on.
+    pub async fn try_new_with_runtime(
+        endpoint: String,
+        is_secure: bool,
+        username: Option<String>,
+        password: Option<String>,
+        config: WsConfig,
+        runtime: Handle,
+    ) -> Result<Self> {
+        let mut headers = header::HeaderMap::new();
+        if let (Some(username), Some(password)) = (username, password) {
+            headers.append(header::AUTHORIZATION, basic_auth(&username, &password));
         }
+        Self::connect(endpoint, is_secure, headers, None, config, Some(runtime)).await
+    }
+
+    /// Connects like [`try_new_with_config`](Self::try_new_with_config), with
+    /// credentials that are fetched from `credentials` for every connection.
+    ///
+    /// Credentials the server refuses while reconnecting are fetched again, up to
+    /// [`WsConfig::max_auth_failures`] times in a row.
+    pub async fn try_new_with_credentials(
+        endpoint: String,
+        is_secure: bool,
+        credentials: impl CredentialsProvider + 'static,
+        config: WsConfig,
+    ) -> Result<Self> {
+        let headers = header::HeaderMap::new();
+        let credentials: Arc<dyn CredentialsProvider> = Arc::new(credentials);
+        Self::connect(
+            endpoint,
+            is_secure,
+            headers,
+            Some(credentials),
+            config,
+            None,
+        )
+        .await
+    }
+
+    async fn connect(
+        endpoint: String,
+        is_secure: bool,
+        headers: header::HeaderMap,
+        credentials: Option<Arc<dyn CredentialsProvider>>,
+        config: WsConfig,
+        runtime: Option<Handle>,
+    ) -> Result<Self> {
+        let scheme = if is_secure { "wss" } else { "ws" };
+        let urls = std::iter::once(&endpoint)
+            .chain(&config.fallback_endpoints)
+            .map(|endpoint| super::endpoint_url(scheme, endpoint, WS_PATH))
+            .collect();
+        let endpoints = Arc::new(Endpoints::new(
+            endpoint,
+            config.fallback_endpoints.clone(),
+            config.failback,
+        ));
 
         let (sink, stream) = mpsc::unbounded_channel();
-        let bw = BackgroundWorker::new(req, stream).await?;
-        tokio::spawn(bw.main_loop());
+        let (events, _) = broadcast::channel(16);
+        let server = Server {
+            urls,
+            endpoints: endpoints.clone(),
+            headers,
+            credentials,
+            connect_timeout: config.connect_timeout,
+            tcp_keepalive: config.tcp_keepalive,
+            tcp_nodelay: config.tcp_nodelay,
+            proxy: config.proxy.clone(),
+            connector: config.tls.connector()?,
+            config: WebSocketConfig {
+                max_message_size: config.max_message_size,
+                max_frame_size: config.max_frame_size,
+                ..Default::default()
+            },
+        };
+
+        // the socket is connected on the runtime the worker runs on, as it is
+        // bound to the reactor of that runtime
+        let (connected, connecting) = oneshot::channel();
+        let worker_events = events.clone();
+        let worker_config = config.clone();
+        runtime.unwrap_or_else(Handle::current).spawn(async move {
+            match BackgroundWorker::new(server, stream, worker_config, worker_events).await {
+                Ok(bw) => {
+                    let _ = connected.send(Ok(()));
+                    bw.main_loop().await;
+                }
+                Err(err) => {
+                    let _ = connected.send(Err(err));
+                }
+            }
+        });
+        connecting.await.map_err(|_| Error::BackendShutDown)??;
+
+        Ok(Self {
+            operations: sink,
+            events,
+            subscription_limit: config
+                .max_subscriptions
+                .map(|limit| (limit, Arc::new(Semaphore::new(limit)))),
+            buffer_size: config.buffer_size,
+            overflow: config.overflow,
+            buffered: Arc::default(),
+            handshake_timeout: config.handshake_timeout,
+            reconnects: config.reconnect.is_some(),
+            streams: Arc::default(),
+            endpoints,
+        })
+    }
+}
 
-        Ok(Self { operations: sink })
+/// The stream side of a request, which cancels it when dropped
+struct Receiver {
+    id: Uuid,
+    buffer: Arc<Buffer>,
+    operations: mpsc::WeakUnboundedSender<OperationMsg>,
+    streams: Arc<Mutex<HashMap<Uuid, Arc<Mutex<SubscriptionStats>>>>>,
+    span: Span,
+    /// Released with the stream, for another request to run
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        self.buffer.drop_receiver();
+        self.streams.lock().unwrap().remove(&self.id);
+        let stats = self.buffer.stats.lock().unwrap();
+        debug!(
+            parent: &self.span,
+            messages = stats.messages,
+            bytes = stats.bytes,
+            "request ended"
+        );
+        drop(stats);
+        if let Some(operations) = self.operations.upgrade() {
+            let _ = operations.send(OperationMsg::Cancel(self.id));
+        }
+    }
+}
+
+/// The messages of a request between the background worker and its stream
+struct Buffer {
+    state: Mutex<BufferState>,
+    /// Shared with [`WsProvider::stats`]
+    stats: Arc<Mutex<SubscriptionStats>>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    /// Shared by the buffers of a connection
+    buffered: Arc<AtomicUsize>,
+    /// Wakes the stream after a message was pushed or the buffer was closed
+    readable: Notify,
+    /// Wakes the worker after a message was popped
+    writable: Notify,
+}
+
+#[derive(Default)]
+struct BufferState {
+    messages: VecDeque<WsResult>,
+    /// No more messages are pushed
+    closed: bool,
+    /// The stream was dropped
+    dropped: bool,
+}
+
+enum Pushed {
+    Buffered,
+    /// The stream was dropped
+    Dropped,
+    /// The buffer is full and the overflow policy is [`OverflowPolicy::Error`]
+    Overflowed,
+}
+
+impl Buffer {
+    /// Pushes a message, waiting for a full buffer as the overflow policy says
+    async fn push(&self, message: WsResult) -> Pushed {
+        loop {
+            let writable = self.writable.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.dropped {
+                    return Pushed::Dropped;
+                }
+                if state.messages.len() >= self.capacity {
+                    match self.overflow {
+                        OverflowPolicy::Block => {}
+                        OverflowPolicy::DropOldest => {
+                            state.messages.pop_front();
+                            self.buffered.fetch_sub(1, Ordering::Relaxed);
+                        }
+                        OverflowPolicy::Error => return Pushed::Overflowed,
+                    }
+                }
+                if state.messages.len() < self.capacity {
+                    state.messages.push_back(message);
+                    self.buffered.fetch_add(1, Ordering::Relaxed);
+                    self.readable.notify_one();
+                    return Pushed::Buffered;
+                }
+            }
+            writable.await;
+        }
+    }
+
+    /// Ends the stream after the buffered messages, with `err` if any, which
+    /// does not count against the capacity
+    fn close(&self, err: Option<Error>) {
+        let mut state = self.state.lock().unwrap();
+        if state.closed || state.dropped {
+            return;
+        }
+        if let Some(err) = err {
+            state.messages.push_back(Err(err));
+            self.buffered.fetch_add(1, Ordering::Relaxed);
+        }
+        state.closed = true;
+        self.readable.notify_one();
+    }
+
+    /// Counts a message with data in the stats
+    fn record(&self, data: &[u8], last_block: Option<i64>) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.messages += 1;
+        stats.bytes += data.len() as u64;
+        stats.last_block = last_block.or(stats.last_block);
+        stats.last_message_at = Some(Instant::now());
+    }
+
+    fn is_dropped(&self) -> bool {
+        self.state.lock().unwrap().dropped
+    }
+
+    async fn pop(&self) -> Option<WsResult> {
+        loop {
+            let readable = self.readable.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if let Some(message) = state.messages.pop_front() {
+                    self.buffered.fetch_sub(1, Ordering::Relaxed);
+                    self.writable.notify_one();
+                    return Some(message);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            readable.await;
+        }
+    }
+
+    fn drop_receiver(&self) {
+        let mut state = self.state.lock().unwrap();
+        self.buffered
+            .fetch_sub(state.messages.len(), Ordering::Relaxed);
+        state.messages.clear();
+        state.dropped = true;
+        self.writable.notify_one();
+    }
+}
+
+fn to_params(params: impl Serialize, format: Format) -> Result<Params> {
+    let params = serde_json::to_value(params)?;
+    let mut params: Params = if matches!(params, serde_json::Value::Null) {
+        HashMap::new()
+    } else {
+        serde_json::from_value(params)?
+    };
+    // the options of the format are sent next to the request parameters,
+    // the same way the HTTP provider sends them as query parameters
+    params.extend(
+        format
+            .options()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), serde_json::Value::String(value))),
+    );
+    Ok(params)
+}
+
+#[async_trait]
+impl Provider for WsProvider {
+    async fn try_new(
+        endpoint: String,
+        is_secure: bool,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> Result<Self> {
+        Self::try_new_with_config(endpoint, is_secure, username, password, WsConfig::default())
+            .await
     }
 
     async fn get_status_by_format(&self, format: Format) -> StreamResponse<Vec<u8>> {
@@ -134,7 +925,7 @@ impl ChainProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetBlocks, request, format, deltas)
+        self.request_resumable(Operation::GetBlocks, request, format, deltas)
             .await
     }
 
@@ -144,7 +935,7 @@ impl ChainProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetLogs, request, format, deltas)
+        self.request_resumable(Operation::GetLogs, request, format, deltas)
             .await
     }
 
@@ -154,7 +945,7 @@ impl ChainProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetTxs, request, format, deltas)
+        self.request_resumable(Operation::GetTxs, request, format, deltas)
             .await
     }
 
@@ -164,7 +955,7 @@ impl ChainProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetTransfers, request, format, deltas)
+        self.request_resumable(Operation::GetTransfers, request, format, deltas)
             .await
     }
 }
@@ -177,7 +968,7 @@ impl UniswapV2Provider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetUniswapV2Pairs, request, format, deltas)
+        self.request_resumable(Operation::GetUniswapV2Pairs, request, format, deltas)
             .await
     }
 
@@ -187,7 +978,7 @@ impl UniswapV2Provider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetUniswapV2Prices, request, format, deltas)
+        self.request_resumable(Operation::GetUniswapV2Prices, request, format, deltas)
             .await
     }
 }
@@ -200,7 +991,7 @@ impl UniswapV3Provider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetUniswapV3Fees, request, format, deltas)
+        self.request_resumable(Operation::GetUniswapV3Fees, request, format, deltas)
             .await
     }
 
@@ -210,7 +1001,7 @@ impl UniswapV3Provider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetUniswapV3Pools, request, format, deltas)
+        self.request_resumable(Operation::GetUniswapV3Pools, request, format, deltas)
             .await
     }
 
@@ -220,7 +1011,7 @@ impl UniswapV3Provider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetUniswapV3Positions, request, format, deltas)
+        self.request_resumable(Operation::GetUniswapV3Positions, request, format, deltas)
             .await
     }
 
@@ -230,7 +1021,7 @@ impl UniswapV3Provider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetUniswapV3Prices, request, format, deltas)
+        self.request_resumable(Operation::GetUniswapV3Prices, request, format, deltas)
             .await
     }
 }
@@ -243,7 +1034,7 @@ impl CurveProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetCurveTokens, request, format, deltas)
+        self.request_resumable(Operation::GetCurveTokens, request, format, deltas)
             .await
     }
 
@@ -253,7 +1044,7 @@ impl CurveProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetCurvePools, request, format, deltas)
+        self.request_resumable(Operation::GetCurvePools, request, format, deltas)
             .await
     }
 
@@ -263,7 +1054,7 @@ impl CurveProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetCurvePrices, request, format, deltas)
+        self.request_resumable(Operation::GetCurvePrices, request, format, deltas)
             .await
     }
 }
@@ -276,7 +1067,7 @@ impl Erc20Provider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetErc20, request, format, deltas)
+        self.request_resumable(Operation::GetErc20, request, format, deltas)
             .await
     }
 
@@ -286,17 +1077,17 @@ impl Erc20Provider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetErc20Approvals, request, format, deltas)
+        self.request_resumable(Operation::GetErc20Approvals, request, format, deltas)
             .await
     }
 
     async fn get_erc20_transfers_by_format(
         &self,
-        request: erc20::GetErc20TransferssRequest,
+        request: erc20::GetErc20TransfersRequest,
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetErc20Transfers, request, format, deltas)
+        self.request_resumable(Operation::GetErc20Transfers, request, format, deltas)
             .await
     }
 }
@@ -309,7 +1100,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetBlocks, request, format, deltas)
+        self.request_resumable(Operation::GetBlocks, request, format, deltas)
             .await
     }
 
@@ -319,7 +1110,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetLogs, request, format, deltas)
+        self.request_resumable(Operation::GetLogs, request, format, deltas)
             .await
     }
 
@@ -329,7 +1120,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetFuelLogsDecoded, request, format, deltas)
+        self.request_resumable(Operation::GetFuelLogsDecoded, request, format, deltas)
             .await
     }
 
@@ -339,7 +1130,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetTxs, request, format, deltas)
+        self.request_resumable(Operation::GetTxs, request, format, deltas)
             .await
     }
 
@@ -349,7 +1140,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetReceipts, request, format, deltas)
+        self.request_resumable(Operation::GetReceipts, request, format, deltas)
             .await
     }
 
@@ -359,7 +1150,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetMessages, request, format, deltas)
+        self.request_resumable(Operation::GetMessages, request, format, deltas)
             .await
     }
 
@@ -369,7 +1160,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetUnspentUtxos, request, format, deltas)
+        self.request_resumable(Operation::GetUnspentUtxos, request, format, deltas)
             .await
     }
 
@@ -379,7 +1170,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetSparkMarket, request, format, deltas)
+        self.request_resumable(Operation::GetSparkMarket, request, format, deltas)
             .await
     }
 
@@ -389,7 +1180,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetSparkOrder, request, format, deltas)
+        self.request_resumable(Operation::GetSparkOrder, request, format, deltas)
             .await
     }
 
@@ -399,7 +1190,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetSrc20, request, format, deltas)
+        self.request_resumable(Operation::GetSrc20, request, format, deltas)
             .await
     }
 
@@ -409,7 +1200,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetSrc7, request, format, deltas)
+        self.request_resumable(Operation::GetSrc7, request, format, deltas)
             .await
     }
 
@@ -419,7 +1210,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetMiraV1Pools, request, format, deltas)
+        self.request_resumable(Operation::GetMiraV1Pools, request, format, deltas)
             .await
     }
 
@@ -429,7 +1220,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetMiraV1Liqudity, request, format, deltas)
+        self.request_resumable(Operation::GetMiraV1Liqudity, request, format, deltas)
             .await
     }
 
@@ -439,7 +1230,7 @@ impl FuelProvider for WsProvider {
         format: Format,
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
-        self.request(Operation::GetMiraV1Swaps, request, format, deltas)
+        self.request_resumable(Operation::GetMiraV1Swaps, request, format, deltas)
             .await
     }
 }
@@ -453,7 +1244,7 @@ impl BtcProvider for WsProvider {
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
         request.chains = HashSet::from_iter(vec![ChainId::BTC]);
-        self.request(Operation::GetBlocks, request, format, deltas)
+        self.request_resumable(Operation::GetBlocks, request, format, deltas)
             .await
     }
 
@@ -464,67 +1255,430 @@ impl BtcProvider for WsProvider {
         deltas: bool,
     ) -> StreamResponse<Vec<u8>> {
         request.chains = HashSet::from_iter(vec![ChainId::BTC]);
-        self.request(Operation::GetTxs, request, format, deltas)
+        self.request_resumable(Operation::GetTxs, request, format, deltas)
             .await
     }
 }
 
+/// Where the background worker connects to, kept to reconnect
+struct Server {
+    /// The URL of each endpoint, the primary one first
+    urls: Vec<String>,
+    endpoints: Arc<Endpoints>,
+    headers: header::HeaderMap,
+    credentials: Option<Arc<dyn CredentialsProvider>>,
+    connect_timeout: Duration,
+    tcp_keepalive: Option<Duration>,
+    tcp_nodelay: bool,
+    proxy: Proxy,
+    connector: Option<Connector>,
+    config: WebSocketConfig,
+}
+
+impl Server {
+    /// Connects to the endpoint in use, or fails over to the next ones if it
+    /// cannot be reached
+    async fn connect(&self) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        let (mut index, mut tried) = (self.endpoints.next(), 1);
+        loop {
+            let err = match self.connect_to(&self.urls[index]).await {
+                Ok(ws) => {
+                    debug!(endpoint = self.endpoints.get(index), "connected");
+                    return Ok(ws);
+                }
+                Err(err) => err,
+            };
+            if !endpoint_failed(&err) {
+                return Err(err);
+            }
+            let next = self.endpoints.failed(index);
+            if tried == self.urls.len() {
+                return Err(err);
+            }
+            warn!(
+                "{} failed, failing over to {}: {err}",
+                self.endpoints.get(index),
+                self.endpoints.get(next)
+            );
+            (index, tried) = (next, tried + 1);
+        }
+    }
+
+    async fn connect_to(&self, url: &str) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        let mut req = url.into_client_request()?;
+        req.headers_mut().extend(self.headers.clone());
+        if let Some(credentials) = &self.credentials {
+            let (username, password) = credentials.get().await?;
+            req.headers_mut()
+                .insert(header::AUTHORIZATION, basic_auth(&username, &password));
+        }
+
+        let host = req.uri().host().unwrap_or_default().to_string();
+        let is_secure = req.uri().scheme_str() == Some("wss");
+        let port = req
+            .uri()
+            .port_u16()
+            .unwrap_or(if is_secure { 443 } else { 80 });
+
+        let connect = async {
+            let socket = self.proxy.connect(&host, port, is_secure).await?;
+            socket.set_nodelay(self.tcp_nodelay)?;
+            if let Some(keepalive) = self.tcp_keepalive {
+                let keepalive = TcpKeepalive::new()
+                    .with_time(keepalive)
+                    .with_interval(keepalive);
+                SockRef::from(&socket).set_tcp_keepalive(&keepalive)?;
+            }
+            client_async_tls_with_config(req, socket, Some(self.config), self.connector.clone())
+                .await
+                .map_err(Error::from)
+        };
+        let (ws, _) = tokio::time::timeout(self.connect_timeout, connect)
+            .await
+            .map_err(|_| Error::ConnectTimeout(self.connect_timeout))?
+            .map_err(|err| match &err {
+                Error::Tungstenite(source) => match &**source {
+                    tungstenite::Error::Http(response)
+                        if matches!(response.status().as_u16(), 401 | 403) =>
+                    {
+                        Error::Unauthorized
+                    }
+                    _ => err,
+                },
+                _ => err,
+            })?;
+        Ok(ws)
+    }
+}
+
+/// Whether a connection failed because the endpoint is unreachable or broken,
+/// rather than because of the request
+fn endpoint_failed(err: &Error) -> bool {
+    match err {
+        Error::IO(_) | Error::ConnectTimeout(_) => true,
+        Error::Tungstenite(source) => match &**source {
+            tungstenite::Error::Io(_) => true,
+            tungstenite::Error::Http(response) => response.status().is_server_error(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn basic_auth(username: &str, password: &str) -> header::HeaderValue {
+    let encoded = BASE64.encode(format!("{username}:{password}"));
+    header::HeaderValue::from_str(&format!("Basic {encoded}"))
+        .expect("Only non-ascii chars result in an error")
+}
+
+/// An active request, kept to send it again after a reconnect
+struct ActiveRequest {
+    request: Request,
+    sink: Arc<Buffer>,
+    /// Taken with the first message of the response, which fails the request if
+    /// it is an error
+    ack: Option<oneshot::Sender<Result<()>>>,
+    /// The records delivered so far, for requests that resume after a reconnect
+    cursor: Option<Cursor>,
+}
+
+impl Drop for ActiveRequest {
+    fn drop(&mut self) {
+        self.sink.close(None);
+    }
+}
+
+impl ActiveRequest {
+    /// The request to send on a new connection
+    fn payload(&mut self) -> Result<Vec<u8>> {
+        let block = self.cursor.as_mut().and_then(Cursor::resume);
+        match (block, &self.request.resume) {
+            (Some((block, delivered)), Some(resume)) => {
+                let request = Request {
+                    params: resume(block, delivered)?,
+                    ..self.request.clone()
+                };
+                Ok(serde_json::to_vec(&request)?)
+            }
+            _ => Ok(serde_json::to_vec(&self.request)?),
+        }
+    }
+
+    /// Counts a message with data for [`WsProvider::stats`]
+    fn record(&self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        let last_block = match self.request.format {
+            Format::JsonStream => last_block_number(data),
+            _ => None,
+        };
+        self.sink.record(data, last_block);
+    }
+}
+
+/// Follows the records of a [`Format::JsonStream`] response line by line.
+///
+/// Lines are only delivered once complete, so that a line cut off by a dropped
+/// connection is not delivered half.
+#[derive(Default)]
+struct Cursor {
+    /// The unfinished last line of the data received so far
+    partial: Vec<u8>,
+    /// The last delivered block and the number of its delivered records
+    last: Option<(i64, usize)>,
+    /// The records of the last block that are received again after a resume
+    skip: Option<(i64, usize)>,
+    /// The delivered records with a block
+    delivered: u64,
+}
+
+impl Cursor {
+    /// Returns the complete lines of `data` that were not delivered yet
+    fn push(&mut self, data: &[u8]) -> Vec<u8> {
+        self.partial.extend_from_slice(data);
+        let Some(end) = self.partial.iter().rposition(|byte| *byte == b'\n') else {
+            return Vec::new();
+        };
+        let partial = self.partial.split_off(end + 1);
+        let lines = std::mem::replace(&mut self.partial, partial);
+
+        let mut data = Vec::with_capacity(lines.len());
+        for line in lines.split_inclusive(|byte| *byte == b'\n') {
+            if self.deliver(line) {
+                data.extend_from_slice(line);
+            }
+        }
+        data
+    }
+
+    fn deliver(&mut self, line: &[u8]) -> bool {
+        let Some(block) = block_number(line) else {
+            return true;
+        };
+        if let Some((boundary, remaining)) = &mut self.skip {
+            if *boundary == block && *remaining > 0 {
+                *remaining -= 1;
+                return false;
+            }
+            self.skip = None;
+        }
+
+        self.last = match self.last {
+            Some((last, records)) if last == block => Some((last, records + 1)),
+            _ => Some((block, 1)),
+        };
+        self.delivered += 1;
+        true
+    }
+
+    /// Returns the unfinished last line at the end of the response
+    fn finish(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.partial)
+    }
+
+    /// Returns the block to resume from with the number of records delivered
+    /// before it, and skips its delivered records
+    fn resume(&mut self) -> Option<(i64, u64)> {
+        self.partial.clear();
+        self.skip = self.last;
+        self.last
+            .map(|(block, records)| (block, self.delivered - records as u64))
+    }
+}
+
+/// The block of the last complete record in `data`
+fn last_block_number(data: &[u8]) -> Option<i64> {
+    data.split(|byte| *byte == b'\n')
+        .rev()
+        .find_map(block_number)
+}
+
 struct BackgroundWorker {
-    ws: WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
+    server: Server,
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
     operations: Fuse<UnboundedReceiverStream<OperationMsg>>,
-    subscriptions: HashMap<Uuid, mpsc::Sender<Result<Vec<u8>>>>,
+    subscriptions: HashMap<Uuid, ActiveRequest>,
+    config: WsConfig,
+    events: broadcast::Sender<ConnectionEvent>,
+    /// Dropped with the worker, which completes [`WsProvider::shutdown`]
+    shutdown: Option<oneshot::Sender<()>>,
+    /// When the first message that is not flushed yet was written
+    unflushed: Option<Instant>,
 }
 
 impl BackgroundWorker {
-    pub async fn new(
-        ws_server: http::Request<()>,
+    async fn new(
+        server: Server,
         operations: mpsc::UnboundedReceiver<OperationMsg>,
+        config: WsConfig,
+        events: broadcast::Sender<ConnectionEvent>,
     ) -> Result<Self> {
-        let config = WebSocketConfig {
-            max_frame_size: None,
-            max_message_size: None,
-            ..Default::default()
-        };
-        let (ws, _) = connect_async_with_config(ws_server, Some(config), false).await?;
+        let ws = server.connect().await?;
         let operations = UnboundedReceiverStream::new(operations).fuse();
 
         Ok(Self {
+            server,
             ws,
             operations,
             subscriptions: HashMap::default(),
+            config,
+            events,
+            shutdown: None,
+            unflushed: None,
         })
     }
 
-    pub async fn main_loop(mut self) {
-        let Err(err) = self.try_run().await else {
-            return;
+    async fn main_loop(mut self) {
+        let err = loop {
+            let Err(err) = self.try_run().await else {
+                return;
+            };
+
+            error!("Websocket connection failed: {err}");
+            let _ = self.events.send(ConnectionEvent::Disconnected {
+                reason: err.to_string(),
+            });
+
+            if let Error::MessageTooLarge { .. } = err {
+                break err;
+            }
+            match self.reconnect(err).await {
+                Ok(()) => continue,
+                Err(err) => break err,
+            }
         };
 
-        error!("Websocket connection failed: {err}");
+        for sub in self.subscriptions.values_mut() {
+            let err = match err {
+                Error::ConnectionStale => Error::ConnectionStale,
+                Error::MessageTooLarge { size, limit } => Error::MessageTooLarge { size, limit },
+                Error::Unauthorized => Error::Unauthorized,
+                ref err => Error::ErrorMsg(err.to_string()),
+            };
+            sub.sink.close(Some(err));
+        }
+    }
+
+    /// Re-establishes the connection and sends the active subscriptions again,
+    /// or returns the last error once the policy gives up
+    async fn reconnect(&mut self, err: Error) -> Result<()> {
+        let Some(policy) = self.config.reconnect else {
+            return Err(err);
+        };
+
+        // the consumers of dropped streams are not interested in a new connection
+        self.subscriptions.retain(|_, sub| !sub.sink.is_dropped());
+
+        // fixed credentials would be refused again
+        let max_auth_failures = match self.server.credentials {
+            Some(_) => self.config.max_auth_failures.max(1),
+            None => 1,
+        };
+        let mut auth_failures = 0;
+        let mut last_err = err;
+        for attempt in 1..=policy.max_attempts {
+            let delay = policy.delay(attempt);
+            let _ = self
+                .events
+                .send(ConnectionEvent::Reconnecting { attempt, delay });
+            tokio::time::sleep(delay).await;
+
+            match self.resubscribe().await {
+                Ok(()) => {
+                    warn!("Websocket reconnected after {attempt} attempt(s)");
+                    let _ = self.events.send(ConnectionEvent::Reconnected {
+                        attempt,
+                        subscriptions: self.subscriptions.len(),
+                    });
+                    return Ok(());
+                }
+                Err(Error::Unauthorized) => {
+                    error!("Websocket reconnect attempt {attempt} was refused");
+                    let reason = Error::Unauthorized.to_string();
+                    let _ = self.events.send(ConnectionEvent::AuthFailed {
+                        reason: reason.clone(),
+                    });
+                    auth_failures += 1;
+                    if auth_failures >= max_auth_failures {
+                        let _ = self.events.send(ConnectionEvent::GaveUp {
+                            attempts: attempt,
+                            reason,
+                        });
+                        return Err(Error::Unauthorized);
+                    }
+                    last_err = Error::Unauthorized;
+                }
+                Err(err) => {
+                    warn!("Websocket reconnect attempt {attempt} failed: {err}");
+                    auth_failures = 0;
+                    last_err = err;
+                }
+            }
+        }
+
+        let _ = self.events.send(ConnectionEvent::GaveUp {
+            attempts: policy.max_attempts,
+            reason: last_err.to_string(),
+        });
+        Err(last_err)
+    }
 
-        let err = err.to_string();
+    async fn resubscribe(&mut self) -> Result<()> {
+        self.ws = self.server.connect().await?;
+        self.unflushed = None;
+        let _ = self.events.send(ConnectionEvent::Connected);
         for sub in self.subscriptions.values_mut() {
-            let _ = sub.send(Err(Error::ErrorMsg(err.clone()))).await;
+            let payload = sub.payload()?;
+            self.ws.send(Message::Binary(payload)).await?;
         }
+        Ok(())
     }
 
     async fn try_run(&mut self) -> Result<()> {
-        let mut ping_interval = tokio::time::interval(Duration::from_secs(5));
-        let mut latest_msg_stamp = Instant::now();
+        let mut ping_interval = tokio::time::interval(self.config.ping_interval);
+        let pong_timeout = self.config.pong_timeout;
+        // when the first ping without a message since was sent
+        let mut unanswered_ping: Option<Instant> = None;
 
         loop {
+            let stale = async {
+                match unanswered_ping {
+                    Some(sent) => tokio::time::sleep_until((sent + pong_timeout).into()).await,
+                    None => futures::future::pending().await,
+                }
+            };
+            let unflushed = self.unflushed;
+            let flush = async {
+                match (unflushed, self.config.flush) {
+                    (Some(written), FlushPolicy::Coalesce(delay)) => {
+                        tokio::time::sleep_until((written + delay).into()).await
+                    }
+                    _ => futures::future::pending().await,
+                }
+            };
+
             select_biased! {
+                _ = stale.fuse() => return Err(Error::ConnectionStale),
+                _ = flush.fuse() => {
+                    self.unflushed = None;
+                    self.ws.flush().await?
+                }
                 _ = ping_interval.tick().fuse() => {
-                    if latest_msg_stamp.elapsed().as_secs_f64() > 9.0 {
-                        return Err(Error::PingTimeout);
-                    }
-                    self.ws.send(Message::Ping(Vec::new())).await?
+                    unanswered_ping.get_or_insert_with(Instant::now);
+                    self.write(Message::Ping(Vec::new())).await?
                 }
                 operation = self.operations.next() => {
                     match operation {
-                        Some(operation) => self.operate(operation).await?,
+                        Some(operation) => {
+                            self.operate(operation).await?;
+                            if self.shutdown.is_some() {
+                                self.close().await;
+                                return Ok(())
+                            }
+                        }
                         None => {
-                            self.ws.close(None).await?;
+                            self.close().await;
                             return Ok(())
                         },
                     }
@@ -532,29 +1686,99 @@ impl BackgroundWorker {
                 resp = self.ws.try_next() => {
                     match resp {
                         Ok(Some(message)) => {
-                            latest_msg_stamp = Instant::now();
+                            unanswered_ping = None;
                             self.handle(message).await?;
                         }
                         Ok(None) => return Err(Error::UnexpectedClose),
-                        Err(e) => return Err(Error::Tungstenite(e)),
+                        Err(e) => return Err(e.into()),
                     }
                 }
             }
         }
     }
 
+    /// Writes a message, and flushes it as the flush policy says
+    async fn write(&mut self, message: Message) -> Result<()> {
+        match self.config.flush {
+            FlushPolicy::Immediate => self.ws.send(message).await?,
+            FlushPolicy::Coalesce(_) => {
+                self.ws.feed(message).await?;
+                self.unflushed.get_or_insert_with(Instant::now);
+            }
+        }
+        Ok(())
+    }
+
+    /// Closes the connection, and passes on the messages the server sent before
+    /// it answered the close
+    async fn close(&mut self) {
+        let close = async {
+            self.ws.close(None).await?;
+            while let Some(message) = self.ws.try_next().await? {
+                match message {
+                    Message::Close(_) => break,
+                    message => self.handle(message).await?,
+                }
+            }
+            Ok::<_, Error>(())
+        };
+
+        match tokio::time::timeout(CLOSE_TIMEOUT, close).await {
+            Ok(Ok(())) => debug!("Websocket connection closed"),
+            Ok(Err(err)) => debug!("Websocket connection closed with an error: {err}"),
+            Err(_) => debug!("Websocket server did not answer the close in time"),
+        }
+    }
+
     async fn operate(&mut self, operation: OperationMsg) -> Result<()> {
-        let (request, sink) = operation;
+        let (request, sink, ack) = match operation {
+            OperationMsg::Request(request, sink, ack) => (request, sink, ack),
+            OperationMsg::Cancel(id) => return self.cancel(id).await,
+            OperationMsg::Shutdown(shutdown) => {
+                self.shutdown = Some(shutdown);
+                return Ok(());
+            }
+        };
         let payload = serde_json::to_vec(&request)?;
 
-        if self.subscriptions.insert(request.id, sink).is_some() {
+        let resumable = self.config.reconnect.is_some()
+            && request.resume.is_some()
+            && request.format == Format::JsonStream;
+        let subscription = ActiveRequest {
+            request: request.clone(),
+            sink,
+            ack: Some(ack),
+            cursor: resumable.then(Cursor::default),
+        };
+        if self
+            .subscriptions
+            .insert(request.id, subscription)
+            .is_some()
+        {
             warn!(
                 "Replacing already-registered subscription with id {:?}",
                 request.id
             );
         }
 
-        self.ws.send(Message::Binary(payload)).await?;
+        self.write(Message::Binary(payload)).await?;
+
+        Ok(())
+    }
+
+    /// Tells the server to stop a request whose stream was dropped, unless it
+    /// ended already
+    async fn cancel(&mut self, id: Uuid) -> Result<()> {
+        if self.subscriptions.remove(&id).is_none() {
+            return Ok(());
+        }
+
+        debug!("Subscription with id {:?} was cancelled", id);
+        let payload = serde_json::to_vec(&Cancel {
+            id,
+            operation: Operation::Unsubscribe,
+        })?;
+        self.write(Message::Binary(payload)).await?;
 
         Ok(())
     }
@@ -571,7 +1795,7 @@ impl BackgroundWorker {
     }
 
     async fn handle_ping(&mut self, inner: Vec<u8>) -> Result<()> {
-        self.ws.send(Message::Pong(inner)).await?;
+        self.write(Message::Pong(inner)).await?;
         Ok(())
     }
 
@@ -579,57 +1803,128 @@ impl BackgroundWorker {
         let (header, data) = Header::try_from_data(data)?;
         let id = header.id;
 
+        if !matches!(header.kind, Kind::Error) {
+            if let Some(ack) = self
+                .subscriptions
+                .get_mut(&id.0)
+                .and_then(|subscription| subscription.ack.take())
+            {
+                let _ = ack.send(Ok(()));
+            }
+        }
+
         let msg = match header.kind {
             Kind::Start => {
                 return Ok(());
             }
-            Kind::Continue => Ok(data),
-            Kind::ContinueWithError => match data.first() {
-                Some(b'{') => match serde_json::from_slice::<ResponseError>(&data) {
-                    Ok(err) => Err(Error::ErrorResponse(err)),
-                    Err(_) => Err(Error::UnexpectedMessageFormat),
-                },
-                _ => match String::from_utf8(data) {
-                    Ok(s) => Err(Error::ErrorMsg(s)),
-                    Err(_) => Err(Error::UnexpectedMessageFormat),
-                },
+            Kind::Continue => match self.subscriptions.get_mut(&id.0) {
+                Some(ActiveRequest {
+                    cursor: Some(cursor),
+                    ..
+                }) => Ok(cursor.push(&data)),
+                _ => Ok(data),
             },
+            Kind::ContinueWithError => Err(error_from_data(data)),
             Kind::End => {
                 debug!("Subscription with id {:?} ended", id);
-                self.subscriptions.remove(&header.id.0);
+                if let Some(mut subscription) = self.subscriptions.remove(&header.id.0) {
+                    if let Some(cursor) = &mut subscription.cursor {
+                        let data = cursor.finish();
+                        subscription.record(&data);
+                        subscription.sink.push(Ok(data)).await;
+                    }
+                }
+                return Ok(());
+            }
+            Kind::Error => {
+                // the request failed as a whole, e.g. when the server refused it.
+                // Before any other message the request itself fails, afterwards
+                // its stream ends with the error.
+                let err = match error_from_data(data) {
+                    Error::ErrorResponse(err) if err.status == 429 => {
+                        Error::TooManySubscriptions { limit: None }
+                    }
+                    err => err,
+                };
+                if let Some(mut subscription) = self.subscriptions.remove(&id.0) {
+                    match subscription.ack.take() {
+                        Some(ack) => {
+                            let _ = ack.send(Err(err));
+                        }
+                        None => subscription.sink.close(Some(err)),
+                    }
+                }
                 return Ok(());
             }
-            Kind::Error => match String::from_utf8(data) {
-                Ok(s) => Err(Error::ErrorMsg(s)),
-                Err(_) => Err(Error::UnexpectedMessageFormat),
-            },
             _ => Err(Error::UnexpectedMessageFormat),
         };
 
-        if let std::collections::hash_map::Entry::Occupied(mut occupied) =
-            self.subscriptions.entry(id.0)
-        {
-            if let Err(err) = occupied.get_mut().send(msg).await {
-                return Err(Error::Custom(
-                    format!("failed to send message: {err:?}").into(),
-                ));
+        let pushed = match self.subscriptions.get(&id.0) {
+            Some(subscription) => {
+                if let Ok(data) = &msg {
+                    subscription.record(data);
+                }
+                subscription.sink.push(msg).await
+            }
+            None => return Ok(()),
+        };
+        match pushed {
+            Pushed::Buffered => Ok(()),
+            // the consumer dropped the stream, e.g. after reading the first record,
+            // which may be noticed here before the cancel operation arrives
+            Pushed::Dropped => self.cancel(id.0).await,
+            Pushed::Overflowed => {
+                warn!("The buffer of subscription with id {:?} overflowed", id);
+                if let Some(subscription) = self.subscriptions.get(&id.0) {
+                    subscription.sink.close(Some(Error::BufferOverflow));
+                }
+                self.cancel(id.0).await
             }
         }
+    }
+}
 
-        Ok(())
+/// Reads the error of an error message, which is a [`ResponseError`] or plain text
+fn error_from_data(data: Vec<u8>) -> Error {
+    match data.first() {
+        Some(b'{') => match serde_json::from_slice::<ResponseError>(&data) {
+            Ok(err) => Error::ErrorResponse(err),
+            Err(_) => Error::UnexpectedMessageFormat,
+        },
+        _ => match String::from_utf8(data) {
+            Ok(s) => Error::ErrorMsg(s),
+            Err(_) => Error::UnexpectedMessageFormat,
+        },
     }
 }
 
+enum OperationMsg {
+    Request(Request, Arc<Buffer>, oneshot::Sender<Result<()>>),
+    /// The stream of a request was dropped
+    Cancel(Uuid),
+    /// Closes the connection, the sender is dropped once it is closed
+    Shutdown(oneshot::Sender<()>),
+}
+
+/// Stops the request with the same id
+#[derive(serde::Serialize)]
+struct Cancel {
+    id: Uuid,
+    operation: Operation,
+}
+
 #[derive(Clone, serde::Serialize)]
 struct Request {
     id: Uuid,
     operation: Operation,
     #[serde(flatten)]
-    params: HashMap<String, serde_json::Value>,
+    params: Params,
     #[serde(default)]
     format: Format,
     #[serde(default)]
     deltas: bool,
+    #[serde(skip)]
+    resume: Option<Resume>,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -664,6 +1959,7 @@ pub enum Operation {
     GetMiraV1Pools,
     GetMiraV1Liqudity,
     GetMiraV1Swaps,
+    Unsubscribe,
 }
 
 #[derive(Debug, Clone, Deserialize)]
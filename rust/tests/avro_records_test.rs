@@ -0,0 +1,135 @@
+#![cfg(feature = "avro")]
+
+use apache_avro::types::Value;
+use futures::StreamExt;
+use pangea_client::{
+    avro::{avro_records, avro_records_as},
+    provider::ResponseStream,
+    Error,
+};
+
+const SCHEMA: &str = r#"{"type":"record","name":"Transfer","fields":[{"name":"block_number","type":"long"},{"name":"from","type":"string"},{"name":"value","type":"long"}]}"#;
+const SYNC: [u8; 16] = *b"0123456789abcdef";
+
+fn long(out: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn bytes(out: &mut Vec<u8>, value: &[u8]) {
+    long(out, value.len() as i64);
+    out.extend_from_slice(value);
+}
+
+/// Encodes an object container holding `blocks` of `(block_number, from, value)` records
+fn container(blocks: &[&[(i64, &str, i64)]]) -> Vec<u8> {
+    let mut out = b"Obj\x01".to_vec();
+    long(&mut out, 2);
+    bytes(&mut out, b"avro.schema");
+    bytes(&mut out, SCHEMA.as_bytes());
+    bytes(&mut out, b"avro.codec");
+    bytes(&mut out, b"null");
+    long(&mut out, 0);
+    out.extend_from_slice(&SYNC);
+
+    for records in blocks {
+        let mut data = Vec::new();
+        for (block_number, from, value) in records.iter() {
+            long(&mut data, *block_number);
+            bytes(&mut data, from.as_bytes());
+            long(&mut data, *value);
+        }
+        long(&mut out, records.len() as i64);
+        bytes(&mut out, &data);
+        out.extend_from_slice(&SYNC);
+    }
+    out
+}
+
+fn chunked(data: &[u8], size: usize) -> ResponseStream<Vec<u8>> {
+    let chunks = data.chunks(size).map(<[u8]>::to_vec).collect::<Vec<_>>();
+    futures::stream::iter(chunks.into_iter().map(Ok)).boxed()
+}
+
+const FIRST: &[(i64, &str, i64)] = &[(19_000_000, "0xdead", 1), (19_000_001, "0xbeef", -5)];
+const SECOND: &[(i64, &str, i64)] = &[(19_000_002, "0xf00d", i64::MAX)];
+
+#[tokio::test]
+async fn blocks_split_across_chunks() {
+    let data = container(&[FIRST, SECOND]);
+
+    for size in [1, 5, 64, data.len()] {
+        let mut records = avro_records(chunked(&data, size));
+        assert!(records.schema().is_none());
+
+        let mut values = Vec::new();
+        while let Some(value) = records.next().await {
+            values.push(value.unwrap());
+            assert!(records.schema().is_some());
+        }
+
+        assert_eq!(values.len(), 3, "chunk size {size}");
+        assert_eq!(
+            values[1],
+            Value::Record(vec![
+                ("block_number".to_string(), Value::Long(19_000_001)),
+                ("from".to_string(), Value::String("0xbeef".to_string())),
+                ("value".to_string(), Value::Long(-5)),
+            ])
+        );
+    }
+}
+
+#[tokio::test]
+async fn typed_records() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Transfer {
+        block_number: i64,
+        from: String,
+        value: i64,
+    }
+
+    let transfers = avro_records_as::<Transfer>(chunked(&container(&[FIRST, SECOND]), 7))
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(transfers.len(), 3);
+    assert_eq!(transfers[2].from, "0xf00d");
+    assert_eq!(transfers[2].value, i64::MAX);
+}
+
+#[tokio::test]
+async fn sync_marker_mismatch() {
+    let mut data = container(&[FIRST, SECOND]);
+    let last = data.len() - 1;
+    data[last] ^= 0xff;
+
+    let results = avro_records(chunked(&data, 11)).collect::<Vec<_>>().await;
+
+    // the first block is intact, the second one is rejected
+    assert_eq!(results.len(), 3);
+    assert!(results[..2].iter().all(Result::is_ok));
+    assert!(matches!(results[2], Err(Error::AvroContainer(_))));
+}
+
+#[tokio::test]
+async fn not_a_container() {
+    let results = avro_records(chunked(b"{\"status\":\"ok\"}\n", 4))
+        .collect::<Vec<_>>()
+        .await;
+    assert!(matches!(results[..], [Err(Error::AvroContainer(_))]));
+}
+
+#[tokio::test]
+async fn truncated_container() {
+    let data = container(&[FIRST]);
+    let results = avro_records(chunked(&data[..data.len() - 3], 8))
+        .collect::<Vec<_>>()
+        .await;
+    assert!(matches!(results[..], [Err(Error::AvroContainer(_))]));
+}
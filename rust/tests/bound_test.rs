@@ -0,0 +1,61 @@
+use pangea_client::query::Bound;
+
+const BOUNDS: [Bound; 6] = [
+    Bound::Exact(19_000_000),
+    Bound::Exact(0),
+    Bound::Latest,
+    Bound::FromLatest(1000),
+    Bound::Earliest,
+    Bound::Subscribe,
+];
+
+#[test]
+fn bounds_round_trip_through_json() {
+    for bound in BOUNDS {
+        let json = serde_json::to_string(&bound).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Bound>(&json).unwrap(),
+            bound,
+            "{json}"
+        );
+    }
+}
+
+#[test]
+fn bounds_round_trip_through_strings() {
+    // query parameters arrive as strings
+    for bound in BOUNDS {
+        let text = match serde_json::to_value(bound).unwrap() {
+            serde_json::Value::String(text) => text,
+            number => number.to_string(),
+        };
+        let json = serde_json::Value::String(text.clone());
+        assert_eq!(
+            serde_json::from_value::<Bound>(json).unwrap(),
+            bound,
+            "{text}"
+        );
+    }
+}
+
+#[test]
+fn bounds_are_serialized_as_the_server_expects() {
+    let json = |bound| serde_json::to_value(bound).unwrap();
+
+    assert_eq!(json(Bound::Exact(5)), 5);
+    assert_eq!(json(Bound::FromLatest(1000)), -1000);
+    assert_eq!(json(Bound::Latest), "latest");
+    assert_eq!(json(Bound::Earliest), "earliest");
+    assert_eq!(json(Bound::Subscribe), "none");
+    assert_eq!(
+        serde_json::from_str::<Bound>("\"latest - 10\"").unwrap(),
+        Bound::FromLatest(10)
+    );
+}
+
+#[test]
+fn earliest_comes_before_every_other_bound() {
+    for bound in &BOUNDS[..4] {
+        assert!(Bound::Earliest < *bound, "{bound:?}");
+    }
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "clickhouse")]
+
+mod common;
+
+use std::time::Duration;
+
+use clickhouse::{Client, Compression, Row};
+use common::{listen, read_head};
+use futures::StreamExt;
+use pangea_client::{
+    core::types::blocks::Block,
+    sinks::clickhouse::{insert, InsertOptions, InsertSummary},
+    Error, Result,
+};
+use serde::Serialize;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    task::JoinHandle,
+};
+
+const OK: &str = "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+const FAILED: &str =
+    "HTTP/1.1 500 Internal Server Error\r\ncontent-length: 6\r\nconnection: close\r\n\r\nfailed";
+
+#[derive(Row, Serialize)]
+struct BlockRow {
+    block_number: u64,
+}
+
+/// Reads the chunked body of an `INSERT`
+async fn read_body(socket: &mut TcpStream) -> Vec<u8> {
+    let mut reader = BufReader::new(socket);
+    let mut body = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        let size = usize::from_str_radix(line.trim_end(), 16).unwrap();
+        let mut chunk = vec![0; size + 2];
+        reader.read_exact(&mut chunk).await.unwrap();
+        if size == 0 {
+            return body;
+        }
+        body.extend_from_slice(&chunk[..size]);
+    }
+}
+
+/// Answers an `INSERT` per response, and returns the block numbers of every
+/// `INSERT`
+async fn serve_inserts(responses: Vec<&'static str>) -> (String, JoinHandle<Vec<Vec<u64>>>) {
+    let (listener, endpoint) = listen().await;
+
+    let server = tokio::spawn(async move {
+        let mut inserts = Vec::new();
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let head = read_head(&mut socket).await;
+            assert!(head.starts_with("POST "), "{head}");
+            let rows = read_body(&mut socket).await;
+            inserts.push(
+                rows.chunks(8)
+                    .map(|row| u64::from_le_bytes(row.try_into().unwrap()))
+                    .collect(),
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+        inserts
+    });
+
+    (endpoint, server)
+}
+
+fn blocks(numbers: &[u64]) -> Vec<Result<Block>> {
+    numbers
+        .iter()
+        .map(|&number| {
+            Ok(Block {
+                block_number: number.into(),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+async fn insert_blocks(
+    endpoint: &str,
+    records: Vec<Result<Block>>,
+    options: InsertOptions,
+) -> Result<InsertSummary> {
+    let client = Client::default()
+        .with_url(format!("http://{endpoint}"))
+        .with_compression(Compression::None);
+    let stream = futures::stream::iter(records).boxed();
+    let mapping = |block: &Block| BlockRow {
+        block_number: block.block_number.as_u64(),
+    };
+    insert(Ok(stream), &client, "blocks", mapping, options).await
+}
+
+fn options(batch_size: usize, retries: usize) -> InsertOptions {
+    InsertOptions {
+        batch_size,
+        retries,
+        retry_delay: Duration::from_millis(10),
+    }
+}
+
+#[tokio::test]
+async fn batches_are_cut_on_block_boundaries() {
+    let (endpoint, server) = serve_inserts(vec![OK; 3]).await;
+
+    let records = blocks(&[1, 1, 2, 2, 2, 3]);
+    let summary = insert_blocks(&endpoint, records, options(2, 0))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        summary,
+        InsertSummary {
+            rows: 6,
+            last_block: Some(3)
+        }
+    );
+    // a full batch keeps growing until its last block is complete
+    assert_eq!(server.await.unwrap(), [vec![1, 1], vec![2, 2, 2], vec![3]]);
+}
+
+#[tokio::test]
+async fn failed_batches_are_retried() {
+    let (endpoint, server) = serve_inserts(vec![FAILED, OK]).await;
+
+    let summary = insert_blocks(&endpoint, blocks(&[1, 2]), options(10, 1))
+        .await
+        .unwrap();
+
+    assert_eq!(summary.rows, 2);
+    assert_eq!(summary.last_block, Some(2));
+    assert_eq!(server.await.unwrap(), [vec![1, 2], vec![1, 2]]);
+}
+
+#[tokio::test]
+async fn failed_batches_report_the_uncommitted_blocks() {
+    let (endpoint, server) = serve_inserts(vec![OK, FAILED, FAILED]).await;
+
+    let err = insert_blocks(&endpoint, blocks(&[1, 2, 3, 3]), options(2, 1))
+        .await
+        .unwrap_err();
+
+    let Error::UncommittedBlocks {
+        from_block,
+        to_block,
+        source,
+    } = err
+    else {
+        panic!("{err:?}");
+    };
+    assert_eq!((from_block, to_block), (Some(3), Some(3)));
+    assert!(matches!(*source, Error::ClickHouse(_)), "{source:?}");
+    assert_eq!(server.await.unwrap(), [vec![1, 2], vec![3, 3], vec![3, 3]]);
+}
+
+#[tokio::test]
+async fn failed_streams_report_the_uncommitted_blocks() {
+    let (endpoint, server) = serve_inserts(vec![OK]).await;
+
+    let mut records = blocks(&[1, 1, 2]);
+    records.push(Err(Error::ConnectionClosed));
+    let err = insert_blocks(&endpoint, records, options(2, 0))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            Error::UncommittedBlocks {
+                from_block: Some(2),
+                to_block: Some(2),
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+    assert_eq!(server.await.unwrap(), [vec![1, 1]]);
+}
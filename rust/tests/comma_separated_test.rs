@@ -0,0 +1,88 @@
+use std::collections::{BTreeSet, HashSet};
+
+use ethers_core::types::Address;
+use pangea_client::{
+    requests::logs::GetLogsRequest,
+    utils::{serialize_comma_separated, CommaSeparated},
+    ChainId,
+};
+use proptest::prelude::*;
+
+fn round_trip<C>(value: &CommaSeparated<C>) -> CommaSeparated<C>
+where
+    CommaSeparated<C>: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let json = serde_json::to_value(value).unwrap();
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn vecs_keep_their_order() {
+    let columns = CommaSeparated(vec!["to", "from", "value"]);
+    assert_eq!(serde_json::to_value(&columns).unwrap(), "to,from,value");
+}
+
+#[test]
+fn empty_collections_are_none() {
+    let mut json = serde_json::Serializer::new(Vec::new());
+    serialize_comma_separated(&HashSet::<u64>::new(), &mut json).unwrap();
+    assert_eq!(json.into_inner(), b"null");
+
+    let empty: CommaSeparated<Vec<u64>> = serde_json::from_str("null").unwrap();
+    assert!(empty.0.is_empty());
+}
+
+#[test]
+fn sequences_and_strings_are_deserialized() {
+    let from_seq: CommaSeparated<Vec<u64>> = serde_json::from_str("[1, 2]").unwrap();
+    let from_str: CommaSeparated<Vec<u64>> = serde_json::from_str(r#""1,2""#).unwrap();
+    assert_eq!(from_seq, from_str);
+
+    let err = serde_json::from_str::<CommaSeparated<Vec<u64>>>(r#""1,x""#).unwrap_err();
+    assert!(err.to_string().contains("invalid item \"x\""), "{err}");
+}
+
+#[test]
+fn requests_round_trip_through_their_query_form() {
+    let request = GetLogsRequest {
+        chains: HashSet::from([ChainId::ETH, ChainId::ARB]),
+        address__in: HashSet::from([Address::repeat_byte(1), Address::repeat_byte(2)]),
+        columns: vec!["data".into(), "address".into()],
+        ..Default::default()
+    };
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["chains"], "ARB,ETH");
+    let back: GetLogsRequest = serde_json::from_value(json).unwrap();
+    assert_eq!(back.chains, request.chains);
+    assert_eq!(back.address__in, request.address__in);
+    assert_eq!(back.columns, request.columns);
+}
+
+proptest! {
+    #[test]
+    fn vecs_round_trip(items in prop::collection::vec(any::<u64>(), 0..16)) {
+        let value = CommaSeparated(items);
+        prop_assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn string_sets_round_trip(items in prop::collection::btree_set("[a-zA-Z0-9_]{1,8}", 0..16)) {
+        let value = CommaSeparated(items);
+        prop_assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn hash_sets_are_serialized_in_a_stable_order(
+        items in prop::collection::vec(any::<[u8; 20]>(), 0..16)
+    ) {
+        let addresses: Vec<Address> = items.into_iter().map(Address::from).collect();
+        let forward = CommaSeparated(addresses.iter().copied().collect::<HashSet<_>>());
+        let backward = CommaSeparated(addresses.iter().rev().copied().collect::<HashSet<_>>());
+        let sorted = CommaSeparated(addresses.iter().copied().collect::<BTreeSet<_>>());
+
+        let forward = serde_json::to_value(&forward).unwrap();
+        prop_assert_eq!(&forward, &serde_json::to_value(&backward).unwrap());
+        prop_assert_eq!(&forward, &serde_json::to_value(&sorted).unwrap());
+    }
+}
@@ -0,0 +1,108 @@
+//! A raw HTTP server for the tests of the providers, and the query strings
+//! of requests
+#![allow(dead_code)]
+
+pub mod ws;
+
+use serde::Serialize;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// The query string `request` is sent with
+pub fn query(request: &impl Serialize) -> String {
+    let request = pangea_client::reqwest::Client::new()
+        .get("http://localhost/v1/api")
+        .query(request)
+        .build()
+        .unwrap();
+    request.url().query().unwrap_or_default().to_string()
+}
+
+/// A listener on a free local port, and its endpoint
+pub async fn listen() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+    (listener, endpoint)
+}
+
+/// An endpoint nothing listens on
+pub async fn unreachable() -> String {
+    listen().await.1
+}
+
+/// Reads the head of a request, up to its body
+pub async fn read_head(socket: &mut TcpStream) -> String {
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(socket.read_u8().await.unwrap());
+    }
+    String::from_utf8(head).unwrap()
+}
+
+/// Reads the body of a request with the given head, by its `content-length`
+pub async fn read_body(socket: &mut TcpStream, head: &str) -> Vec<u8> {
+    let length = head
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse().unwrap())
+        })
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    socket.read_exact(&mut body).await.unwrap();
+    body
+}
+
+/// A `200 OK` response of `body`, closing the connection
+pub fn ok(content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Answers a single request with the raw `response`, and returns the head of
+/// the request
+pub async fn serve_once(
+    response: impl AsRef<[u8]> + Send + 'static,
+) -> (String, JoinHandle<String>) {
+    let (listener, endpoint) = listen().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let head = read_head(&mut socket).await;
+        socket.write_all(response.as_ref()).await.unwrap();
+        head
+    });
+
+    (endpoint, server)
+}
+
+/// Answers one connection after the other with the raw `responses`, closing it
+/// without an answer for `None`, and returns the heads of the requests
+pub async fn serve_all<R>(responses: Vec<R>) -> (String, JoinHandle<Vec<String>>)
+where
+    R: Into<Option<String>> + Send + 'static,
+{
+    let (listener, endpoint) = listen().await;
+
+    let server = tokio::spawn(async move {
+        let mut heads = Vec::new();
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let head = read_head(&mut socket).await;
+            read_body(&mut socket, &head).await;
+            heads.push(head);
+            if let Some(response) = response.into() {
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        }
+        heads
+    });
+
+    (endpoint, server)
+}
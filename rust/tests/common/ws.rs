@@ -0,0 +1,53 @@
+//! A WebSocket server that answers like the Pangea backend
+
+use futures::{SinkExt, StreamExt};
+use pangea_client::{
+    tokio_tungstenite::{accept_async, WebSocketStream},
+    tungstenite::Message,
+};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Accepts the next connection as a WebSocket
+pub async fn accept(listener: &TcpListener) -> WebSocketStream<TcpStream> {
+    let (socket, _) = listener.accept().await.unwrap();
+    accept_async(socket).await.unwrap()
+}
+
+/// A frame of the response to the request `id`, followed by `data`
+pub fn frame(kind: &str, id: &str, data: &[u8]) -> Message {
+    let mut frame =
+        format!("{{\"kind\":\"{kind}\",\"id\":\"{id}\",\"counter\":0,\"epoch\":null}}\n")
+            .into_bytes();
+    frame.extend_from_slice(data);
+    Message::Binary(frame)
+}
+
+/// The next binary message sent by the client, skipping pings and pongs
+pub async fn next_payload(ws: &mut WebSocketStream<TcpStream>) -> Vec<u8> {
+    loop {
+        if let Message::Binary(payload) = ws.next().await.unwrap().unwrap() {
+            return payload;
+        }
+    }
+}
+
+/// Acknowledges the message in `payload` if it is a request, and returns it
+pub async fn acknowledge(ws: &mut WebSocketStream<TcpStream>, payload: &[u8]) -> serde_json::Value {
+    let message: serde_json::Value = serde_json::from_slice(payload).unwrap();
+    if message["operation"] != "unsubscribe" {
+        let id = message["id"].as_str().unwrap();
+        ws.send(frame("Start", id, b"")).await.unwrap();
+    }
+    message
+}
+
+/// Returns the next message sent by the client, and acknowledges requests
+pub async fn next_message(ws: &mut WebSocketStream<TcpStream>) -> serde_json::Value {
+    let payload = next_payload(ws).await;
+    acknowledge(ws, &payload).await
+}
+
+/// Acknowledges the next request sent by the client and returns its id
+pub async fn next_request(ws: &mut WebSocketStream<TcpStream>) -> String {
+    next_message(ws).await["id"].as_str().unwrap().to_string()
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "compression")]
+
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
+use futures::StreamExt;
+use pangea_client::{
+    sinks::{write_compressed, Compression},
+    Error,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, BufReader};
+
+fn lines(count: usize) -> Vec<u8> {
+    (0..count)
+        .flat_map(|n| format!("{{\"block_number\":{n},\"chain\":1}}\n").into_bytes())
+        .collect()
+}
+
+async fn read_to_end(mut reader: impl AsyncRead + Unpin) -> Vec<u8> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).await.unwrap();
+    data
+}
+
+#[tokio::test]
+async fn round_trip() {
+    let data = lines(10_000);
+
+    for compression in [Compression::Gzip(6), Compression::Zstd(3)] {
+        let chunks = data.chunks(1000).map(<[u8]>::to_vec).collect::<Vec<_>>();
+        let stream = futures::stream::iter(chunks.into_iter().map(Ok)).boxed();
+        let path = std::env::temp_dir().join(format!(
+            "pangea-client-compressed-test.jsonl.{}",
+            compression.file_extension()
+        ));
+
+        let summary = write_compressed(Ok(stream), &path, compression)
+            .await
+            .unwrap();
+        assert_eq!(summary.raw_bytes, data.len() as u64);
+        assert!(summary.bytes < summary.raw_bytes);
+
+        let file = BufReader::new(tokio::fs::File::open(&path).await.unwrap());
+        let decoded = match compression {
+            Compression::Gzip(_) => read_to_end(GzipDecoder::new(file)).await,
+            Compression::Zstd(_) => read_to_end(ZstdDecoder::new(file)).await,
+        };
+        assert_eq!(decoded, data);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn partial_file_is_removed_on_error() {
+    let chunks = vec![Ok(lines(100)), Err(Error::UnexpectedClose)];
+    let stream = futures::stream::iter(chunks).boxed();
+    let path = std::env::temp_dir().join("pangea-client-compressed-test-error.jsonl.zst");
+
+    let err = write_compressed(Ok(stream), &path, Compression::default())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::UnexpectedClose), "{err}");
+    assert!(!path.exists());
+}
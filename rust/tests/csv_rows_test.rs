@@ -0,0 +1,80 @@
+use futures::StreamExt;
+use pangea_client::{
+    csv::{csv_rows, csv_rows_as},
+    provider::ResponseStream,
+    CsvOptions,
+};
+
+const TRANSFERS: &str = "chain,block_number,from,to,memo\r\n\
+1,19000000,0x28c6c06298d514db089934071355e5743bf21d60,0x68b3465833fb72a70ecdf485e0e4c7bd8665fc45,plain\r\n\
+1,19000001,0x28c6c06298d514db089934071355e5743bf21d60,0x68b3465833fb72a70ecdf485e0e4c7bd8665fc45,\"two\nlines, and \"\"quotes\"\"\"\r\n\
+1,19000002,0x68b3465833fb72a70ecdf485e0e4c7bd8665fc45,0x28c6c06298d514db089934071355e5743bf21d60,\r\n";
+
+fn chunked(data: &str, size: usize) -> ResponseStream<Vec<u8>> {
+    let chunks = data
+        .as_bytes()
+        .chunks(size)
+        .map(<[u8]>::to_vec)
+        .collect::<Vec<_>>();
+    futures::stream::iter(chunks.into_iter().map(Ok)).boxed()
+}
+
+#[tokio::test]
+async fn rows_split_across_chunks() {
+    // a chunk size of 3 also splits the header
+    for size in [3, 17, TRANSFERS.len()] {
+        let rows = csv_rows(chunked(TRANSFERS, size), CsvOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(
+            rows.headers().iter().collect::<Vec<_>>(),
+            ["chain", "block_number", "from", "to", "memo"]
+        );
+
+        let rows = rows.map(Result::unwrap).collect::<Vec<_>>().await;
+        assert_eq!(rows.len(), 3, "chunk size {size}");
+        assert_eq!(rows[0].get(4), Some("plain"));
+        assert_eq!(rows[1].get(4), Some("two\nlines, and \"quotes\""));
+        assert_eq!(rows[2].get(1), Some("19000002"));
+        assert_eq!(rows[2].get(4), Some(""));
+    }
+}
+
+#[tokio::test]
+async fn typed_rows() {
+    #[derive(serde::Deserialize)]
+    struct Row {
+        block_number: u64,
+        memo: String,
+    }
+
+    let rows = csv_rows_as::<Row>(chunked(TRANSFERS, 5), CsvOptions::new())
+        .await
+        .unwrap();
+    assert_eq!(rows.headers().get(1), Some("block_number"));
+
+    let rows = rows.map(Result::unwrap).collect::<Vec<_>>().await;
+    assert_eq!(
+        rows.iter().map(|row| row.block_number).collect::<Vec<_>>(),
+        [19_000_000, 19_000_001, 19_000_002]
+    );
+    assert!(rows[1].memo.starts_with("two\n"));
+}
+
+#[tokio::test]
+async fn rows_follow_the_requested_dialect() {
+    let data = "block_number\tmemo\r\n19000000\t'tab\there'\r\n";
+    let options = CsvOptions::tsv().quote(b'\'');
+
+    let rows = csv_rows(chunked(data, 4), options).await.unwrap();
+    assert_eq!(rows.headers().get(1), Some("memo"));
+    let rows = rows.map(Result::unwrap).collect::<Vec<_>>().await;
+    assert_eq!(rows[0].get(1), Some("tab\there"));
+
+    // without a header, the first row is a record
+    let rows = csv_rows(chunked(data, 4), options.header(false))
+        .await
+        .unwrap();
+    assert!(rows.headers().is_empty());
+    assert_eq!(rows.map(Result::unwrap).collect::<Vec<_>>().await.len(), 2);
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "datafusion")]
+
+use std::{collections::HashSet, sync::Arc};
+
+use arrow::datatypes::{DataType, Field, Schema};
+use datafusion::prelude::{col, lit};
+use pangea_client::{
+    datafusion::PangeaTable, query::Bound, requests::logs::GetLogsRequest, Address, Client,
+    ClientBuilder, HttpProvider,
+};
+
+async fn table() -> PangeaTable<Client<HttpProvider>> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new("address", DataType::Utf8, false),
+    ]));
+    let client = ClientBuilder::default()
+        .endpoint("localhost:8080")
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    PangeaTable::logs(Arc::new(client), schema, GetLogsRequest::default())
+}
+
+#[tokio::test]
+async fn block_range_is_pushed_down() {
+    let table = table().await;
+
+    let request = table.request_for(&[col("block_number").between(lit(100i64), lit(200i64))]);
+    assert_eq!(request.from_block, Bound::Exact(100));
+    assert_eq!(request.to_block, Bound::Exact(201));
+
+    let request = table.request_for(&[
+        col("block_number").gt(lit(100i64)),
+        lit(150i64).gt_eq(col("block_number")),
+    ]);
+    assert_eq!(request.from_block, Bound::Exact(101));
+    assert_eq!(request.to_block, Bound::Exact(151));
+}
+
+#[tokio::test]
+async fn addresses_are_pushed_down() {
+    let table = table().await;
+    let a = Address::repeat_byte(1);
+    let b = Address::repeat_byte(2);
+
+    let request = table.request_for(&[
+        col("address").in_list(vec![lit(format!("{a:?}")), lit(format!("{b:?}"))], false),
+        col("address").eq(lit(format!("{b:?}"))),
+    ]);
+    assert_eq!(request.address__in, HashSet::from([b]));
+
+    // unsupported filters leave the request untouched
+    let request = table.request_for(&[col("address").not_eq(lit(format!("{a:?}")))]);
+    assert!(request.address__in.is_empty());
+}
@@ -0,0 +1,221 @@
+mod common;
+
+use std::{sync::Mutex, time::Duration};
+
+use async_trait::async_trait;
+use common::serve_all;
+use pangea_client::{
+    query::{Bound, SortOrder},
+    requests::{blocks::GetBlocksRequest, logs::GetLogsRequest},
+    Checkpoint, ClientBuilder, Error, FileCheckpoint, Format, HttpProvider, Result, RetryPolicy,
+};
+
+/// A response of `records`, cut off after them if `length` is longer
+fn response(records: &str, length: usize) -> String {
+    format!("HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: {length}\r\nconnection: close\r\n\r\n{records}")
+}
+
+#[derive(Default)]
+struct MemoryCheckpoint(Mutex<Option<i64>>);
+
+#[async_trait]
+impl Checkpoint for MemoryCheckpoint {
+    async fn load(&self) -> Result<Option<i64>> {
+        Ok(*self.0.lock().unwrap())
+    }
+
+    async fn save(&self, block: i64) -> Result<()> {
+        *self.0.lock().unwrap() = Some(block);
+        Ok(())
+    }
+}
+
+fn retry() -> RetryPolicy {
+    RetryPolicy {
+        initial_delay: Duration::from_millis(10),
+        ..Default::default()
+    }
+}
+
+const BLOCKS: &str =
+    "{\"block_number\":1}\n{\"block_number\":2}\n{\"block_number\":2}\n{\"block_number\":3}\n";
+
+#[tokio::test]
+async fn failed_downloads_resume_after_the_last_complete_block() {
+    // block 3 is not known to be complete when the response is cut off
+    let (endpoint, server) = serve_all(vec![
+        response(BLOCKS, 100),
+        response("{\"block_number\":3}\n{\"block_number\":4}\n", 38),
+    ])
+    .await;
+    let client = ClientBuilder::default()
+        .endpoint(&endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    let request = GetBlocksRequest {
+        from_block: Bound::Exact(1),
+        ..Default::default()
+    };
+    let checkpoint = MemoryCheckpoint::default();
+    let mut sink = Vec::new();
+
+    let summary = client
+        .download_resumable_with_retry(request, Format::JsonStream, &mut sink, &checkpoint, retry())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(sink).unwrap(),
+        format!("{BLOCKS}{{\"block_number\":4}}\n")
+    );
+    assert_eq!(summary.last_block, Some(4));
+    assert_eq!(summary.resumes, 1);
+    assert_eq!(checkpoint.load().await.unwrap(), Some(4));
+    let requests = server.await.unwrap();
+    assert!(requests[0].contains("from_block=1"), "{requests:?}");
+    assert!(requests[1].contains("from_block=3"), "{requests:?}");
+}
+
+#[tokio::test]
+async fn downloads_continue_after_a_saved_checkpoint() {
+    let (endpoint, server) = serve_all(vec![response("{\"block_number\":6}\n", 19)]).await;
+    let client = ClientBuilder::default()
+        .endpoint(&endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    let path = std::env::temp_dir().join(format!("checkpoint-{}", std::process::id()));
+    let checkpoint = FileCheckpoint::new(&path);
+    checkpoint.save(5).await.unwrap();
+    let mut sink = Vec::new();
+
+    let summary = client
+        .download_resumable(
+            GetBlocksRequest::default(),
+            Format::JsonStream,
+            &mut sink,
+            &checkpoint,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(sink, b"{\"block_number\":6}\n");
+    assert_eq!(summary.last_block, Some(6));
+    assert_eq!(checkpoint.load().await.unwrap(), Some(6));
+    let requests = server.await.unwrap();
+    assert!(requests[0].contains("from_block=6"), "{requests:?}");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn only_json_lines_downloads_can_be_resumed() {
+    let client = ClientBuilder::default()
+        .endpoint("127.0.0.1:9")
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    let mut sink = Vec::new();
+
+    let err = client
+        .download_resumable(
+            GetBlocksRequest::default(),
+            Format::Arrow,
+            &mut sink,
+            &MemoryCheckpoint::default(),
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::UnsupportedFormat { .. }), "{err:?}");
+}
+
+#[tokio::test]
+async fn limited_downloads_resume_with_the_rest_of_the_limit() {
+    let (endpoint, server) = serve_all(vec![
+        response(BLOCKS, 100),
+        response("{\"block_number\":3}\n", 19),
+    ])
+    .await;
+    let client = ClientBuilder::default()
+        .endpoint(&endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    let request = GetLogsRequest {
+        from_block: Bound::Exact(1),
+        limit: Some(10),
+        ..Default::default()
+    };
+    let mut sink = Vec::new();
+
+    let summary = client
+        .download_resumable_with_retry(
+            request,
+            Format::JsonStream,
+            &mut sink,
+            &MemoryCheckpoint::default(),
+            retry(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(summary.records, 4);
+    let requests = server.await.unwrap();
+    assert!(requests[0].contains("limit=10"), "{requests:?}");
+    // the three records of blocks 1 and 2 were written before the resume
+    assert!(requests[1].contains("limit=7"), "{requests:?}");
+}
+
+#[tokio::test]
+async fn limited_downloads_do_not_continue_after_a_saved_checkpoint() {
+    let client = ClientBuilder::default()
+        .endpoint("127.0.0.1:9")
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    let checkpoint = MemoryCheckpoint::default();
+    checkpoint.save(5).await.unwrap();
+    let request = GetLogsRequest {
+        limit: Some(10),
+        ..Default::default()
+    };
+
+    let err = client
+        .download_resumable(request, Format::JsonStream, &mut Vec::new(), &checkpoint)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InvalidRequest(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn descending_downloads_cannot_be_resumed() {
+    let client = ClientBuilder::default()
+        .endpoint("127.0.0.1:9")
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    let request = GetLogsRequest {
+        order: Some(SortOrder::Desc),
+        ..Default::default()
+    };
+
+    let err = client
+        .download_resumable(
+            request,
+            Format::JsonStream,
+            &mut Vec::new(),
+            &MemoryCheckpoint::default(),
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InvalidRequest(_)), "{err:?}");
+}
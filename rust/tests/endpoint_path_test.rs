@@ -0,0 +1,76 @@
+use futures::TryStreamExt;
+use pangea_client::{
+    provider::ChainProvider,
+    requests::blocks::GetBlocksRequest,
+    tokio_tungstenite::accept_hdr_async,
+    tungstenite::{handshake::server, http},
+    ClientBuilder, Format, HttpProvider, WsProvider,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+#[tokio::test]
+async fn http_requests_keep_the_path_of_the_endpoint() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("{}/pangea/", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(socket.read_u8().await.unwrap());
+        }
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: 3\r\nconnection: close\r\n\r\n{}\n")
+            .await
+            .unwrap();
+        String::from_utf8(head).unwrap()
+    });
+
+    let client = ClientBuilder::default()
+        .endpoint(&endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    client
+        .inner
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .try_concat()
+        .await
+        .unwrap();
+
+    let head = server.await.unwrap();
+    assert!(head.starts_with("GET /pangea/v1/api/blocks?"), "{head}");
+}
+
+#[tokio::test]
+async fn ws_connections_keep_the_path_of_the_endpoint() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("{}/pangea", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut path = String::new();
+        // the error type is the handshake callback's, set by tungstenite
+        #[allow(clippy::result_large_err)]
+        let callback = |request: &server::Request, response: server::Response| {
+            path = request.uri().path().to_string();
+            Ok::<_, http::Response<Option<String>>>(response)
+        };
+        let ws = accept_hdr_async(socket, callback).await.unwrap();
+        (path, ws)
+    });
+
+    let _client = ClientBuilder::default()
+        .endpoint(&endpoint)
+        .secure(false)
+        .build::<WsProvider>()
+        .await
+        .unwrap();
+
+    let (path, _ws) = server.await.unwrap();
+    assert_eq!(path, "/pangea/v1/websocket");
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{listen, serve_all, unreachable};
+use futures::TryStreamExt;
+use pangea_client::{
+    provider::ChainProvider, requests::blocks::GetBlocksRequest, Failback, Format, HttpConfig,
+    HttpProvider, WsConfig, WsProvider,
+};
+
+const OK: &str = "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: 3\r\nconnection: close\r\n\r\n{}\n";
+const UNAVAILABLE: &str =
+    "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+
+async fn get_blocks(provider: &HttpProvider) -> Vec<u8> {
+    provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .try_concat()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn unreachable_endpoints_fail_over_to_the_next() {
+    let primary = unreachable().await;
+    let (fallback, server) = serve_all(vec![OK.to_string(); 2]).await;
+    let config = HttpConfig {
+        fallback_endpoints: vec![fallback.clone()],
+        failback: Failback::Sticky,
+        ..Default::default()
+    };
+    let provider = HttpProvider::try_new_with_config(primary, false, None, None, config)
+        .await
+        .unwrap();
+
+    assert_eq!(get_blocks(&provider).await, b"{}\n");
+    assert_eq!(provider.endpoint(), fallback);
+    // the provider sticks to the fallback
+    assert_eq!(get_blocks(&provider).await, b"{}\n");
+    assert_eq!(server.await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn requests_fail_back_to_the_primary_endpoint() {
+    let (primary, primary_server) = serve_all(vec![UNAVAILABLE.to_string(), OK.to_string()]).await;
+    let (fallback, fallback_server) = serve_all(vec![OK.to_string()]).await;
+    let config = HttpConfig {
+        fallback_endpoints: vec![fallback.clone()],
+        failback: Failback::After(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    let provider = HttpProvider::try_new_with_config(primary.clone(), false, None, None, config)
+        .await
+        .unwrap();
+
+    assert_eq!(get_blocks(&provider).await, b"{}\n");
+    assert_eq!(provider.endpoint(), fallback);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(get_blocks(&provider).await, b"{}\n");
+    assert_eq!(provider.endpoint(), primary);
+
+    assert_eq!(primary_server.await.unwrap().len(), 2);
+    assert_eq!(fallback_server.await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn ws_connections_fail_over_to_the_next_endpoint() {
+    let primary = unreachable().await;
+    let (listener, fallback) = listen().await;
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        tokio_tungstenite::accept_async(socket).await.unwrap()
+    });
+
+    let config = WsConfig {
+        fallback_endpoints: vec![fallback.clone()],
+        ..Default::default()
+    };
+    let provider = WsProvider::try_new_with_config(primary, false, None, None, config)
+        .await
+        .unwrap();
+
+    let _ws = server.await.unwrap();
+    assert_eq!(provider.endpoint(), fallback);
+}
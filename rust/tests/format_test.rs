@@ -0,0 +1,148 @@
+use pangea_client::{CsvOptions, Format};
+
+const FORMATS: [Format; 8] = [
+    Format::Json,
+    Format::JsonStream,
+    Format::Arrow,
+    Format::ArrowStream,
+    Format::Csv(CsvOptions::new()),
+    Format::Parquet,
+    Format::Avro,
+    Format::Proto,
+];
+
+#[test]
+fn query_string() {
+    let client = pangea_client::reqwest::Client::new();
+    let queries = FORMATS.map(|format| {
+        let request = client
+            .get("http://localhost/v1/api/blocks")
+            .query(&[("format", format)])
+            .build()
+            .unwrap();
+        request.url().query().unwrap().to_string()
+    });
+
+    assert_eq!(
+        queries,
+        [
+            "format=json",
+            "format=json_stream",
+            "format=arrow",
+            "format=arrow_stream",
+            "format=csv",
+            "format=parquet",
+            "format=avro",
+            "format=proto",
+        ]
+    );
+}
+
+#[test]
+fn csv_options_query_string() {
+    let client = pangea_client::reqwest::Client::new();
+    let query = |format: Format| {
+        let request = client
+            .get("http://localhost/v1/api/blocks")
+            .query(&[("format", format)])
+            .query(&format.options())
+            .build()
+            .unwrap();
+        request.url().query().unwrap().to_string()
+    };
+
+    assert_eq!(query(Format::Csv(CsvOptions::default())), "format=csv");
+    assert_eq!(
+        query(Format::Csv(CsvOptions::tsv().header(false))),
+        "format=csv&delimiter=%09&header=false"
+    );
+    assert_eq!(
+        query(Format::Csv(CsvOptions::new().delimiter(b';').quote(b'\''))),
+        "format=csv&delimiter=%3B&quote=%27"
+    );
+}
+
+#[test]
+fn format_round_trip() {
+    for format in FORMATS {
+        let json = serde_json::to_string(&format).unwrap();
+        assert_eq!(json, format!("\"{}\"", format.as_str()));
+        assert_eq!(serde_json::from_str::<Format>(&json).unwrap(), format);
+    }
+}
+
+#[test]
+fn content_type_and_extension() {
+    assert_eq!(
+        Format::Parquet.content_type(),
+        "application/vnd.apache.parquet"
+    );
+    assert_eq!(Format::Parquet.file_extension(), "parquet");
+    assert_eq!(Format::JsonStream.file_extension(), "jsonl");
+    assert_eq!(Format::Csv(CsvOptions::tsv()).file_extension(), "tsv");
+
+    let extensions = FORMATS
+        .map(|format| format.file_extension())
+        .into_iter()
+        .collect::<std::collections::HashSet<_>>();
+    assert_eq!(extensions.len(), FORMATS.len());
+}
+
+#[cfg(feature = "parquet")]
+#[tokio::test]
+async fn read_parquet_response() {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::Int64Array,
+        datatypes::{DataType, Field, Schema},
+        record_batch::RecordBatch,
+    };
+    use futures::StreamExt;
+    use parquet::arrow::ArrowWriter;
+
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "block_number",
+        DataType::Int64,
+        false,
+    )]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(Int64Array::from_iter_values(0..100))],
+    )
+    .unwrap();
+
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema, None).unwrap();
+    writer.write(&batch).unwrap();
+    let data = writer.into_inner().unwrap();
+
+    let chunks = data.chunks(10).map(<[u8]>::to_vec).collect::<Vec<_>>();
+    let stream = futures::stream::iter(chunks.into_iter().map(Ok)).boxed();
+    let batches = pangea_client::parquet::read_parquet(Ok(stream))
+        .await
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(batches, vec![batch]);
+}
+
+#[test]
+fn columns_query_string() {
+    let request = pangea_client::requests::logs::GetLogsRequest {
+        columns: vec!["block_number".to_string(), "address".to_string()],
+        ..Default::default()
+    };
+
+    let url = pangea_client::reqwest::Client::new()
+        .get("http://localhost/v1/api/logs")
+        .query(&request)
+        .build()
+        .unwrap()
+        .url()
+        .clone();
+
+    assert!(url
+        .query_pairs()
+        .any(|(key, value)| key == "columns" && value == "block_number,address"));
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        ArrayRef, BooleanArray, FixedSizeBinaryArray, FixedSizeBinaryBuilder, ListBuilder,
+        StringArray, UInt64Array,
+    },
+    datatypes::{Field, Schema},
+    record_batch::RecordBatch,
+};
+use ethers_core::types::{Address, H256, U256, U64};
+use futures::StreamExt;
+use pangea_client::{
+    arrow::{batches_to_records, FromRecordBatch},
+    core::types::{logs::Log, transfers::Transfer, txs::Transaction},
+    ChainId, Error,
+};
+
+fn fixed(size: i32, values: Vec<Option<Vec<u8>>>) -> ArrayRef {
+    Arc::new(
+        FixedSizeBinaryArray::try_from_sparse_iter_with_size(values.into_iter(), size).unwrap(),
+    )
+}
+
+fn batch(columns: Vec<(&str, ArrayRef)>) -> RecordBatch {
+    let fields = columns
+        .iter()
+        .map(|(name, array)| Field::new(*name, array.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let arrays = columns.into_iter().map(|(_, array)| array).collect();
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays).unwrap()
+}
+
+fn logs_batch() -> RecordBatch {
+    let mut topics = ListBuilder::new(FixedSizeBinaryBuilder::new(32));
+    topics.values().append_value([1; 32]).unwrap();
+    topics.values().append_value([2; 32]).unwrap();
+    topics.append(true);
+    topics.append(true);
+
+    batch(vec![
+        ("chain", Arc::new(UInt64Array::from(vec![1, 1]))),
+        (
+            "block_number",
+            Arc::new(UInt64Array::from(vec![17_000_000, 17_000_001])),
+        ),
+        (
+            "transaction_hash",
+            fixed(32, vec![Some(vec![0xaa; 32]), Some(vec![0xbb; 32])]),
+        ),
+        ("log_index", Arc::new(UInt64Array::from(vec![0, 3]))),
+        (
+            "address",
+            fixed(20, vec![Some(vec![0x11; 20]), Some(vec![0x22; 20])]),
+        ),
+        ("topics", Arc::new(topics.finish())),
+        ("removed", Arc::new(BooleanArray::from(vec![false, true]))),
+    ])
+}
+
+#[test]
+fn logs_from_record_batch() {
+    let logs = Log::from_record_batch(&logs_batch()).unwrap();
+
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].chain, ChainId::ETH);
+    assert_eq!(logs[0].block_number, U64::from(17_000_000));
+    assert_eq!(logs[0].tx_hash, H256::repeat_byte(0xaa));
+    assert_eq!(logs[1].log_index, U64::from(3));
+    assert_eq!(logs[1].address, Address::repeat_byte(0x22));
+    assert_eq!(
+        logs[0].topics,
+        vec![H256::repeat_byte(1), H256::repeat_byte(2)]
+    );
+    assert!(logs[1].topics.is_empty());
+    assert!(logs[0].data.is_empty());
+    assert!(logs[1].removed);
+}
+
+#[test]
+fn nulls_map_to_none() {
+    let batch = batch(vec![
+        ("chain", Arc::new(UInt64Array::from(vec![1, 1]))),
+        ("block_number", Arc::new(UInt64Array::from(vec![1, 2]))),
+        (
+            "tx_hash",
+            fixed(32, vec![Some(vec![1; 32]), Some(vec![2; 32])]),
+        ),
+        ("nonce", Arc::new(UInt64Array::from(vec![0, 1]))),
+        (
+            "from",
+            fixed(20, vec![Some(vec![1; 20]), Some(vec![2; 20])]),
+        ),
+        ("to", fixed(20, vec![None, Some(vec![3; 20])])),
+        (
+            "value",
+            Arc::new(StringArray::from(vec!["0x33b2e3c9fd0803ce8000000", "0x0"])),
+        ),
+        ("gas", Arc::new(UInt64Array::from(vec![21_000, 53_000]))),
+        (
+            "gas_price",
+            Arc::new(UInt64Array::from(vec![Some(7), None])),
+        ),
+        (
+            "max_fee_per_gas",
+            Arc::new(UInt64Array::from(vec![None, Some(9)])),
+        ),
+    ]);
+
+    let txs = Transaction::from_record_batch(&batch).unwrap();
+
+    assert_eq!(txs[0].to, None);
+    assert_eq!(txs[1].to, Some(Address::repeat_byte(3)));
+    assert_eq!(
+        txs[0].value,
+        U256::from_dec_str("1000000000000000000000000000").unwrap()
+    );
+    assert_eq!(txs[0].gas_price, Some(U256::from(7)));
+    assert_eq!(txs[1].gas_price, None);
+    assert!(!txs[0].is_eip1559());
+    assert!(txs[1].is_eip1559());
+}
+
+#[test]
+fn missing_column_is_named() {
+    let batch = batch(vec![
+        ("chain", Arc::new(UInt64Array::from(vec![1]))),
+        ("block_number", Arc::new(UInt64Array::from(vec![1]))),
+        ("from", fixed(20, vec![Some(vec![1; 20])])),
+        ("to", fixed(20, vec![Some(vec![2; 20])])),
+        ("value", Arc::new(StringArray::from(vec!["1"]))),
+    ]);
+
+    let err = Transfer::from_record_batch(&batch).unwrap_err();
+
+    assert!(matches!(err, Error::RecordBatchRow { row: 0, .. }), "{err}");
+    assert!(
+        err.to_string().contains("missing column `tx_hash`"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn batches_to_records_flattens_batches() {
+    let batches = futures::stream::iter(vec![Ok(logs_batch()), Ok(logs_batch())]);
+
+    let logs = batches_to_records::<Log, _>(batches)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(logs.len(), 4);
+    assert_eq!(logs[2], logs[0]);
+}
@@ -0,0 +1,114 @@
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futures::{future, StreamExt, TryStreamExt};
+use hyper::{server::conn::Http, service::service_fn, Body, Response};
+use pangea_client::{
+    provider::ChainProvider, requests::blocks::GetBlocksRequest, Format, HttpConfig, HttpProvider,
+};
+use tokio::{net::TcpListener, sync::Notify};
+
+/// Serves HTTP/2 with `respond`, and returns the number of connections
+async fn serve_h2<F>(respond: F) -> (String, Arc<AtomicUsize>)
+where
+    F: Fn() -> Body + Clone + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    let counter = connections.clone();
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = listener.accept().await.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+            let respond = respond.clone();
+            let service = service_fn(move |_| {
+                let body = respond();
+                async move {
+                    let response = Response::builder()
+                        .header("content-type", "application/x-ndjson")
+                        .body(body)
+                        .unwrap();
+                    Ok::<_, Infallible>(response)
+                }
+            });
+            tokio::spawn(
+                Http::new()
+                    .http2_only(true)
+                    .serve_connection(socket, service),
+            );
+        }
+    });
+
+    (endpoint, connections)
+}
+
+async fn provider(endpoint: &str) -> HttpProvider {
+    let config = HttpConfig {
+        http2_prior_knowledge: true,
+        ..Default::default()
+    };
+    HttpProvider::try_new_with_config(endpoint.to_string(), false, None, None, config)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn concurrent_requests_share_one_connection() {
+    let (endpoint, connections) = serve_h2(|| Body::from("{}\n")).await;
+    let provider = provider(&endpoint).await;
+
+    let responses = future::try_join_all((0..50).map(|_| async {
+        provider
+            .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+            .await?
+            .try_concat()
+            .await
+    }))
+    .await
+    .unwrap();
+
+    assert!(responses.iter().all(|body| body == b"{}\n"));
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn responses_are_streamed_as_they_arrive() {
+    // the rest of the response is only sent once the first chunk was received
+    let received = Arc::new(Notify::new());
+    let notified = received.clone();
+    let (endpoint, _) = serve_h2(move || {
+        let (mut sender, body) = Body::channel();
+        let received = notified.clone();
+        tokio::spawn(async move {
+            sender.send_data("{\"a\":1}\n".into()).await.unwrap();
+            received.notified().await;
+            sender.send_data("{\"b\":2}\n".into()).await.unwrap();
+        });
+        body
+    })
+    .await;
+    let provider = provider(&endpoint).await;
+
+    let mut chunks = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap();
+    let first = tokio::time::timeout(Duration::from_secs(5), chunks.next())
+        .await
+        .expect("the first chunk was held back")
+        .unwrap()
+        .unwrap();
+    received.notify_one();
+    let rest = chunks.try_concat().await.unwrap();
+
+    assert_eq!(first, b"{\"a\":1}\n");
+    assert_eq!(rest, b"{\"b\":2}\n");
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "compression")]
+
+mod common;
+
+use async_compression::tokio::write::{GzipEncoder, ZstdEncoder};
+use common::serve_once;
+use futures::StreamExt;
+use pangea_client::{
+    provider::ChainProvider, requests::blocks::GetBlocksRequest, ClientBuilder, Error, Format,
+    HttpProvider,
+};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+fn lines(count: usize) -> Vec<u8> {
+    (0..count)
+        .flat_map(|n| format!("{{\"block_number\":{n},\"chain\":1}}\n").into_bytes())
+        .collect()
+}
+
+async fn compress(mut encoder: impl AsyncWrite + Unpin, data: &[u8]) {
+    encoder.write_all(data).await.unwrap();
+    encoder.shutdown().await.unwrap();
+}
+
+/// A response of `body` in `encoding`
+fn encoded(encoding: &str, body: Vec<u8>) -> Vec<u8> {
+    let head = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-encoding: {encoding}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        body.len()
+    );
+    [head.into_bytes(), body].concat()
+}
+
+async fn get_blocks(endpoint: &str) -> Vec<Result<Vec<u8>, Error>> {
+    ClientBuilder::default()
+        .endpoint(endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap()
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn gzip_responses_are_decompressed() {
+    let data = lines(10_000);
+    let mut body = Vec::new();
+    compress(GzipEncoder::new(&mut body), &data).await;
+    let (endpoint, server) = serve_once(encoded("gzip", body)).await;
+
+    let chunks = get_blocks(&endpoint).await;
+
+    let decompressed = chunks
+        .into_iter()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .concat();
+    assert_eq!(decompressed, data);
+    let head = server.await.unwrap().to_lowercase();
+    assert!(head.contains("accept-encoding: gzip, zstd\r\n"), "{head}");
+}
+
+#[tokio::test]
+async fn zstd_responses_are_decompressed() {
+    let data = lines(10_000);
+    let mut body = Vec::new();
+    compress(ZstdEncoder::new(&mut body), &data).await;
+    let (endpoint, server) = serve_once(encoded("zstd", body)).await;
+
+    let chunks = get_blocks(&endpoint).await;
+
+    let decompressed = chunks
+        .into_iter()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .concat();
+    assert_eq!(decompressed, data);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn corrupted_responses_fail() {
+    let mut body = Vec::new();
+    compress(GzipEncoder::new(&mut body), &lines(100)).await;
+    body[20..40].fill(0xff);
+    let (endpoint, server) = serve_once(encoded("gzip", body)).await;
+
+    let chunks = get_blocks(&endpoint).await;
+
+    let err = chunks.into_iter().find_map(Result::err).unwrap();
+    match err {
+        Error::StreamInterrupted { source, .. } => assert!(
+            matches!(&*source, Error::Decompression { encoding, .. } if encoding == "gzip"),
+            "{source:?}"
+        ),
+        other => panic!("expected an interrupted stream, got {other:?}"),
+    }
+    server.await.unwrap();
+}
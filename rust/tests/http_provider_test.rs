@@ -0,0 +1,831 @@
+mod common;
+
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use common::{listen, ok, read_body, read_head, serve_all, serve_once};
+use futures::{StreamExt, TryStreamExt};
+use pangea_client::{
+    provider::ChainProvider,
+    requests::{blocks::GetBlocksRequest, logs::GetLogsRequest},
+    Address, Client, ClientBuilder, Cursor, Error, Format, HttpConfig, HttpProvider, PageOptions,
+    RedirectPolicy, RequestMethod, RetryAfterPolicy, RetryPolicy,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    task::JoinHandle,
+};
+
+async fn get_blocks(endpoint: &str, format: Format) -> Result<Vec<u8>, Error> {
+    let client = ClientBuilder::default()
+        .endpoint(endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await?;
+    let stream = client
+        .get_blocks_by_format(GetBlocksRequest::default(), format, false)
+        .await?;
+    stream.try_concat().await
+}
+
+#[tokio::test]
+async fn accept_header_is_sent() {
+    let (endpoint, server) = serve_once(ok("application/x-ndjson; charset=utf-8", "{}\n")).await;
+
+    let body = get_blocks(&endpoint, Format::JsonStream).await.unwrap();
+
+    assert_eq!(body, b"{}\n");
+    let head = server.await.unwrap().to_lowercase();
+    assert!(head.contains("accept: application/x-ndjson\r\n"), "{head}");
+}
+
+#[tokio::test]
+async fn unexpected_content_type() {
+    let (endpoint, server) = serve_once(ok("application/x-ndjson", "{}\n")).await;
+
+    let err = get_blocks(&endpoint, Format::Arrow).await.unwrap_err();
+
+    match err {
+        Error::UnexpectedContentType { expected, got } => {
+            assert_eq!(expected, "application/vnd.apache.arrow.file");
+            assert_eq!(got, "application/x-ndjson");
+        }
+        other => panic!("expected an unexpected content type, got {other:?}"),
+    }
+    server.await.unwrap();
+}
+
+async fn client(endpoint: &str) -> Client<HttpProvider> {
+    ClientBuilder::default()
+        .endpoint(endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn raw_get_requests_the_path_under_the_api() {
+    let (endpoint, server) = serve_once(ok("application/x-ndjson", "{}\n")).await;
+
+    let body = client(&endpoint)
+        .await
+        .raw_get("blocks/latest", [("chains", "ETH")], Format::JsonStream)
+        .await
+        .unwrap()
+        .try_concat()
+        .await
+        .unwrap();
+
+    assert_eq!(body, b"{}\n");
+    let head = server.await.unwrap();
+    assert!(
+        head.starts_with("GET /v1/api/blocks/latest?chains=ETH&format=json_stream"),
+        "{head}"
+    );
+}
+
+#[tokio::test]
+async fn raw_get_rejects_paths_outside_the_api() {
+    let client = client("127.0.0.1:1").await;
+
+    for path in [
+        "../status",
+        "blocks/../../status",
+        "%2E%2e/status",
+        "/status",
+        "//example.com/status",
+        "http://example.com/v1/api/status",
+    ] {
+        let err = client.raw_get(path, (), Format::JsonStream).await.err();
+        assert!(
+            matches!(err, Some(Error::InvalidPath(_))),
+            "{path}: {err:?}"
+        );
+    }
+}
+
+const OK: &str = "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: 3\r\nconnection: close\r\n\r\n{}\n";
+
+fn retry() -> Option<RetryPolicy> {
+    Some(RetryPolicy {
+        initial_delay: Duration::from_millis(10),
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn failed_requests_are_retried() {
+    let unavailable =
+        "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+    let (endpoint, server) = serve_all(vec![
+        None,
+        Some(unavailable.to_string()),
+        Some(OK.to_string()),
+    ])
+    .await;
+
+    let client = client(&endpoint).await;
+    let body = client
+        .inner
+        .with_retry(retry())
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .try_concat()
+        .await
+        .unwrap();
+
+    assert_eq!(body, b"{}\n");
+    assert_eq!(server.await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn requests_are_not_retried_by_default() {
+    let (endpoint, server) = serve_all(vec![None::<String>]).await;
+
+    let err = get_blocks(&endpoint, Format::JsonStream).await.unwrap_err();
+
+    assert!(matches!(err, Error::Reqwest(_)), "{err:?}");
+    assert_eq!(server.await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn interrupted_responses_report_the_delivered_bytes() {
+    let cut_off = "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: 100\r\nconnection: close\r\n\r\n{}\n";
+    let (endpoint, server) = serve_all(vec![Some(cut_off.to_string())]).await;
+
+    let client = client(&endpoint).await;
+    let results = client
+        .inner
+        .with_retry(retry())
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap(), b"{}\n");
+    assert!(matches!(
+        results[1],
+        Err(Error::StreamInterrupted { bytes: 3, .. })
+    ));
+    // not retried
+    assert_eq!(server.await.unwrap().len(), 1);
+}
+
+fn failure(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[tokio::test]
+async fn failed_statuses_are_typed_errors() {
+    let (endpoint, server) = serve_all(vec![
+        Some(failure("401 Unauthorized", "<html>denied</html>")),
+        Some(failure("404 Not Found", "")),
+        Some(failure(
+            "400 Bad Request",
+            r#"{"status":400,"error":"unknown chain"}"#,
+        )),
+        Some(failure("500 Internal Server Error", "<html>oops</html>")),
+    ])
+    .await;
+
+    let mut errors = Vec::new();
+    for _ in 0..4 {
+        errors.push(get_blocks(&endpoint, Format::Arrow).await.unwrap_err());
+    }
+
+    assert!(matches!(errors[0], Error::Unauthorized), "{:?}", errors[0]);
+    assert!(matches!(errors[1], Error::NotFound), "{:?}", errors[1]);
+    assert!(
+        matches!(&errors[2], Error::ErrorResponse(err) if err.status == 400 && err.error == "unknown chain"),
+        "{:?}",
+        errors[2]
+    );
+    assert!(
+        matches!(&errors[3], Error::Http { status: 500, body } if body == "<html>oops</html>"),
+        "{:?}",
+        errors[3]
+    );
+    server.await.unwrap();
+}
+
+/// Answers a single request with the start of a response, and keeps the
+/// connection open until the client closes it
+async fn serve_and_stall(response: &'static str) -> (String, JoinHandle<()>) {
+    let (listener, endpoint) = listen().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        read_head(&mut socket).await;
+        socket.write_all(response.as_bytes()).await.unwrap();
+        while socket.read_u8().await.is_ok() {}
+    });
+
+    (endpoint, server)
+}
+
+async fn client_with_config(endpoint: &str, config: HttpConfig) -> HttpProvider {
+    HttpProvider::try_new_with_config(endpoint.to_string(), false, None, None, config)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn responses_that_do_not_start_time_out() {
+    let (endpoint, server) = serve_and_stall("").await;
+    let config = HttpConfig {
+        first_byte_timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    };
+
+    let err = client_with_config(&endpoint, config)
+        .await
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(err, Error::Timeout(timeout) if timeout == Duration::from_millis(50)));
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn stalled_responses_fail() {
+    let stalled =
+        "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: 100\r\n\r\n{}\n";
+    let (endpoint, server) = serve_and_stall(stalled).await;
+    let config = HttpConfig {
+        idle_timeout: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+
+    // the timeout of the provider is overridden for the request
+    let results = client_with_config(&endpoint, config)
+        .await
+        .with_timeouts(None, Some(Duration::from_millis(50)))
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap(), b"{}\n");
+    match &results[1] {
+        Err(Error::StreamInterrupted { bytes: 3, source }) => {
+            assert!(matches!(**source, Error::Stalled(_)), "{source:?}")
+        }
+        other => panic!("expected a stalled stream, got {other:?}"),
+    }
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn default_headers_are_sent() {
+    let (endpoint, server) = serve_once(ok("application/x-ndjson", "{}\n")).await;
+    let config = HttpConfig {
+        headers: vec![("X-Org-Id".into(), "acme".into())],
+        user_agent: Some("indexer/1.2.3".into()),
+        ..Default::default()
+    };
+
+    client_with_config(&endpoint, config)
+        .await
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .try_concat()
+        .await
+        .unwrap();
+
+    let head = server.await.unwrap().to_lowercase();
+    assert!(head.contains("x-org-id: acme\r\n"), "{head}");
+    assert!(head.contains("user-agent: indexer/1.2.3\r\n"), "{head}");
+}
+
+#[tokio::test]
+async fn headers_cannot_replace_the_credentials() {
+    let config = HttpConfig {
+        headers: vec![("Authorization".into(), "Bearer token".into())],
+        ..Default::default()
+    };
+
+    let result = HttpProvider::try_new_with_config(
+        "127.0.0.1:9".into(),
+        false,
+        Some("user".into()),
+        Some("secret".into()),
+        config,
+    )
+    .await;
+
+    let Err(err) = result else {
+        panic!("expected the header to be refused");
+    };
+    assert!(matches!(err, Error::InvalidHeader { .. }), "{err:?}");
+}
+
+/// Answers every request with a small keep-alive response, and returns how
+/// many connections were opened
+async fn serve_keep_alive() -> (String, Arc<AtomicUsize>) {
+    let (listener, endpoint) = listen().await;
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    let opened = connections.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            opened.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(async move {
+                loop {
+                    let mut head = Vec::new();
+                    while !head.ends_with(b"\r\n\r\n") {
+                        match socket.read_u8().await {
+                            Ok(byte) => head.push(byte),
+                            Err(_) => return,
+                        }
+                    }
+                    let response = "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: 3\r\n\r\n{}\n";
+                    if socket.write_all(response.as_bytes()).await.is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+
+    (endpoint, connections)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_requests_reuse_connections() {
+    const CONCURRENCY: usize = 64;
+    const ROUNDS: usize = 4;
+
+    let (endpoint, connections) = serve_keep_alive().await;
+    let config = HttpConfig {
+        pool_max_idle_per_host: CONCURRENCY,
+        pool_idle_timeout: Some(Duration::from_secs(30)),
+        ..Default::default()
+    };
+    let provider = client_with_config(&endpoint, config).await;
+
+    for _ in 0..ROUNDS {
+        let requests = (0..CONCURRENCY).map(|_| {
+            let provider = provider.clone();
+            tokio::spawn(async move {
+                provider
+                    .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+                    .await?
+                    .try_concat()
+                    .await
+            })
+        });
+        for body in futures::future::join_all(requests).await {
+            assert_eq!(body.unwrap().unwrap(), b"{}\n");
+        }
+        // connections return to the pool in the background once a response ended
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let opened = connections.load(Ordering::SeqCst);
+    assert!(opened <= CONCURRENCY, "opened {opened} connections");
+}
+
+fn log_line(block: u64) -> String {
+    format!(
+        "{{\"chain\":1,\"block_number\":\"{block:#x}\",\"tx_hash\":\"0x{:064x}\",\"log_index\":\"0x0\",\"address\":\"0x{:040x}\"}}\n",
+        block, 1
+    )
+}
+
+#[tokio::test]
+async fn pages_return_the_cursor_of_the_header() {
+    let body = format!("{}{}", log_line(1), log_line(2));
+    let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\nx-next-cursor: abc\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let (endpoint, server) = serve_once(response).await;
+    let client = client(&endpoint).await;
+
+    let page = PageOptions::after(Some(Cursor::new("xyz")), 2);
+    let (logs, next) = client
+        .get_logs_page(GetLogsRequest::default(), page)
+        .await
+        .unwrap();
+
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[1].block_number.as_u64(), 2);
+    assert_eq!(next, Some(Cursor::new("abc")));
+    let head = server.await.unwrap();
+    let query = head.lines().next().unwrap();
+    assert!(query.contains("limit=2&cursor=xyz"), "{query}");
+}
+
+#[tokio::test]
+async fn pages_return_the_cursor_of_the_trailer() {
+    let body = format!("{}{{\"next_cursor\":\"abc\"}}\n", log_line(1));
+    let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let (endpoint, _server) = serve_once(response).await;
+
+    let (logs, next) = client(&endpoint)
+        .await
+        .get_logs_page(GetLogsRequest::default(), PageOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(logs.len(), 1);
+    assert_eq!(next, Some(Cursor::new("abc")));
+}
+
+#[tokio::test]
+async fn pages_refuse_a_limit_on_both_the_request_and_the_page() {
+    // nothing listens, the request must fail before it is sent
+    let request = GetLogsRequest {
+        limit: Some(5),
+        ..Default::default()
+    };
+
+    let err = client("127.0.0.1:9")
+        .await
+        .get_logs_page(request, PageOptions::after(None, 2))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::InvalidRequest(_)), "{err:?}");
+}
+
+/// Answers a single request with an empty page and returns its head and body
+async fn serve_and_read_body() -> (String, JoinHandle<(String, String)>) {
+    let (listener, endpoint) = listen().await;
+
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let head = read_head(&mut socket).await.to_lowercase();
+        let body = read_body(&mut socket, &head).await;
+        socket.write_all(OK.as_bytes()).await.unwrap();
+        (head, String::from_utf8(body).unwrap())
+    });
+
+    (endpoint, server)
+}
+
+async fn get_logs(config: HttpConfig, request: GetLogsRequest) -> (String, String) {
+    let (endpoint, server) = serve_and_read_body().await;
+    client_with_config(&endpoint, config)
+        .await
+        .get_logs_by_format(request, Format::JsonStream, false)
+        .await
+        .unwrap()
+        .try_concat()
+        .await
+        .unwrap();
+    server.await.unwrap()
+}
+
+#[tokio::test]
+async fn long_queries_are_posted() {
+    let config = HttpConfig {
+        method: RequestMethod::PostAbove(1024),
+        ..Default::default()
+    };
+    let addresses = (0..100u64)
+        .map(Address::from_low_u64_be)
+        .collect::<HashSet<_>>();
+
+    // a short query is still sent as a get
+    let (head, body) = get_logs(config.clone(), GetLogsRequest::default()).await;
+    assert!(head.starts_with("get /v1/api/logs?"), "{head}");
+    assert!(body.is_empty());
+
+    let request = GetLogsRequest {
+        address__in: addresses.clone(),
+        ..Default::default()
+    };
+    let (head, body) = get_logs(config, request).await;
+    let request_line = head.lines().next().unwrap();
+    assert!(request_line.starts_with("post /v1/api/logs?"), "{head}");
+    assert!(request_line.contains("format=json_stream"), "{head}");
+    assert!(!request_line.contains("address__in"), "{head}");
+    assert!(
+        head.contains("content-type: application/json\r\n"),
+        "{head}"
+    );
+
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let posted = body["address__in"].as_str().unwrap().split(',').count();
+    assert_eq!(posted, addresses.len());
+}
+
+#[tokio::test]
+async fn requests_can_always_be_posted() {
+    let config = HttpConfig {
+        method: RequestMethod::Post,
+        ..Default::default()
+    };
+
+    let (head, body) = get_logs(config, GetLogsRequest::default()).await;
+
+    assert!(head.starts_with("post /v1/api/logs?"), "{head}");
+    assert!(body.starts_with('{'), "{body}");
+}
+
+fn rate_limited_response(remaining: u64, reset: u64) -> String {
+    format!("HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\nx-ratelimit-limit: 100\r\nx-ratelimit-remaining: {remaining}\r\nx-ratelimit-reset: {reset}\r\ncontent-length: 3\r\nconnection: close\r\n\r\n{{}}\n")
+}
+
+async fn get_blocks_with(provider: &HttpProvider) -> Result<Vec<u8>, Error> {
+    provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await?
+        .try_concat()
+        .await
+}
+
+#[tokio::test]
+async fn rate_limits_are_read_from_responses() {
+    let (endpoint, server) = serve_all(vec![Some(rate_limited_response(7, 30))]).await;
+    let client = client(&endpoint).await;
+    assert_eq!(client.rate_limit(), None);
+
+    get_blocks_with(&client.inner).await.unwrap();
+
+    let info = client.rate_limit().unwrap();
+    assert_eq!(info.limit, Some(100));
+    assert_eq!(info.remaining, Some(7));
+    let reset_in = info.reset_at.unwrap() - info.received_at;
+    assert!(reset_in > Duration::from_secs(29), "{reset_in:?}");
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn rate_limited_requests_fail() {
+    let limited = "HTTP/1.1 429 Too Many Requests\r\nretry-after: 2\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+    let (endpoint, server) = serve_all(vec![Some(limited.to_string())]).await;
+
+    let err = get_blocks(&endpoint, Format::JsonStream).await.unwrap_err();
+
+    match err {
+        Error::RateLimited { retry_after } => {
+            assert_eq!(retry_after, Some(Duration::from_secs(2)))
+        }
+        other => panic!("expected a rate limited request, got {other:?}"),
+    }
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn requests_are_throttled_until_the_rate_limit_resets() {
+    let (endpoint, server) = serve_all(vec![
+        Some(rate_limited_response(0, 1)),
+        Some(rate_limited_response(100, 60)),
+    ])
+    .await;
+    let mut config = HttpConfig::default();
+    config.rate_limit.throttle_below = Some(1);
+    let provider = client_with_config(&endpoint, config).await;
+
+    get_blocks_with(&provider).await.unwrap();
+    let started = std::time::Instant::now();
+    get_blocks_with(&provider).await.unwrap();
+
+    assert!(started.elapsed() >= Duration::from_millis(900));
+    assert_eq!(provider.rate_limit().unwrap().remaining, Some(100));
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn retry_after_is_waited_out() {
+    let limited = "HTTP/1.1 429 Too Many Requests\r\nretry-after: 1\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+    let unavailable = "HTTP/1.1 503 Service Unavailable\r\nretry-after: 0\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+    let (endpoint, server) = serve_all(vec![
+        Some(limited.to_string()),
+        Some(unavailable.to_string()),
+        Some(OK.to_string()),
+    ])
+    .await;
+    let config = HttpConfig {
+        retry_after: Some(RetryAfterPolicy::default()),
+        ..Default::default()
+    };
+    let provider = client_with_config(&endpoint, config).await;
+
+    let started = std::time::Instant::now();
+    let body = get_blocks_with(&provider).await.unwrap();
+
+    assert_eq!(body, b"{}\n");
+    assert!(started.elapsed() >= Duration::from_millis(900));
+    assert_eq!(server.await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn long_retry_afters_fail_right_away() {
+    let limited = "HTTP/1.1 429 Too Many Requests\r\nretry-after: 3600\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+    let (endpoint, server) = serve_all(vec![Some(limited.to_string())]).await;
+    let config = HttpConfig {
+        retry_after: Some(RetryAfterPolicy::default()),
+        ..Default::default()
+    };
+    let provider = client_with_config(&endpoint, config).await;
+
+    let err = get_blocks_with(&provider).await.unwrap_err();
+
+    assert!(matches!(err, Error::RateLimited { .. }), "{err:?}");
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn logs_are_counted_without_fetching_them() {
+    let (endpoint, server) = serve_once(ok("application/json", "{\"count\":42}")).await;
+    let client = ClientBuilder::default()
+        .endpoint(&endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    let request = GetLogsRequest {
+        address__in: HashSet::from([Address::from_low_u64_be(1)]),
+        ..Default::default()
+    };
+
+    let count = client.get_logs_count(request).await.unwrap();
+
+    assert_eq!(count, 42);
+    let head = server.await.unwrap();
+    let request_line = head.lines().next().unwrap();
+    assert!(request_line.starts_with("GET /v1/api/logs?"), "{head}");
+    let params = request_line.split(['?', '&', ' ']).collect::<Vec<_>>();
+    assert!(params.contains(&"count_only=true"), "{head}");
+    assert!(params.contains(&"format=json"), "{head}");
+    assert!(request_line.contains("address__in=0x"), "{head}");
+}
+
+#[tokio::test]
+async fn datasets_the_server_cannot_count_are_unsupported() {
+    // a server without counting ignores the parameter and sends the records
+    let (endpoint, _server) = serve_once(ok("application/json", "[{\"block_number\":1}]")).await;
+    let client = ClientBuilder::default()
+        .endpoint(&endpoint)
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+
+    let err = client
+        .get_erc20_transfers_count(Default::default())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Unsupported { .. }), "{err:?}");
+}
+
+#[tokio::test]
+async fn unchanged_responses_are_not_modified() {
+    let (endpoint, server) = serve_all(vec![
+        "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\netag: \"v1\"\r\nlast-modified: Wed, 21 Oct 2026 07:28:00 GMT\r\ncontent-length: 3\r\nconnection: close\r\n\r\n{}\n".to_string(),
+        "HTTP/1.1 304 Not Modified\r\netag: \"v1\"\r\nconnection: close\r\n\r\n".to_string(),
+    ])
+    .await;
+    let config = HttpConfig {
+        conditional_requests: true,
+        ..Default::default()
+    };
+    let provider = client_with_config(&endpoint, config).await;
+
+    assert_eq!(get_blocks_with(&provider).await.unwrap(), b"{}\n");
+    let err = get_blocks_with(&provider).await.unwrap_err();
+
+    assert!(matches!(err, Error::NotModified), "{err:?}");
+    let heads = server.await.unwrap();
+    assert!(!heads[0].contains("if-none-match"), "{}", heads[0]);
+    assert!(
+        heads[1].contains("if-none-match: \"v1\"\r\n"),
+        "{}",
+        heads[1]
+    );
+    assert!(
+        heads[1].contains("if-modified-since: Wed, 21 Oct 2026 07:28:00 GMT\r\n"),
+        "{}",
+        heads[1]
+    );
+}
+
+fn redirect(location: &str) -> String {
+    format!("HTTP/1.1 307 Temporary Redirect\r\nlocation: {location}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+}
+
+#[tokio::test]
+async fn redirects_to_the_same_host_keep_the_credentials() {
+    let (endpoint, server) = serve_all(vec![
+        redirect("/v1/api/eu/blocks?chains=ETH"),
+        OK.to_string(),
+    ])
+    .await;
+    let provider = HttpProvider::try_new_with_config(
+        endpoint,
+        false,
+        Some("user".into()),
+        Some("secret".into()),
+        HttpConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(get_blocks_with(&provider).await.unwrap(), b"{}\n");
+
+    let heads = server.await.unwrap();
+    assert!(
+        heads[1].starts_with("GET /v1/api/eu/blocks?chains=ETH "),
+        "{}",
+        heads[1]
+    );
+    assert!(heads[1].contains("authorization: Basic "), "{}", heads[1]);
+}
+
+#[tokio::test]
+async fn see_other_redirects_are_followed_without_the_body() {
+    let see_other = "HTTP/1.1 303 See Other\r\nlocation: /v1/api/logs/result\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+    let (endpoint, server) = serve_all(vec![see_other.to_string(), OK.to_string()]).await;
+    let config = HttpConfig {
+        method: RequestMethod::Post,
+        ..Default::default()
+    };
+
+    client_with_config(&endpoint, config)
+        .await
+        .get_logs_by_format(GetLogsRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .try_concat()
+        .await
+        .unwrap();
+
+    let heads = server.await.unwrap();
+    assert!(heads[0].starts_with("POST /v1/api/logs?"), "{}", heads[0]);
+    assert!(
+        heads[1].starts_with("GET /v1/api/logs/result "),
+        "{}",
+        heads[1]
+    );
+    let head = heads[1].to_lowercase();
+    assert!(!head.contains("content-length"), "{head}");
+    assert!(!head.contains("content-type"), "{head}");
+}
+
+#[tokio::test]
+async fn redirects_to_other_hosts_need_to_be_trusted() {
+    let (target, target_server) = serve_all(vec![OK.to_string()]).await;
+    let port = target.rsplit(':').next().unwrap();
+    let location = format!("http://localhost:{port}/v1/api/blocks");
+    let (endpoint, _server) = serve_all(vec![redirect(&location); 2]).await;
+    let provider = |redirect| {
+        let config = HttpConfig {
+            redirect,
+            ..Default::default()
+        };
+        let endpoint = endpoint.clone();
+        async move {
+            HttpProvider::try_new_with_config(
+                endpoint,
+                false,
+                Some("user".into()),
+                Some("secret".into()),
+                config,
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    let untrusted = provider(RedirectPolicy::default()).await;
+    let err = get_blocks_with(&untrusted).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::Redirect { location: refused, .. } if *refused == location),
+        "{err:?}"
+    );
+
+    let trusted = provider(RedirectPolicy {
+        trusted_hosts: vec!["localhost".into()],
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(get_blocks_with(&trusted).await.unwrap(), b"{}\n");
+    let heads = target_server.await.unwrap();
+    assert!(heads[0].contains("authorization: Basic "), "{}", heads[0]);
+}
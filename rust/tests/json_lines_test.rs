@@ -0,0 +1,116 @@
+use futures::{executor::block_on, StreamExt, TryStreamExt};
+use pangea_client::{
+    core::types::{btc::BtcTransaction, fuel::Receipt, txs::Transaction},
+    provider::ResponseStream,
+    utils::{json_lines, json_lines_with_max_line_size, json_values},
+    Error,
+};
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+
+const BTC_TXS: &str = include_str!("fixtures/btc_txs.jsonl");
+const FUEL_RECEIPTS: &str = include_str!("fixtures/fuel_receipts.jsonl");
+const TXS: &str = include_str!("fixtures/txs.jsonl");
+
+/// Splits `data` into consecutive chunks, cycling through `sizes`
+fn rechunked(data: &[u8], sizes: &[usize]) -> ResponseStream<Vec<u8>> {
+    let mut chunks = Vec::new();
+    let mut rest = data;
+    for size in sizes.iter().cycle() {
+        if rest.is_empty() {
+            break;
+        }
+        let (chunk, tail) = rest.split_at((*size).min(rest.len()));
+        chunks.push(chunk.to_vec());
+        rest = tail;
+    }
+    futures::stream::iter(chunks.into_iter().map(Ok)).boxed()
+}
+
+fn decode<T>(stream: ResponseStream<Vec<u8>>) -> Vec<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    block_on(json_lines::<T>(stream).unwrap().try_collect()).unwrap()
+}
+
+fn assert_rechunking_is_transparent<T>(fixture: &str, sizes: &[usize])
+where
+    T: DeserializeOwned + PartialEq + std::fmt::Debug + Send + 'static,
+{
+    let expected = fixture
+        .lines()
+        .map(|line| serde_json::from_str::<T>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(decode::<T>(rechunked(fixture.as_bytes(), sizes)), expected);
+}
+
+proptest! {
+    #[test]
+    fn rechunked_txs(sizes in prop::collection::vec(1usize..256, 1..32)) {
+        assert_rechunking_is_transparent::<Transaction>(TXS, &sizes);
+    }
+
+    #[test]
+    fn rechunked_btc_txs(sizes in prop::collection::vec(1usize..256, 1..32)) {
+        assert_rechunking_is_transparent::<BtcTransaction>(BTC_TXS, &sizes);
+    }
+
+    #[test]
+    fn rechunked_fuel_receipts(sizes in prop::collection::vec(1usize..256, 1..32)) {
+        assert_rechunking_is_transparent::<Receipt>(FUEL_RECEIPTS, &sizes);
+    }
+}
+
+#[test]
+fn empty_and_crlf_lines() {
+    let data = TXS.replace('\n', "\r\n\r\n\n");
+    let expected = decode::<Transaction>(rechunked(TXS.as_bytes(), &[TXS.len()]));
+    assert_eq!(
+        decode::<Transaction>(rechunked(data.as_bytes(), &[3])),
+        expected
+    );
+}
+
+#[test]
+fn max_line_size_exceeded() {
+    let line = TXS.lines().next().unwrap();
+    let data = format!("{line}\n{line}{}\n{line}\n", " ".repeat(line.len()));
+
+    let records = block_on(
+        json_lines_with_max_line_size::<Transaction>(
+            rechunked(data.as_bytes(), &[7]),
+            line.len() + 1,
+        )
+        .unwrap()
+        .collect::<Vec<_>>(),
+    );
+
+    // the first record fits, the oversized one ends the stream
+    assert_eq!(records.len(), 2);
+    assert!(records[0].is_ok());
+    assert!(matches!(records[1], Err(Error::MaxLineSizeExceeded(_))));
+}
+
+#[test]
+fn values_keep_going_after_a_malformed_line() {
+    let line = TXS.lines().next().unwrap();
+    let data = format!("{line}\n{{\"chain\": 1,\n{line}\n");
+
+    let records = block_on(
+        json_values(rechunked(data.as_bytes(), &[5]))
+            .unwrap()
+            .collect::<Vec<_>>(),
+    );
+
+    assert_eq!(records.len(), 3);
+    assert_eq!(
+        records[0].as_ref().unwrap(),
+        &serde_json::from_str::<serde_json::Value>(line).unwrap()
+    );
+    match &records[1] {
+        Err(Error::MalformedJsonLine { line, .. }) => assert_eq!(line, "{\"chain\": 1,"),
+        other => panic!("expected a malformed line, got {other:?}"),
+    }
+    assert!(records[2].is_ok());
+}
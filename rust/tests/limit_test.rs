@@ -0,0 +1,86 @@
+mod common;
+
+use common::{ok, query, serve_once};
+use futures::{StreamExt, TryStreamExt};
+use pangea_client::{
+    provider::Provider,
+    query::SortOrder,
+    requests::{logs::GetLogsRequest, mira::GetMiraSwapsRequest, txs::GetTxsRequest},
+    Client, HttpProvider,
+};
+
+const LOGS: &str = include_str!("fixtures/logs.jsonl");
+
+#[test]
+fn limit_and_order_are_sent_only_when_set() {
+    assert_eq!(
+        query(&GetTxsRequest::default()),
+        "chains=ETH&from_block=latest&to_block=latest"
+    );
+
+    let request = GetTxsRequest {
+        limit: Some(100),
+        order: Some(SortOrder::Desc),
+        ..Default::default()
+    };
+    assert!(query(&request).ends_with("&limit=100&order=desc"));
+
+    let swaps = GetMiraSwapsRequest::builder()
+        .chain(pangea_client::ChainId::FUEL)
+        .limit(10)
+        .order(SortOrder::Asc)
+        .build()
+        .unwrap();
+    assert!(query(&swaps).ends_with("&limit=10&order=asc"));
+}
+
+#[tokio::test]
+async fn typed_streams_stop_at_the_limit() {
+    assert!(LOGS.lines().count() > 1);
+    // the server ignores the limit and sends all of its logs
+    let (endpoint, server) = serve_once(ok("application/x-ndjson", LOGS)).await;
+    let provider = HttpProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let request = GetLogsRequest {
+        limit: Some(1),
+        order: Some(SortOrder::Desc),
+        ..Default::default()
+    };
+
+    let logs: Vec<_> = Client::new(provider)
+        .get_logs(request)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(logs.len(), 1);
+    let head = server.await.unwrap();
+    assert!(head.contains("limit=1&order=desc"), "{head}");
+}
+
+#[tokio::test]
+async fn malformed_records_do_not_count_towards_the_limit() {
+    let body = format!("{{\"malformed\":true}}\n{LOGS}");
+    let (endpoint, _server) = serve_once(ok("application/x-ndjson", &body)).await;
+    let provider = HttpProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let request = GetLogsRequest {
+        limit: Some(2),
+        ..Default::default()
+    };
+
+    let logs: Vec<_> = Client::new(provider)
+        .get_logs(request)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(logs.len(), 3);
+    assert!(logs[0].is_err());
+    assert!(logs[1..].iter().all(Result::is_ok));
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "proto")]
+
+use futures::{StreamExt, TryStreamExt};
+use pangea_client::{
+    proto::{proto_records, Log},
+    provider::ResponseStream,
+    Error, Format,
+};
+use prost::Message;
+
+fn logs(count: u64) -> Vec<Log> {
+    (0..count)
+        .map(|n| Log {
+            chain: 1,
+            block_number: 17_000_000 + n / 4,
+            tx_hash: vec![n as u8; 32],
+            log_index: n,
+            address: vec![0x11; 20],
+            // messages above 127 bytes need a multi byte length prefix
+            topics: vec![vec![0xaa; 32]; (n % 5) as usize],
+            data: vec![0; (n * 7) as usize],
+            removed: false,
+        })
+        .collect()
+}
+
+fn encode(logs: &[Log]) -> Vec<u8> {
+    logs.iter()
+        .flat_map(Message::encode_length_delimited_to_vec)
+        .collect()
+}
+
+fn chunked(data: &[u8], size: usize) -> ResponseStream<Vec<u8>> {
+    let chunks = data.chunks(size).map(<[u8]>::to_vec).collect::<Vec<_>>();
+    futures::stream::iter(chunks.into_iter().map(Ok)).boxed()
+}
+
+#[tokio::test]
+async fn messages_spanning_chunks() {
+    let expected = logs(50);
+    let data = encode(&expected);
+
+    for size in [1, 3, 64, data.len()] {
+        let decoded = proto_records::<Log>(Ok(chunked(&data, size)))
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(decoded, expected, "chunk size {size}");
+    }
+}
+
+#[tokio::test]
+async fn truncated_response() {
+    let data = encode(&logs(3));
+
+    let results = proto_records::<Log>(Ok(chunked(&data[..data.len() - 1], 16)))
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[2], Err(Error::ProtoFraming(_))));
+}
+
+#[tokio::test]
+async fn refused_format() {
+    let err = proto_records::<Log>(Err(Error::UnexpectedContentType {
+        expected: Format::Proto.content_type(),
+        got: "application/x-ndjson".to_string(),
+    }))
+    .err()
+    .unwrap();
+    assert!(matches!(
+        err,
+        Error::UnsupportedFormat {
+            format: Format::Proto,
+            ..
+        }
+    ));
+
+    let refusal = br#"{"status":400,"error":"unknown format proto"}"#;
+    let results = proto_records::<Log>(Ok(chunked(refusal, refusal.len())))
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    match &results[..] {
+        [Err(Error::UnsupportedFormat { reason, .. })] => {
+            assert_eq!(reason, "unknown format proto")
+        }
+        other => panic!("expected an unsupported format, got {other:?}"),
+    }
+
+    // a server ignoring the format answers with JSON records
+    let json = b"{\"chain\":1,\"block_number\":\"0x1\"}\n";
+    let results = proto_records::<Log>(Ok(chunked(json, 4)))
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    assert!(
+        matches!(results.last(), Some(Err(Error::UnsupportedFormat { .. }))),
+        "{results:?}"
+    );
+}
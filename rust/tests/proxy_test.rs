@@ -0,0 +1,147 @@
+mod common;
+
+use common::{listen, read_head};
+use futures::TryStreamExt;
+use pangea_client::{
+    provider::ChainProvider, requests::blocks::GetBlocksRequest, Error, Format, HttpConfig,
+    HttpProvider, Proxy, WsConfig, WsProvider,
+};
+use tokio::{io::AsyncWriteExt, net::TcpStream, sync::oneshot};
+
+/// Relays a single connection, tunnelled with `CONNECT` or forwarded in
+/// absolute form, and sends on the head the client sent to the proxy
+async fn proxy() -> (String, oneshot::Receiver<String>) {
+    let (listener, address) = listen().await;
+    let url = format!("http://{address}");
+
+    let (sender, receiver) = oneshot::channel();
+    tokio::spawn(async move {
+        let (mut client, _) = listener.accept().await.unwrap();
+        let head = read_head(&mut client).await;
+        let target = head.split_whitespace().nth(1).unwrap().to_string();
+
+        let mut server = match target.strip_prefix("http://") {
+            Some(target) => {
+                let authority = target.split('/').next().unwrap();
+                let mut server = TcpStream::connect(authority).await.unwrap();
+                server.write_all(head.as_bytes()).await.unwrap();
+                server
+            }
+            None => {
+                let server = TcpStream::connect(target.as_str()).await.unwrap();
+                client
+                    .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                    .await
+                    .unwrap();
+                server
+            }
+        };
+        sender.send(head).unwrap();
+        let _ = tokio::io::copy_bidirectional(&mut client, &mut server).await;
+    });
+
+    (url, receiver)
+}
+
+#[tokio::test]
+async fn http_requests_go_through_the_proxy() {
+    let (listener, endpoint) = listen().await;
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        read_head(&mut socket).await;
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ncontent-length: 3\r\nconnection: close\r\n\r\n{}\n")
+            .await
+            .unwrap();
+    });
+    let (url, proxy) = proxy().await;
+
+    let config = HttpConfig {
+        proxy: Proxy::Http {
+            url,
+            username: Some("user".into()),
+            password: Some("secret".into()),
+        },
+        ..Default::default()
+    };
+    let provider = HttpProvider::try_new_with_config(endpoint.clone(), false, None, None, config)
+        .await
+        .unwrap();
+    let body: Vec<u8> = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .try_concat()
+        .await
+        .unwrap();
+
+    assert_eq!(body, b"{}\n");
+    server.await.unwrap();
+    let head = proxy.await.unwrap().to_lowercase();
+    assert!(
+        head.starts_with(&format!("get http://{endpoint}/")),
+        "{head}"
+    );
+    // user:secret
+    assert!(
+        head.contains("proxy-authorization: basic dxnlcjpzzwnyzxq=\r\n"),
+        "{head}"
+    );
+}
+
+#[tokio::test]
+async fn ws_connections_are_tunnelled() {
+    let (listener, endpoint) = listen().await;
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        tokio_tungstenite::accept_async(socket).await.unwrap()
+    });
+    let (url, proxy) = proxy().await;
+
+    let config = WsConfig {
+        proxy: Proxy::Http {
+            url,
+            username: None,
+            password: None,
+        },
+        ..Default::default()
+    };
+    let _provider = WsProvider::try_new_with_config(endpoint.clone(), false, None, None, config)
+        .await
+        .unwrap();
+
+    let _ws = server.await.unwrap();
+    let head = proxy.await.unwrap();
+    assert!(
+        head.starts_with(&format!("CONNECT {endpoint} HTTP/1.1\r\n")),
+        "{head}"
+    );
+}
+
+#[tokio::test]
+async fn refused_tunnels_fail() {
+    let (listener, address) = listen().await;
+    let url = format!("http://{address}");
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        read_head(&mut socket).await;
+        socket
+            .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+            .await
+            .unwrap();
+    });
+
+    let config = WsConfig {
+        proxy: Proxy::Http {
+            url,
+            username: None,
+            password: None,
+        },
+        ..Default::default()
+    };
+    let err = WsProvider::try_new_with_config("127.0.0.1:9".into(), false, None, None, config)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Proxy(_)), "{err:?}");
+}
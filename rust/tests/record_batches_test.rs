@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Int64Array, StringArray},
+    datatypes::{DataType, Field, Schema},
+    ipc::writer::StreamWriter,
+    record_batch::RecordBatch,
+};
+use futures::StreamExt;
+use pangea_client::{
+    arrow::{peek_schema, record_batches, select_columns},
+    provider::ResponseStream,
+    Error,
+};
+
+fn schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("block_number", DataType::Int64, false),
+        Field::new("hash", DataType::Utf8, false),
+    ]))
+}
+
+fn batch(from: i64, len: i64) -> RecordBatch {
+    let numbers = (from..from + len).collect::<Vec<_>>();
+    let hashes = numbers
+        .iter()
+        .map(|n| format!("0x{n:064x}"))
+        .collect::<Vec<_>>();
+    RecordBatch::try_new(
+        schema(),
+        vec![
+            Arc::new(Int64Array::from(numbers)),
+            Arc::new(StringArray::from(hashes)),
+        ],
+    )
+    .unwrap()
+}
+
+/// Writes `batches` as one IPC stream
+fn ipc_stream(batches: &[RecordBatch]) -> Vec<u8> {
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema()).unwrap();
+    for batch in batches {
+        writer.write(batch).unwrap();
+    }
+    writer.into_inner().unwrap()
+}
+
+fn chunked(data: &[u8], size: usize) -> ResponseStream<Vec<u8>> {
+    let chunks = data.chunks(size).map(<[u8]>::to_vec).collect::<Vec<_>>();
+    futures::stream::iter(chunks.into_iter().map(Ok)).boxed()
+}
+
+#[tokio::test]
+async fn batch_spanning_many_chunks() {
+    let batches = vec![batch(0, 100), batch(100, 50)];
+    let data = ipc_stream(&batches);
+
+    for size in [1, 7, 64, 1000] {
+        let mut stream = record_batches(chunked(&data, size));
+        assert!(stream.schema().is_none());
+
+        let mut decoded = Vec::new();
+        while let Some(batch) = stream.next().await {
+            decoded.push(batch.unwrap());
+            assert_eq!(stream.schema(), Some(schema()));
+        }
+        assert_eq!(decoded, batches, "chunk size {size}");
+    }
+}
+
+#[tokio::test]
+async fn many_batches_in_one_chunk() {
+    let batches = vec![batch(0, 3), batch(3, 3), batch(6, 3)];
+    let data = ipc_stream(&batches);
+
+    let decoded = record_batches(chunked(&data, data.len()))
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(decoded, batches);
+}
+
+#[tokio::test]
+async fn schema_without_batches() {
+    let data = ipc_stream(&[]);
+
+    let mut stream = record_batches(chunked(&data, 5));
+    assert!(stream.next().await.is_none());
+    assert_eq!(stream.schema(), Some(schema()));
+}
+
+#[tokio::test]
+async fn back_to_back_ipc_streams() {
+    let mut data = ipc_stream(&[batch(0, 10)]);
+    data.extend(ipc_stream(&[batch(10, 10), batch(20, 10)]));
+
+    let decoded = record_batches(chunked(&data, 13))
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(decoded, vec![batch(0, 10), batch(10, 10), batch(20, 10)]);
+}
+
+#[tokio::test]
+async fn truncated_stream_is_an_error() {
+    let data = ipc_stream(&[batch(0, 10)]);
+
+    let results = record_batches(chunked(&data[..data.len() - 20], 16))
+        .collect::<Vec<_>>()
+        .await;
+    assert!(matches!(results.last(), Some(Err(_))));
+}
+
+/// The length of the schema message, which follows the continuation marker and its size
+fn header_len(data: &[u8]) -> usize {
+    8 + u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize
+}
+
+#[tokio::test]
+async fn peek_schema_reads_only_the_header() {
+    let data = ipc_stream(&[batch(0, 100)]);
+
+    for size in [1, 7, 1000] {
+        // the chunks after the header are never polled
+        let stream = chunked(&data[..header_len(&data)], size)
+            .chain(futures::stream::once(async {
+                panic!("polled past the header")
+            }))
+            .boxed();
+
+        assert_eq!(peek_schema(Ok(stream)).await.unwrap(), schema());
+    }
+}
+
+#[tokio::test]
+async fn peek_schema_of_empty_response() {
+    assert!(peek_schema(Ok(chunked(&[], 1))).await.is_err());
+}
+
+#[tokio::test]
+async fn select_columns_projects_batches() {
+    let data = ipc_stream(&[batch(0, 5), batch(5, 5)]);
+
+    let projected = select_columns(record_batches(chunked(&data, 64)), &["hash"])
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(projected.len(), 2);
+    for (projected, expected) in projected.iter().zip([batch(0, 5), batch(5, 5)]) {
+        assert_eq!(projected.num_columns(), 1);
+        assert_eq!(projected.schema().field(0).name(), "hash");
+        assert_eq!(projected.column(0), expected.column(1));
+    }
+}
+
+#[tokio::test]
+async fn select_missing_column() {
+    let data = ipc_stream(&[batch(0, 5), batch(5, 5)]);
+
+    let results = select_columns(record_batches(chunked(&data, 64)), &["hash", "address"])
+        .collect::<Vec<_>>()
+        .await;
+
+    // the stream ends with the first error
+    assert_eq!(results.len(), 1);
+    assert!(matches!(&results[0], Err(Error::MissingColumn(column)) if column == "address"));
+}
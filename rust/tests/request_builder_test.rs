@@ -0,0 +1,182 @@
+mod common;
+
+use common::query;
+use ethers_core::types::{Address, H256};
+use pangea_client::{
+    query::{Bound, TimeBound},
+    requests::{
+        logs::GetLogsRequest,
+        mira::{GetMiraLiquidityRequest, GetMiraPoolsRequest, GetMiraSwapsRequest},
+    },
+    ChainId, Error,
+};
+
+#[test]
+fn logs_requests_are_built_from_typed_setters() {
+    let request = GetLogsRequest::builder()
+        .chain(ChainId::ETH)
+        .chains([ChainId::ARB])
+        .address(Address::repeat_byte(1))
+        .addresses([Address::repeat_byte(2)])
+        .topic0(H256::repeat_byte(3))
+        .from_block(Bound::Exact(100))
+        .to_block(Bound::Exact(200))
+        .build()
+        .unwrap();
+
+    assert_eq!(request.chains.len(), 2);
+    assert_eq!(request.address__in.len(), 2);
+    assert!(request.topic0__in.contains(&H256::repeat_byte(3)));
+    assert_eq!(request.from_block, Bound::Exact(100));
+    assert_eq!(request.to_block, Bound::Exact(200));
+}
+
+#[test]
+fn logs_requests_are_bounded_by_time() {
+    let request = GetLogsRequest::builder()
+        .chain(ChainId::ETH)
+        .topic1(H256::repeat_byte(1))
+        .from_time(1_700_000_000)
+        .to_time(1_700_086_400)
+        .build()
+        .unwrap();
+
+    assert!(request.topic1__in.contains(&H256::repeat_byte(1)));
+    assert_eq!(request.from_time, Some(TimeBound::Timestamp(1_700_000_000)));
+    assert_eq!(request.to_time, Some(TimeBound::Timestamp(1_700_086_400)));
+
+    let err = GetLogsRequest::builder()
+        .chain(ChainId::ETH)
+        .from_block(Bound::Exact(1))
+        .from_time(1_700_000_000)
+        .build()
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidBounds(_)), "{err:?}");
+}
+
+#[test]
+fn logs_requests_need_a_chain() {
+    let err = GetLogsRequest::builder().build().unwrap_err();
+
+    assert!(matches!(&err, Error::InvalidRequest(reason) if reason.contains("no chains")));
+}
+
+#[test]
+fn logs_requests_cannot_end_before_they_start() {
+    let err = GetLogsRequest::builder()
+        .chain(ChainId::ETH)
+        .from_block(Bound::Exact(200))
+        .to_block(Bound::Exact(100))
+        .build()
+        .unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Invalid request: from_block 200 is after to_block 100"
+    );
+    // relative bounds are only known to the server
+    GetLogsRequest::builder()
+        .chain(ChainId::ETH)
+        .from_block(Bound::Exact(200))
+        .to_block(Bound::Latest)
+        .build()
+        .unwrap();
+}
+
+#[test]
+fn mira_requests_are_built_from_typed_setters() {
+    let pool = H256::repeat_byte(1);
+    let request = GetMiraSwapsRequest::builder()
+        .chain(ChainId::FUEL)
+        .from_time(1_700_000_000)
+        .pool_address__in([pool])
+        .build()
+        .unwrap();
+
+    assert_eq!(request.from_time, Some(TimeBound::Timestamp(1_700_000_000)));
+    assert!(request.pool_address__in.contains(&pool));
+    assert!(request.asset0_address__in.is_empty());
+
+    let err = GetMiraSwapsRequest::builder()
+        .chain(ChainId::FUEL)
+        .from_block(Bound::Exact(1))
+        .from_time(1_700_000_000)
+        .build()
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidBounds(_)));
+}
+
+#[test]
+fn mira_requests_keep_their_wire_format() {
+    let request: GetMiraPoolsRequest = serde_json::from_value(serde_json::json!({
+        "chains": ["FUEL"],
+        "from_block": 10,
+        "asset0__in": [H256::repeat_byte(2)],
+    }))
+    .unwrap();
+
+    assert_eq!(request.from_block, Bound::Exact(10));
+    assert_eq!(request.to_block, Bound::Latest);
+    assert_eq!(request.asset0_address__in.len(), 1);
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["chains"], "FUEL");
+    assert!(json["pool_address__in"].is_null());
+    assert!(json.get("from_timestamp").is_none());
+
+    let default = GetMiraLiquidityRequest::default();
+    assert!(default.chains.contains(&ChainId::ETH));
+}
+
+#[test]
+fn logs_requests_filter_events_by_topic() {
+    let to = Address::repeat_byte(1);
+    let mut request = GetLogsRequest::event("Transfer(address,address,uint256)");
+    request.topic2__in.insert(H256::from(to));
+
+    let transfer: H256 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+        .parse()
+        .unwrap();
+    assert_eq!(request.topic0__in, [transfer].into());
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["topic0__in"], format!("{transfer:?}"));
+    assert_eq!(json["topic2__in"], format!("{:?}", H256::from(to)));
+    // empty filters are left out
+    assert!(json.get("topic1__in").is_none());
+    assert!(json.get("topic3__in").is_none());
+    assert!(json.get("address__in").is_none());
+}
+
+#[test]
+fn mira_pairs_match_either_asset_order() {
+    let a = H256::repeat_byte(0x11);
+    let b = H256::repeat_byte(0x22);
+    let request = GetMiraPoolsRequest::default().pair(a, b).unwrap();
+
+    let query = query(&request);
+    let assets = [format!("{a:?}%2C{b:?}"), format!("{b:?}%2C{a:?}")];
+    let filter = |name: &str| {
+        assets
+            .iter()
+            .any(|assets| query.contains(&format!("{name}={assets}")))
+    };
+    assert!(filter("asset0_address__in"), "{query}");
+    assert!(filter("asset1_address__in"), "{query}");
+    assert!(query.starts_with("chains=ETH&from_block=latest&to_block=latest&"));
+
+    let swaps = GetMiraSwapsRequest::default().pair(a, b).unwrap();
+    assert_eq!(swaps.asset0_address__in, request.asset0_address__in);
+    assert_eq!(
+        GetMiraLiquidityRequest::default()
+            .pair(a, b)
+            .unwrap()
+            .asset1_address__in
+            .len(),
+        2
+    );
+
+    // a second pair would also match pools of an asset of each pair
+    let c = H256::repeat_byte(0x33);
+    let err = request.pair(c, a).unwrap_err();
+    assert!(matches!(err, Error::InvalidRequest(_)), "{err:?}");
+}
@@ -0,0 +1,355 @@
+mod common;
+
+use std::collections::HashSet;
+
+use common::query;
+use ethers_core::types::{Address, U256};
+use pangea_client::{
+    core::types::fuel::{OrderType, ReceiptType},
+    requests::{
+        curve::GetCrvPriceRequest,
+        erc20::GetErc20TransfersRequest,
+        fuel::{GetFuelLogsRequest, GetFuelReceiptsRequest, GetSparkOrderRequest},
+        transfers::GetTransfersRequest,
+        txs::{GetTxsRequest, TxStatus},
+        uniswap_v3::{GetFeesRequest, GetPositionsRequest, GetPricesRequest},
+        HasBlockBounds,
+    },
+    Error,
+};
+
+#[test]
+fn default_requests_keep_their_query_string() {
+    assert_eq!(
+        query(&GetTxsRequest::default()),
+        "chains=ETH&from_block=latest&to_block=latest"
+    );
+}
+
+#[test]
+fn txs_are_filtered_by_status_and_addresses() {
+    let to = Address::repeat_byte(1);
+    let request = GetTxsRequest {
+        status: TxStatus::Success,
+        to__in: HashSet::from([to]),
+        value__gte: Some(U256::exp10(18)),
+        ..Default::default()
+    };
+
+    let query = query(&request);
+    assert!(query.contains(&format!("to__in={to:?}")), "{query}");
+    assert!(query.contains("status=1"), "{query}");
+    assert!(query.contains("value__gte=0xde0b6b3a7640000"), "{query}");
+    assert!(!query.contains("from__in"), "{query}");
+}
+
+#[test]
+fn txs_filters_round_trip_through_json() {
+    for status in [TxStatus::Success, TxStatus::Failed, TxStatus::All] {
+        let json = serde_json::to_value(status).unwrap();
+        assert_eq!(serde_json::from_value::<TxStatus>(json).unwrap(), status);
+    }
+
+    let from = Address::repeat_byte(2);
+    let request: GetTxsRequest = serde_json::from_value(serde_json::json!({
+        "status": 0,
+        "from__in": [from],
+        "value__gte": U256::from(5),
+    }))
+    .unwrap();
+    assert_eq!(request.status, TxStatus::Failed);
+    assert_eq!(request.from__in, HashSet::from([from]));
+    assert_eq!(request.value__gte, Some(U256::from(5)));
+
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["status"], 0);
+    assert_eq!(json["from__in"], format!("{from:?}"));
+
+    let err = serde_json::from_str::<GetTxsRequest>(r#"{"status":2}"#).unwrap_err();
+    assert!(err.to_string().contains("invalid transaction status"));
+}
+
+#[test]
+fn side_filters_are_sent_along_with_either_side_filters() {
+    let treasury = Address::repeat_byte(1);
+    let exchange = Address::repeat_byte(2);
+    let request = GetTransfersRequest {
+        address__in: HashSet::from([treasury]),
+        to__in: HashSet::from([exchange]),
+        ..Default::default()
+    };
+
+    // neither replaces the other, the server applies both
+    let query = query(&request);
+    assert!(
+        query.contains(&format!("address__in={treasury:?}")),
+        "{query}"
+    );
+    assert!(query.contains(&format!("to__in={exchange:?}")), "{query}");
+    assert!(!query.contains("from__in"), "{query}");
+}
+
+#[test]
+fn side_filters_accept_the_address_names() {
+    let from = Address::repeat_byte(1);
+    let to = Address::repeat_byte(2);
+    let request: GetTransfersRequest = serde_json::from_value(serde_json::json!({
+        "from_address__in": [from],
+        "to_address__in": [to],
+    }))
+    .unwrap();
+
+    assert_eq!(request.from__in, HashSet::from([from]));
+    assert_eq!(request.to__in, HashSet::from([to]));
+    assert!(request.address__in.is_empty());
+}
+
+#[test]
+fn amounts_are_sent_as_decimal_strings() {
+    let request = GetErc20TransfersRequest {
+        amount__gte: Some(U256::exp10(24)),
+        amount__lte: Some(U256::MAX),
+        ..Default::default()
+    };
+
+    let query = query(&request);
+    assert!(
+        query.contains("amount__gte=1000000000000000000000000"),
+        "{query}"
+    );
+    assert!(
+        query.contains(&format!("amount__lte={}", U256::MAX)),
+        "{query}"
+    );
+    let query = self::query(&GetErc20TransfersRequest::default());
+    assert!(!query.contains("amount__"), "{query}");
+}
+
+#[test]
+fn amounts_round_trip_through_json() {
+    let request = GetErc20TransfersRequest {
+        amount__lte: Some(U256::MAX),
+        ..Default::default()
+    };
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["amount__lte"], U256::MAX.to_string());
+
+    let request: GetErc20TransfersRequest = serde_json::from_value(serde_json::json!({
+        "amount__gte": "0x10",
+        "amount__lte": json["amount__lte"],
+    }))
+    .unwrap();
+    assert_eq!(request.amount__gte, Some(U256::from(16)));
+    assert_eq!(request.amount__lte, Some(U256::MAX));
+}
+
+#[test]
+fn empty_amount_ranges_are_refused() {
+    let request = GetErc20TransfersRequest {
+        amount__gte: Some(U256::from(10)),
+        amount__lte: Some(U256::from(5)),
+        ..Default::default()
+    };
+
+    let err = request.check_bounds().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid request: amount__gte 10 is above amount__lte 5"
+    );
+    assert!(matches!(err, Error::InvalidRequest(_)));
+}
+
+#[test]
+#[allow(deprecated)]
+fn the_former_name_still_compiles() {
+    let _: pangea_client::requests::erc20::GetErc20TransferssRequest = Default::default();
+}
+
+#[test]
+fn prices_are_filtered_by_pool_tokens_and_fee_tier() {
+    let pool = Address::repeat_byte(1);
+    let token0 = Address::repeat_byte(2);
+    let request = GetPricesRequest {
+        pool_address__in: HashSet::from([pool]),
+        token0_address__in: HashSet::from([token0]),
+        fee__in: HashSet::from([500]),
+        ..Default::default()
+    };
+
+    let query = query(&request);
+    assert!(
+        query.contains(&format!("pool_address__in={pool:?}")),
+        "{query}"
+    );
+    assert!(
+        query.contains(&format!("token0_address__in={token0:?}")),
+        "{query}"
+    );
+    assert!(query.contains("fee__in=500"), "{query}");
+    assert!(!query.contains("token1_address__in"), "{query}");
+
+    let default = self::query(&GetPricesRequest::default());
+    assert!(!default.contains("fee__in"), "{default}");
+}
+
+#[test]
+fn price_filters_accept_the_short_token_names() {
+    let request: GetPricesRequest = serde_json::from_value(serde_json::json!({
+        "token0__in": [Address::repeat_byte(1)],
+        "token1__in": [Address::repeat_byte(2)],
+        "fee__in": [3000, 10000],
+    }))
+    .unwrap();
+
+    assert_eq!(request.token0_address__in.len(), 1);
+    assert_eq!(request.token1_address__in.len(), 1);
+    assert_eq!(request.fee__in, HashSet::from([3000, 10000]));
+}
+
+#[test]
+fn fees_and_positions_are_filtered_by_pool() {
+    let pool = Address::repeat_byte(1);
+    let fees = GetFeesRequest {
+        pool_address__in: HashSet::from([pool]),
+        ..Default::default()
+    };
+    let positions = GetPositionsRequest {
+        pool_address__in: HashSet::from([pool]),
+        ..Default::default()
+    };
+
+    let expected = format!("pool_address__in={pool:?}");
+    assert!(query(&fees).contains(&expected));
+    assert!(query(&positions).contains(&expected));
+}
+
+#[test]
+fn pairs_match_both_directions() {
+    let usdc = Address::repeat_byte(1);
+    let crvusd = Address::repeat_byte(2);
+    let request = GetCrvPriceRequest::default().pair(usdc, crvusd).unwrap();
+
+    assert_eq!(request.sold_address__in, HashSet::from([usdc, crvusd]));
+    assert_eq!(request.bought_address__in, HashSet::from([usdc, crvusd]));
+    let query = query(&request);
+    assert!(query.contains("sold_address__in="), "{query}");
+    assert!(query.contains("bought_address__in="), "{query}");
+    assert!(!query.contains("pool_address__in"), "{query}");
+
+    let err = request.pair(usdc, Address::repeat_byte(3)).unwrap_err();
+    assert!(matches!(err, Error::InvalidRequest(_)), "{err:?}");
+}
+
+#[test]
+fn pairs_are_refused_on_top_of_either_token_filter() {
+    let (usdc, crvusd) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let sold = GetCrvPriceRequest {
+        sold_address__in: HashSet::from([usdc]),
+        ..Default::default()
+    };
+    let bought = GetCrvPriceRequest {
+        bought_address__in: HashSet::from([usdc]),
+        ..Default::default()
+    };
+
+    for request in [sold, bought] {
+        let err = request.pair(usdc, crvusd).unwrap_err();
+        assert!(matches!(err, Error::InvalidRequest(_)), "{err:?}");
+    }
+}
+
+#[test]
+fn token_filters_accept_the_price_field_names() {
+    let pool = Address::repeat_byte(3);
+    let request: GetCrvPriceRequest = serde_json::from_value(serde_json::json!({
+        "token_in__in": [Address::repeat_byte(1)],
+        "token_out__in": [Address::repeat_byte(2)],
+        "pool_address__in": [pool],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        request.sold_address__in,
+        HashSet::from([Address::repeat_byte(1)])
+    );
+    assert_eq!(
+        request.bought_address__in,
+        HashSet::from([Address::repeat_byte(2)])
+    );
+    assert!(query(&request).contains(&format!("pool_address__in={pool:?}")));
+}
+
+#[test]
+fn receipts_are_filtered_by_type_name() {
+    let request = GetFuelReceiptsRequest {
+        receipt_type__in: HashSet::from([ReceiptType::LogData]),
+        ..Default::default()
+    };
+    assert!(query(&request).ends_with("receipt_type__in=LogData"));
+
+    let request = GetFuelReceiptsRequest {
+        receipt_type__in: HashSet::from([ReceiptType::LogData, ReceiptType::TransferOut]),
+        ..Default::default()
+    };
+    let query = query(&request);
+    assert!(
+        query.ends_with("receipt_type__in=LogData%2CTransferOut")
+            || query.ends_with("receipt_type__in=TransferOut%2CLogData"),
+        "{query}"
+    );
+
+    let query = self::query(&GetFuelReceiptsRequest::default());
+    assert!(!query.contains("receipt_type__in"), "{query}");
+}
+
+#[test]
+fn receipt_types_round_trip_through_json() {
+    let request: GetFuelReceiptsRequest = serde_json::from_value(serde_json::json!({
+        "receipt_type__in": ["TransferOut", "MessageOut", "Burn"],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        request.receipt_type__in,
+        HashSet::from([
+            ReceiptType::TransferOut,
+            ReceiptType::MessageOut,
+            ReceiptType::Burn
+        ])
+    );
+}
+
+#[test]
+fn logs_are_filtered_by_contract_and_registers() {
+    // Fuel addresses are sent as hex without a `0x` prefix
+    let contract = "11".repeat(32);
+    let request: GetFuelLogsRequest = serde_json::from_value(serde_json::json!({
+        "contract_id__in": [contract],
+        "rb__in": [7],
+    }))
+    .unwrap();
+
+    assert_eq!(request.id__in.len(), 1);
+    assert!(request.ra__in.is_empty());
+    assert_eq!(
+        query(&request),
+        format!("chains=FUEL&from_block=latest&to_block=latest&id__in={contract}&rb__in=7")
+    );
+
+    let request = GetFuelLogsRequest {
+        ra__in: HashSet::from([42]),
+        ..Default::default()
+    };
+    assert!(self::query(&request).ends_with("ra__in=42"));
+}
+
+#[test]
+fn orders_are_not_filtered_by_the_unknown_type() {
+    let request = GetSparkOrderRequest {
+        order_type__in: HashSet::from([OrderType::Buy, OrderType::Unknown]),
+        ..Default::default()
+    };
+
+    let err = request.validate().unwrap_err();
+    assert!(matches!(err, Error::InvalidRequest(_)), "{err:?}");
+}
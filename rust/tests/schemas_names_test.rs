@@ -0,0 +1,393 @@
+use ethers_core::types::U256;
+use pangea_client::core::types::{
+    btc::{BtcBlock, BtcTransaction},
+    curve::{CrvPool, CrvPrice, PoolType},
+    erc20::{Approval, Erc20Transfer},
+    fuel::{DecodedLog, FuelBlock, OrderEventType, OrderType, Receipt, SparkOrder, Src20Asset},
+    mira::MiraSwap,
+    uniswap_v2::{Pair, Price, ReserveEvent},
+    uniswap_v3, ChainId,
+};
+use serde_json::Value;
+
+const BTC_BLOCKS: &str = include_str!("fixtures/btc_blocks.jsonl");
+const BTC_TXS: &str = include_str!("fixtures/btc_txs.jsonl");
+const CURVE_POOLS: &str = include_str!("fixtures/curve_pools.jsonl");
+const CURVE_PRICES: &str = include_str!("fixtures/curve_prices.jsonl");
+const ERC20_APPROVALS: &str = include_str!("fixtures/erc20_approvals.jsonl");
+const ERC20_TRANSFERS: &str = include_str!("fixtures/erc20_transfers.jsonl");
+const FUEL_BLOCKS: &str = include_str!("fixtures/fuel_blocks.jsonl");
+const FUEL_LOGS_DECODED: &str = include_str!("fixtures/fuel_logs_decoded.jsonl");
+const FUEL_RECEIPTS: &str = include_str!("fixtures/fuel_receipts.jsonl");
+const FUEL_SPARK_ORDERS: &str = include_str!("fixtures/fuel_spark_orders.jsonl");
+const FUEL_SRC20: &str = include_str!("fixtures/fuel_src20.jsonl");
+const MIRA_SWAPS: &str = include_str!("fixtures/mira_swaps.jsonl");
+const UNISWAP_V2_PAIRS: &str = include_str!("fixtures/uniswap_v2_pairs.jsonl");
+const UNISWAP_V2_PRICES: &str = include_str!("fixtures/uniswap_v2_prices.jsonl");
+const UNISWAP_V3_FEES: &str = include_str!("fixtures/uniswap_v3_fees.jsonl");
+const UNISWAP_V3_POOLS: &str = include_str!("fixtures/uniswap_v3_pools.jsonl");
+const UNISWAP_V3_POSITIONS: &str = include_str!("fixtures/uniswap_v3_positions.jsonl");
+const UNISWAP_V3_PRICES: &str = include_str!("fixtures/uniswap_v3_prices.jsonl");
+
+/// Every field of the server payload must be mapped onto the record, and the
+/// record must not rename any of them
+fn assert_round_trip<T>(line: &str)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let payload: Value = serde_json::from_str(line).unwrap();
+    let record: T = serde_json::from_value(payload.clone()).unwrap();
+    let reserialized = serde_json::to_value(&record).unwrap();
+
+    let payload_keys = payload.as_object().unwrap().keys().collect::<Vec<_>>();
+    let record_keys = reserialized.as_object().unwrap().keys().collect::<Vec<_>>();
+    assert_eq!(payload_keys, record_keys);
+}
+
+#[test]
+fn uniswap_v2_pair_field_names() {
+    for line in UNISWAP_V2_PAIRS.lines() {
+        assert_round_trip::<Pair>(line);
+    }
+
+    let renamed = UNISWAP_V2_PAIRS
+        .lines()
+        .next()
+        .unwrap()
+        .replace("\"token0\"", "\"token_0\"");
+    assert!(serde_json::from_str::<Pair>(&renamed).is_err());
+}
+
+#[test]
+fn uniswap_v2_price_keeps_raw_reserves() {
+    for line in UNISWAP_V2_PRICES.lines() {
+        assert_round_trip::<Price>(line);
+    }
+
+    let price: Price = serde_json::from_str(UNISWAP_V2_PRICES.lines().next().unwrap()).unwrap();
+    assert_eq!(price.event, ReserveEvent::Sync);
+    assert_eq!(price.reserve0, U256::from(41_022_397_130_515u64));
+    assert_eq!(
+        price.reserve1,
+        U256::from_dec_str("22198706751930286325317").unwrap()
+    );
+}
+
+#[test]
+fn uniswap_v3_pool_fee_tiers() {
+    let pools = UNISWAP_V3_POOLS
+        .lines()
+        .inspect(|line| assert_round_trip::<uniswap_v3::Pool>(line))
+        .map(|line| serde_json::from_str::<uniswap_v3::Pool>(line).unwrap())
+        .map(|pool| (pool.fee, pool.tick_spacing))
+        .collect::<Vec<_>>();
+
+    assert_eq!(pools, [(500, 10), (3000, 60), (10000, 200)]);
+}
+
+#[test]
+fn uniswap_v3_price_from_sqrt() {
+    let line = UNISWAP_V3_PRICES.lines().next().unwrap();
+    assert_round_trip::<uniswap_v3::PriceRecord>(line);
+
+    let record: uniswap_v3::PriceRecord = serde_json::from_str(line).unwrap();
+    assert_eq!(record.tick, 201402);
+
+    // USDC (6 decimals) priced in WETH (18 decimals)
+    let price = record.price_from_sqrt(6, 18);
+    assert!((price - 0.0005426).abs() / 0.0005426 < 1e-3, "{price}");
+    // and the inverse, WETH priced in USDC
+    assert!((1.0 / price - 1843.0).abs() < 1.0, "{}", 1.0 / price);
+}
+
+#[test]
+fn uniswap_v3_fee_growth_exceeds_u128() {
+    let line = UNISWAP_V3_FEES.lines().next().unwrap();
+    assert_round_trip::<uniswap_v3::FeeRecord>(line);
+
+    let record: uniswap_v3::FeeRecord = serde_json::from_str(line).unwrap();
+    assert_eq!(
+        record.fee_growth_global0_x128,
+        (U256::one() << 200) + U256::from(12345)
+    );
+    assert_eq!(
+        record.fee_growth_global1_x128,
+        (U256::one() << 160) + U256::one()
+    );
+    assert!(record.protocol_fees_token0.is_zero());
+    assert!(record.protocol_fees_token1.is_zero());
+}
+
+#[test]
+fn uniswap_v3_position_negative_ticks() {
+    let positions = UNISWAP_V3_POSITIONS
+        .lines()
+        .inspect(|line| assert_round_trip::<uniswap_v3::Position>(line))
+        .map(|line| serde_json::from_str::<uniswap_v3::Position>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(positions[0].tick_lower, -887270);
+    assert_eq!(positions[0].tick_upper, -276300);
+    assert_eq!(positions[0].tokens_owed1, U256::from(1450));
+    assert_eq!(positions[1].tick_lower, 200940);
+    assert_eq!(positions[1].liquidity, U256::exp10(16));
+}
+
+#[test]
+fn curve_plain_meta_and_crypto_pools() {
+    let pools = CURVE_POOLS
+        .lines()
+        .inspect(|line| assert_round_trip::<CrvPool>(line))
+        .map(|line| serde_json::from_str::<CrvPool>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    let kinds = pools.iter().map(|pool| pool.pool_type).collect::<Vec<_>>();
+    assert_eq!(kinds, [PoolType::Plain, PoolType::Meta, PoolType::Crypto]);
+
+    assert_eq!(pools[0].coins.len(), 3);
+    assert!(!pools[0].is_metapool());
+
+    assert_eq!(pools[1].coins.len(), 2);
+    assert!(pools[1].is_metapool());
+    assert_eq!(pools[1].base_pool, Some(pools[0].pool_address));
+    assert_eq!(pools[1].base_coins, pools[0].coins);
+
+    assert_eq!(pools[2].initial_a, U256::from(1_707_629));
+}
+
+#[test]
+fn curve_price_virtual_price() {
+    let line = CURVE_PRICES.lines().next().unwrap();
+    assert_round_trip::<CrvPrice>(line);
+
+    let price: CrvPrice = serde_json::from_str(line).unwrap();
+    assert_eq!(
+        price.virtual_price,
+        U256::from(1_026_834_782_114_956_811u64)
+    );
+    assert!((price.virtual_price_f64() - 1.026_834_782_114_957).abs() < 1e-15);
+
+    // a broken pool may report a price too large for a u128
+    let price = CrvPrice {
+        virtual_price: U256::MAX,
+        ..price
+    };
+    assert!((price.virtual_price_f64() / 1.157_920_892_373_162e59 - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn erc20_infinite_approval_round_trips() {
+    let approvals = ERC20_APPROVALS
+        .lines()
+        .inspect(|line| assert_round_trip::<Approval>(line))
+        .map(|line| serde_json::from_str::<Approval>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(approvals[0].amount, U256::MAX);
+    assert!(approvals[0].is_infinite());
+    assert!(approvals[1].is_infinite());
+    assert!(!approvals[2].is_infinite());
+
+    let reserialized = serde_json::to_string(&approvals[0]).unwrap();
+    let approval: Approval = serde_json::from_str(&reserialized).unwrap();
+    assert_eq!(approval.amount, U256::MAX);
+}
+
+#[test]
+fn erc20_transfer_mint_and_burn() {
+    let transfers = ERC20_TRANSFERS
+        .lines()
+        .inspect(|line| assert_round_trip::<Erc20Transfer>(line))
+        .map(|line| serde_json::from_str::<Erc20Transfer>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert!(!transfers[0].is_mint() && !transfers[0].is_burn());
+    assert_eq!(transfers[0].amount, U256::from(250_000_000u64));
+
+    assert!(transfers[1].is_mint());
+    assert!(!transfers[1].is_burn());
+    assert_eq!(transfers[1].amount, U256::from(1_000_000_000_000u64));
+
+    assert!(transfers[2].is_burn());
+    assert!(!transfers[2].is_mint());
+}
+
+#[test]
+fn fuel_blocks_mainnet_and_testnet() {
+    let blocks = FUEL_BLOCKS
+        .lines()
+        .map(|line| serde_json::from_str::<FuelBlock>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(blocks[0].chain, ChainId::FUEL);
+    assert_eq!(blocks[0].height, 4_805_326);
+    assert_eq!(blocks[0].da_height, 21_207_218);
+    assert!(blocks[0].producer.is_some());
+
+    assert_eq!(blocks[1].chain, ChainId::FUELTESTNET);
+    assert_eq!(blocks[1].height, 13_042_518);
+    assert!(blocks[1].transactions_root.is_zero());
+    assert_eq!(blocks[1].producer, None);
+}
+
+#[test]
+fn fuel_decoded_log_into_known_abi() {
+    #[derive(serde::Deserialize)]
+    struct SwapEvent {
+        recipient: String,
+        asset_0_in: u64,
+        asset_1_out: u64,
+    }
+
+    let logs = FUEL_LOGS_DECODED
+        .lines()
+        .inspect(|line| assert_round_trip::<DecodedLog>(line))
+        .map(|line| serde_json::from_str::<DecodedLog>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(logs[0].event_name, "SwapEvent");
+    let swap: SwapEvent = logs[0].decode_into().unwrap();
+    assert!(swap.recipient.starts_with("0x9a2b"));
+    assert_eq!(swap.asset_0_in, 1_500_000_000);
+    assert_eq!(swap.asset_1_out, 4_213_377);
+
+    assert_eq!(logs[1].decode_into::<u64>().unwrap(), 42);
+    assert!(logs[1].decode_into::<SwapEvent>().is_err());
+}
+
+#[test]
+fn fuel_receipts_by_kind() {
+    let receipts = FUEL_RECEIPTS
+        .lines()
+        .inspect(|line| assert_round_trip::<Receipt>(line))
+        .map(|line| serde_json::from_str::<Receipt>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    let Receipt::Call(call) = &receipts[0] else {
+        panic!("expected a call receipt, got {:?}", receipts[0]);
+    };
+    assert_eq!(call.gas, u64::MAX);
+    assert_eq!(call.header.receipt_index, 0);
+
+    let Receipt::LogData(log_data) = &receipts[1] else {
+        panic!("expected a log data receipt, got {:?}", receipts[1]);
+    };
+    assert_eq!(
+        log_data.data.as_deref(),
+        Some(&[0, 0, 0, 0, 0, 0, 0, 42][..])
+    );
+
+    assert!(matches!(receipts[2], Receipt::Log(_)));
+    assert!(matches!(
+        receipts[3],
+        Receipt::ScriptResult(ref r) if r.gas_used == 94_231
+    ));
+
+    // a receipt kind added after this client was released must not fail the stream
+    let Receipt::Other(other) = &receipts[4] else {
+        panic!("expected an unknown receipt, got {:?}", receipts[4]);
+    };
+    assert_eq!(other["receipt_type"], "Blob");
+    assert!(receipts[4].header().is_none());
+    assert!(receipts.iter().take(4).all(|r| r.header().is_some()));
+
+    // a malformed receipt of a known kind is an error, not an unknown receipt
+    let mut malformed: serde_json::Value =
+        serde_json::from_str(FUEL_RECEIPTS.lines().next().unwrap()).unwrap();
+    malformed.as_object_mut().unwrap().remove("gas");
+    assert!(serde_json::from_value::<Receipt>(malformed).is_err());
+}
+
+#[test]
+fn fuel_spark_order_side_and_status() {
+    let orders = FUEL_SPARK_ORDERS
+        .lines()
+        .inspect(|line| assert_round_trip::<SparkOrder>(line))
+        .map(|line| serde_json::from_str::<SparkOrder>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(orders[0].side, OrderType::Buy);
+    assert_eq!(orders[0].status, OrderEventType::Open);
+    assert_eq!(orders[0].size, 2_500_000);
+
+    assert_eq!(orders[1].side, OrderType::Sell);
+    assert_eq!(orders[1].status, OrderEventType::Trade);
+    assert_eq!(orders[1].limit_type, None);
+
+    // states added by the indexer later fall back instead of failing
+    assert_eq!(orders[2].side, OrderType::Unknown);
+    assert_eq!(orders[2].status, OrderEventType::Unknown);
+}
+
+#[test]
+fn fuel_src20_asset_names() {
+    let assets = FUEL_SRC20
+        .lines()
+        .inspect(|line| assert_round_trip::<Src20Asset>(line))
+        .map(|line| serde_json::from_str::<Src20Asset>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(assets[0].symbol, "ETH");
+    assert_eq!(assets[0].decimals, 9);
+    assert_eq!(assets[0].total_supply, None);
+
+    assert_eq!(assets[1].name, "Fuel");
+    assert_eq!(assets[1].total_supply, Some(10_000_000_000_000_000_000));
+}
+
+#[test]
+fn mira_swap_amounts_beyond_u64() {
+    // amounts beyond u64 don't survive a detour through `Value`, so only the
+    // first line is round-tripped
+    assert_round_trip::<MiraSwap>(MIRA_SWAPS.lines().next().unwrap());
+    let swaps = MIRA_SWAPS
+        .lines()
+        .map(|line| serde_json::from_str::<MiraSwap>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert!(swaps[0].is_buy);
+    assert_eq!(swaps[0].amount1_in, 1_500_000_000);
+    assert_eq!(swaps[0].amount0_out, 4_213_377);
+    assert_eq!(swaps[0].pool_id(), swaps[1].pool_id());
+
+    assert!(!swaps[1].is_buy);
+    assert_eq!(swaps[1].amount0_in, u64::MAX as u128);
+    assert_eq!(swaps[1].amount1_out, 2 * u64::MAX as u128);
+}
+
+#[test]
+fn btc_block_names() {
+    let line = BTC_BLOCKS.lines().next().unwrap();
+    assert_round_trip::<BtcBlock>(line);
+
+    let block: BtcBlock = serde_json::from_str(line).unwrap();
+    assert_eq!(block.chain, ChainId::BTC);
+    assert_eq!(block.height, 840_000);
+    assert_eq!(block.tx_count, 3050);
+    assert_eq!(block.hash.0[..9], [0; 9]);
+}
+
+#[test]
+fn btc_coinbase_and_segwit_txs() {
+    let txs = BTC_TXS
+        .lines()
+        .inspect(|line| assert_round_trip::<BtcTransaction>(line))
+        .map(|line| serde_json::from_str::<BtcTransaction>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    let coinbase = &txs[0];
+    assert!(coinbase.is_coinbase());
+    assert_eq!(coinbase.inputs[0].txid, None);
+    assert_eq!(coinbase.output_value(), 650_000_000);
+    assert_eq!(coinbase.outputs[1].address, None);
+
+    let segwit = &txs[1];
+    assert!(!segwit.is_coinbase());
+    assert!(segwit.is_segwit());
+    assert_eq!(segwit.inputs[0].vout, Some(1));
+    assert_eq!(segwit.inputs[0].witness.len(), 2);
+    assert!(segwit.inputs[0].script_sig.is_empty());
+    assert_eq!(segwit.locktime, 839_999);
+    assert_eq!(segwit.outputs[1].value, 8_273_411);
+    assert_eq!(segwit.outputs[0].script_pubkey[..2], [0x00, 0x14]);
+}
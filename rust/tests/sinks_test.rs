@@ -0,0 +1,67 @@
+#![cfg(feature = "parquet")]
+
+use std::{fs::File, sync::Arc};
+
+use arrow::{
+    array::Int64Array,
+    datatypes::{DataType, Field, Schema},
+    ipc::writer::StreamWriter,
+    record_batch::RecordBatch,
+};
+use futures::StreamExt;
+use pangea_client::sinks::{write_parquet, ParquetCompression, ParquetOptions};
+use parquet::{
+    arrow::arrow_reader::ParquetRecordBatchReaderBuilder, basic::Compression,
+    file::reader::FileReader, file::serialized_reader::SerializedFileReader,
+};
+
+fn ipc_stream(batches: usize, rows: i64) -> Vec<u8> {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "block_number",
+        DataType::Int64,
+        false,
+    )]));
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema).unwrap();
+    for i in 0..batches as i64 {
+        let numbers = Int64Array::from_iter_values(i * rows..(i + 1) * rows);
+        let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(numbers)]).unwrap();
+        writer.write(&batch).unwrap();
+    }
+    writer.into_inner().unwrap()
+}
+
+#[tokio::test]
+async fn row_groups_and_compression() {
+    let data = ipc_stream(5, 1000);
+    let chunks = data.chunks(4096).map(<[u8]>::to_vec).collect::<Vec<_>>();
+    let stream = futures::stream::iter(chunks.into_iter().map(Ok)).boxed();
+
+    let path = std::env::temp_dir().join("pangea-client-sinks-test.parquet");
+    let options = ParquetOptions {
+        row_group_size: 2000,
+        compression: ParquetCompression::Snappy,
+    };
+    let summary = write_parquet(Ok(stream), &path, options).await.unwrap();
+
+    assert_eq!(summary.rows, 5000);
+    assert_eq!(summary.bytes, std::fs::metadata(&path).unwrap().len());
+
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    let metadata = reader.metadata();
+    assert_eq!(metadata.num_row_groups(), 3);
+    assert_eq!(metadata.row_group(0).num_rows(), 2000);
+    assert_eq!(
+        metadata.row_group(0).column(0).compression(),
+        Compression::SNAPPY
+    );
+
+    let rows = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap()
+        .map(|batch| batch.unwrap().num_rows())
+        .sum::<usize>();
+    assert_eq!(rows, 5000);
+
+    std::fs::remove_file(path).unwrap();
+}
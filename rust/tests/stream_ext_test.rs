@@ -0,0 +1,96 @@
+use futures::StreamExt;
+use pangea_client::{provider::StreamResponse, Error, StreamResponseExt};
+
+fn chunks(chunks: &[&str]) -> StreamResponse<Vec<u8>> {
+    let chunks = chunks
+        .iter()
+        .map(|chunk| Ok(chunk.as_bytes().to_vec()))
+        .collect::<Vec<_>>();
+    Ok(futures::stream::iter(chunks).boxed())
+}
+
+#[tokio::test]
+async fn collect_bytes_and_string() {
+    let response = chunks(&["{\"chain\":", "1}\n", "{\"chain\":10}\n"]);
+    assert_eq!(
+        response.collect_string().await.unwrap(),
+        "{\"chain\":1}\n{\"chain\":10}\n"
+    );
+
+    let response = chunks(&[r#"{"status":404,"error":"unknown pool"}"#]);
+    assert!(matches!(
+        response.collect_bytes().await,
+        Err(Error::ErrorResponse(err)) if err.status == 404
+    ));
+}
+
+#[tokio::test]
+async fn collect_vec() {
+    let records: StreamResponse<u64> = Ok(futures::stream::iter([Ok(1), Ok(2), Ok(3)]).boxed());
+    assert_eq!(records.collect_vec().await.unwrap(), vec![1, 2, 3]);
+
+    let records: StreamResponse<u64> =
+        Ok(futures::stream::iter([Ok(1), Err(Error::UnexpectedClose)]).boxed());
+    assert!(matches!(
+        records.collect_vec().await,
+        Err(Error::UnexpectedClose)
+    ));
+}
+
+#[tokio::test]
+async fn first_does_not_wait_for_the_rest() {
+    // a live stream that never ends after its first record
+    let records: StreamResponse<u64> = Ok(futures::stream::once(async { Ok(7) })
+        .chain(futures::stream::pending())
+        .boxed());
+    assert_eq!(records.first().await.unwrap(), Some(7));
+
+    let records: StreamResponse<u64> = Ok(futures::stream::empty().boxed());
+    assert_eq!(records.first().await.unwrap(), None);
+
+    let records: StreamResponse<u64> = Err(Error::BackendShutDown);
+    assert!(matches!(records.first().await, Err(Error::BackendShutDown)));
+}
+
+#[tokio::test]
+async fn copy_to_writer() {
+    let mut out = Vec::new();
+    let written = chunks(&["{\"chain\":", "1}\n"])
+        .copy_to(&mut out)
+        .await
+        .unwrap();
+    assert_eq!(written, 12);
+    assert_eq!(out, b"{\"chain\":1}\n");
+
+    // a small pipe forces the copy to wait for the reader
+    let (mut writer, mut reader) = tokio::io::duplex(4);
+    let data = "x".repeat(1000);
+    let copy = tokio::spawn(async move {
+        chunks(&[data.as_str(), data.as_str()])
+            .copy_to(&mut writer)
+            .await
+    });
+    let mut read = Vec::new();
+    tokio::io::AsyncReadExt::read_to_end(&mut reader, &mut read)
+        .await
+        .unwrap();
+    assert_eq!(copy.await.unwrap().unwrap(), 2000);
+    assert_eq!(read.len(), 2000);
+}
+
+#[tokio::test]
+async fn copy_to_stops_on_error() {
+    let stream: StreamResponse<Vec<u8>> = Ok(futures::stream::iter([
+        Ok(b"{}\n".to_vec()),
+        Err(Error::UnexpectedClose),
+        Ok(b"{}\n".to_vec()),
+    ])
+    .boxed());
+
+    let mut out = Vec::new();
+    assert!(matches!(
+        stream.copy_to(&mut out).await,
+        Err(Error::UnexpectedClose)
+    ));
+    assert_eq!(out, b"{}\n");
+}
@@ -0,0 +1,113 @@
+use pangea_client::{
+    provider::ChainProvider,
+    query::{Bound, TimeBound},
+    requests::{blocks::GetBlocksRequest, transfers::GetTransfersRequest, HasBlockBounds},
+    ClientBuilder, Error, Format, HttpProvider,
+};
+
+fn march() -> GetTransfersRequest {
+    GetTransfersRequest {
+        from_time: Some(TimeBound::Rfc3339("2024-03-01T00:00:00Z".into())),
+        to_time: Some(TimeBound::Timestamp(1_711_929_600)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn time_bounds_are_sent_as_timestamps() {
+    let params = serde_json::to_value(march()).unwrap();
+
+    assert_eq!(params["from_timestamp"], "2024-03-01T00:00:00Z");
+    assert_eq!(params["to_timestamp"], 1_711_929_600);
+
+    let params = serde_json::to_value(GetTransfersRequest::default()).unwrap();
+    assert!(params.get("from_timestamp").is_none(), "{params}");
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_block_timestamps_are_still_sent() {
+    let request = GetBlocksRequest {
+        from_timestamp: Some(1_709_251_200),
+        ..Default::default()
+    };
+    let params = serde_json::to_value(&request).unwrap();
+    assert_eq!(params["from_timestamp"], 1_709_251_200);
+    assert!(request.check_bounds().is_ok());
+
+    let request = GetBlocksRequest {
+        from_time: Some(TimeBound::Timestamp(1_709_251_200)),
+        from_timestamp: Some(1_709_251_200),
+        ..Default::default()
+    };
+    assert!(matches!(
+        request.check_bounds(),
+        Err(Error::InvalidBounds(_))
+    ));
+}
+
+#[test]
+fn time_bounds_round_trip() {
+    for bound in [
+        TimeBound::Timestamp(1_709_251_200),
+        TimeBound::Rfc3339("2024-03-01T00:00:00.5+01:00".into()),
+    ] {
+        let json = serde_json::to_string(&bound).unwrap();
+        assert_eq!(serde_json::from_str::<TimeBound>(&json).unwrap(), bound);
+    }
+    assert!(serde_json::from_str::<TimeBound>("\"March\"").is_err());
+}
+
+#[test]
+fn block_and_time_bounds_on_the_same_side_are_invalid() {
+    assert!(march().check_bounds().is_ok());
+
+    let request = GetTransfersRequest {
+        from_block: Bound::Exact(19_000_000),
+        ..march()
+    };
+    assert!(matches!(
+        request.check_bounds(),
+        Err(Error::InvalidBounds(_))
+    ));
+
+    let request = GetTransfersRequest {
+        to_time: Some(TimeBound::Rfc3339("1 April".into())),
+        ..Default::default()
+    };
+    assert!(matches!(
+        request.check_bounds(),
+        Err(Error::InvalidBounds(_))
+    ));
+}
+
+#[tokio::test]
+async fn invalid_bounds_fail_before_the_request_is_sent() {
+    // nothing listens on the port
+    let client = ClientBuilder::default()
+        .endpoint("127.0.0.1:9")
+        .secure(false)
+        .build::<HttpProvider>()
+        .await
+        .unwrap();
+    let request = GetTransfersRequest {
+        to_block: Bound::Exact(19_500_000),
+        ..march()
+    };
+
+    let result = client
+        .inner
+        .get_transfers_by_format(request, Format::JsonStream, false)
+        .await;
+
+    assert!(matches!(result, Err(Error::InvalidBounds(_))));
+}
+
+#[test]
+fn resuming_replaces_the_time_bound() {
+    let mut request = march();
+    request.set_from_block(Bound::Exact(19_400_000));
+
+    assert!(request.from_time.is_none());
+    assert!(request.check_bounds().is_ok());
+}
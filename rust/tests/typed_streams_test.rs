@@ -0,0 +1,254 @@
+use async_trait::async_trait;
+use ethers_core::types::{H256, U256};
+use futures::StreamExt;
+use pangea_client::{
+    core::types::transfers::TransferKind,
+    provider::{ChainProvider, CurveProvider, StreamResponse},
+    requests::{blocks, curve, logs, transfers, txs},
+    Address, Client, Format,
+};
+
+/// Replays a fixed set of chunks for every request, regardless of the dataset
+struct MockProvider {
+    chunks: Vec<Vec<u8>>,
+}
+
+impl MockProvider {
+    /// Splits `data` into chunks of `size` bytes, ignoring record boundaries
+    fn chunked(data: &[u8], size: usize) -> Self {
+        Self {
+            chunks: data.chunks(size).map(<[u8]>::to_vec).collect(),
+        }
+    }
+
+    fn stream(&self) -> StreamResponse<Vec<u8>> {
+        Ok(futures::stream::iter(self.chunks.clone().into_iter().map(Ok)).boxed())
+    }
+}
+
+#[async_trait]
+impl ChainProvider for MockProvider {
+    async fn get_blocks_by_format(
+        &self,
+        _: blocks::GetBlocksRequest,
+        _: Format,
+        _: bool,
+    ) -> StreamResponse<Vec<u8>> {
+        self.stream()
+    }
+
+    async fn get_logs_by_format(
+        &self,
+        _: logs::GetLogsRequest,
+        _: Format,
+        _: bool,
+    ) -> StreamResponse<Vec<u8>> {
+        self.stream()
+    }
+
+    async fn get_txs_by_format(
+        &self,
+        _: txs::GetTxsRequest,
+        _: Format,
+        _: bool,
+    ) -> StreamResponse<Vec<u8>> {
+        self.stream()
+    }
+
+    async fn get_transfers_by_format(
+        &self,
+        _: transfers::GetTransfersRequest,
+        _: Format,
+        _: bool,
+    ) -> StreamResponse<Vec<u8>> {
+        self.stream()
+    }
+}
+
+#[async_trait]
+impl CurveProvider for MockProvider {
+    async fn get_tokens_by_format(
+        &self,
+        _: curve::GetCrvTokenRequest,
+        _: Format,
+        _: bool,
+    ) -> StreamResponse<Vec<u8>> {
+        self.stream()
+    }
+
+    async fn get_pools_by_format(
+        &self,
+        _: curve::GetCrvPoolRequest,
+        _: Format,
+        _: bool,
+    ) -> StreamResponse<Vec<u8>> {
+        self.stream()
+    }
+
+    async fn get_prices_by_format(
+        &self,
+        _: curve::GetCrvPriceRequest,
+        _: Format,
+        _: bool,
+    ) -> StreamResponse<Vec<u8>> {
+        self.stream()
+    }
+}
+
+const BLOCKS: &[u8] = include_bytes!("fixtures/blocks.jsonl");
+
+#[tokio::test]
+async fn blocks_split_across_chunks() {
+    for size in [1, 7, 100, 1024, BLOCKS.len()] {
+        let client = Client {
+            inner: MockProvider::chunked(BLOCKS, size),
+        };
+        let blocks = client
+            .get_blocks(Default::default())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(blocks.len(), 2, "chunk size {size}");
+        let first = blocks[0].as_ref().unwrap();
+        let second = blocks[1].as_ref().unwrap();
+        assert_eq!(first.block_number.as_u64(), 17_000_000);
+        assert_eq!(first.base_fee_per_gas, Some(1_000_000_000u64.into()));
+        assert_eq!(second.parent_hash, first.hash);
+        assert!(second.extra_data.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn malformed_block_does_not_end_stream() {
+    let mut data = b"{\"chain\":1,\"block_number\":\"not a number\"}\n".to_vec();
+    data.extend_from_slice(BLOCKS);
+    let client = Client {
+        inner: MockProvider::chunked(&data, 64),
+    };
+
+    let blocks = client
+        .get_blocks(Default::default())
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(blocks.len(), 3);
+    assert!(blocks[0].is_err());
+    assert!(blocks[1..].iter().all(Result::is_ok));
+}
+
+const LOGS: &[u8] = include_bytes!("fixtures/logs.jsonl");
+
+#[tokio::test]
+async fn logs_with_empty_and_large_data() {
+    let mut data = LOGS.to_vec();
+    data.extend_from_slice(
+        format!(
+            "{{\"chain\":1,\"block_number\":\"0x1036641\",\"transaction_hash\":\"{:#x}\",\
+             \"log_index\":\"0x2\",\"address\":\"{:#x}\",\"topics\":[],\"data\":\"0x{}\"}}\n",
+            H256::repeat_byte(0x9f),
+            Address::repeat_byte(0x22),
+            "ab".repeat(200_000),
+        )
+        .as_bytes(),
+    );
+    let client = Client {
+        inner: MockProvider::chunked(&data, 4096),
+    };
+    let logs = client
+        .get_logs(Default::default())
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(logs.len(), 3);
+    assert_eq!(logs[0].topics.len(), 3);
+    assert_eq!(logs[0].data.len(), 32);
+    assert!(logs[1].data.is_empty());
+    assert!(!logs[1].removed);
+    assert!(logs[2].topics.is_empty());
+    assert_eq!(logs[2].data.len(), 200_000);
+}
+
+const TXS: &[u8] = include_bytes!("fixtures/txs.jsonl");
+
+#[tokio::test]
+async fn legacy_and_eip1559_transactions() {
+    let client = Client {
+        inner: MockProvider::chunked(TXS, 50),
+    };
+    let txs = client
+        .get_txs(Default::default())
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+
+    let (legacy, eip1559) = (&txs[0], &txs[1]);
+    assert!(!legacy.is_eip1559());
+    assert_eq!(legacy.gas_price, Some(20_000_000_000u64.into()));
+    assert_eq!(legacy.value, U256::from(4_290_000_000_000_000u64));
+    assert_eq!(legacy.input.as_ref(), b"hello!");
+
+    assert!(eip1559.is_eip1559());
+    assert_eq!(eip1559.gas_price, None);
+    assert_eq!(
+        eip1559.max_priority_fee_per_gas,
+        Some(1_000_000_000u64.into())
+    );
+    assert_eq!(eip1559.to, None);
+    assert_eq!(eip1559.status.map(|s| s.as_u64()), Some(0));
+}
+
+const TRANSFERS: &[u8] = include_bytes!("fixtures/transfers.jsonl");
+
+#[tokio::test]
+async fn transfer_values_in_decimal_and_hex() {
+    let client = Client {
+        inner: MockProvider::chunked(TRANSFERS, 33),
+    };
+    let transfers = client
+        .get_transfers(Default::default())
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(transfers[0].value, U256::from(4_290_000_000_000_000u64));
+    assert_eq!(transfers[0].value, transfers[1].value);
+    assert_eq!(transfers[1].kind, TransferKind::Internal);
+    assert_eq!(transfers[2].value, U256::from(1000));
+    assert_eq!(transfers[2].kind, TransferKind::Unknown);
+}
+
+#[tokio::test]
+async fn curve_token_with_invalid_utf8_symbol() {
+    let mut data = Vec::new();
+    for symbol in [&b"crvUSD"[..], b"\xf0\x9f\x92USD", b"3Crv"] {
+        data.extend_from_slice(
+            b"{\"chain\":1,\"block_number\":\"0x1\",\"address\":\"0xf939e0a03fb07f59a73314e73794be0e57ac1b4e\",\"symbol\":\"",
+        );
+        data.extend_from_slice(symbol);
+        data.extend_from_slice(b"\",\"name\":\"Curve\",\"decimals\":18,\"pools\":[]}\n");
+    }
+    let client = Client {
+        inner: MockProvider::chunked(&data, 16),
+    };
+
+    let symbols = client
+        .get_curve_tokens(Default::default())
+        .await
+        .unwrap()
+        .map(|token| token.unwrap().symbol)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(symbols, ["crvUSD", "\u{fffd}USD", "3Crv"]);
+}
@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use ethers_core::types::Address;
+use pangea_client::{
+    provider::{ChainProvider, FuelProvider, Provider},
+    query::Bound,
+    requests::{
+        fuel::GetFuelBlocksRequest, logs::GetLogsRequest, txs::GetTxsRequest,
+        uniswap_v3::GetPoolsRequest,
+    },
+    Client, Error, Format, HttpProvider,
+};
+
+/// A client whose requests would fail to connect, so only validation errors
+/// come back as something else
+async fn client() -> Client<HttpProvider> {
+    let provider = HttpProvider::try_new("127.0.0.1:1".into(), false, None, None)
+        .await
+        .unwrap();
+    Client::new(provider)
+}
+
+fn invalid(err: Error) -> String {
+    match err {
+        Error::InvalidRequest(reason) => reason,
+        err => panic!("expected an invalid request, got {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn reversed_ranges_are_refused_before_sending() {
+    let request = GetLogsRequest {
+        from_block: Bound::Exact(20_000_000),
+        to_block: Bound::Exact(10_000_000),
+        ..Default::default()
+    };
+    let Err(err) = client().await.get_logs(request).await else {
+        panic!("the request was sent");
+    };
+
+    assert_eq!(
+        invalid(err),
+        "from_block 20000000 is after to_block 10000000"
+    );
+}
+
+#[tokio::test]
+async fn requests_need_a_chain() {
+    let request = GetTxsRequest {
+        chains: HashSet::new(),
+        ..Default::default()
+    };
+    let Err(err) = client()
+        .await
+        .get_txs_by_format(request, Format::JsonStream, false)
+        .await
+    else {
+        panic!("the request was sent");
+    };
+
+    assert!(invalid(err).contains("`chains`"));
+}
+
+#[tokio::test]
+async fn single_chain_requests_are_sent_without_chains() {
+    // the server uses its chain when none is set
+    let request = GetFuelBlocksRequest {
+        chains: HashSet::new(),
+        ..Default::default()
+    };
+    let Err(err) = client()
+        .await
+        .get_fuel_blocks_by_format(request, Format::JsonStream, false)
+        .await
+    else {
+        panic!("nothing listens on the endpoint");
+    };
+
+    assert!(!matches!(err, Error::InvalidRequest(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn contract_filters_refuse_the_zero_address() {
+    let request = GetPoolsRequest {
+        pool_address__in: HashSet::from([Address::zero()]),
+        ..Default::default()
+    };
+    let Err(err) = client().await.get_uniswap_v3_pools(request).await else {
+        panic!("the request was sent");
+    };
+
+    assert_eq!(invalid(err), "pool_address__in has the zero address");
+}
+
+#[tokio::test]
+async fn valid_requests_are_sent() {
+    let request = GetLogsRequest {
+        from_block: Bound::Exact(10),
+        to_block: Bound::Exact(10),
+        ..Default::default()
+    };
+    let Err(err) = client().await.get_logs(request).await else {
+        panic!("nothing listens on the endpoint");
+    };
+
+    assert!(!matches!(err, Error::InvalidRequest(_)), "{err:?}");
+}
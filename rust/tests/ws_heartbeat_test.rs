@@ -0,0 +1,102 @@
+mod common;
+
+use std::time::Duration;
+
+use common::ws::{accept, frame, next_request};
+use futures::{SinkExt, StreamExt};
+use pangea_client::{
+    provider::ChainProvider, requests::blocks::GetBlocksRequest,
+    tokio_tungstenite::WebSocketStream, ConnectionEvent, Error, Format, ReconnectPolicy, WsConfig,
+    WsProvider,
+};
+use tokio::net::{TcpListener, TcpStream};
+
+fn config() -> WsConfig {
+    WsConfig {
+        ping_interval: Duration::from_millis(20),
+        pong_timeout: Duration::from_millis(50),
+        ..Default::default()
+    }
+}
+
+/// Keeps the connection open without reading from it, so pings go unanswered
+async fn stop_answering(ws: WebSocketStream<TcpStream>) {
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    drop(ws);
+}
+
+async fn connect(endpoint: String, config: WsConfig) -> WsProvider {
+    WsProvider::try_new_with_config(endpoint, false, None, None, config)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn unanswered_pings_fail_streams() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        next_request(&mut ws).await;
+        stop_answering(ws).await;
+    });
+
+    let provider = connect(endpoint, config()).await;
+    let results = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    server.abort();
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(Error::ConnectionStale)));
+}
+
+#[tokio::test]
+async fn stale_connections_are_reconnected() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        next_request(&mut ws).await;
+        let stale = tokio::spawn(stop_answering(ws));
+
+        let mut ws = accept(&listener).await;
+        let id = next_request(&mut ws).await;
+        ws.send(frame("End", &id, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+        stale.abort();
+    });
+
+    let config = WsConfig {
+        reconnect: Some(ReconnectPolicy {
+            max_attempts: 1,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(10),
+        }),
+        ..config()
+    };
+    let provider = connect(endpoint, config).await;
+    let mut events = Box::pin(provider.events());
+    let results = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+
+    assert!(results.is_empty());
+    match events.next().await.unwrap() {
+        ConnectionEvent::Disconnected { reason } => {
+            assert_eq!(reason, Error::ConnectionStale.to_string())
+        }
+        event => panic!("unexpected event {event:?}"),
+    }
+
+    drop(provider);
+    server.await.unwrap();
+}
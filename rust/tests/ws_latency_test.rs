@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+use futures::{SinkExt, StreamExt};
+use pangea_client::{
+    provider::ChainProvider, requests::blocks::GetBlocksRequest, tokio_tungstenite::accept_async,
+    tungstenite::Message, FlushPolicy, Format, WsConfig, WsProvider,
+};
+use tokio::net::TcpListener;
+
+const REQUESTS: u32 = 5;
+const COALESCE: Duration = Duration::from_millis(100);
+
+/// Answers every request right away, and returns the median time until the
+/// client got the answer
+async fn round_trip(config: WsConfig) -> Duration {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        socket.set_nodelay(true).unwrap();
+        let mut ws = accept_async(socket).await.unwrap();
+        while let Some(Ok(message)) = ws.next().await {
+            let Message::Binary(payload) = message else {
+                continue;
+            };
+            let request: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+            let id = request["id"].as_str().unwrap();
+            let start =
+                format!("{{\"kind\":\"Start\",\"id\":\"{id}\",\"counter\":0,\"epoch\":null}}\n");
+            if ws.send(Message::Binary(start.into_bytes())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let provider = WsProvider::try_new_with_config(endpoint, false, None, None, config)
+        .await
+        .unwrap();
+    let mut round_trips = Vec::new();
+    for _ in 0..REQUESTS {
+        let sent = Instant::now();
+        let stream = provider
+            .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+            .await
+            .unwrap();
+        round_trips.push(sent.elapsed());
+        drop(stream);
+    }
+
+    drop(provider);
+    server.await.unwrap();
+    round_trips.sort();
+    round_trips[round_trips.len() / 2]
+}
+
+#[tokio::test]
+async fn immediate_flushes_are_faster_than_coalesced_ones() {
+    let immediate = round_trip(WsConfig {
+        tcp_nodelay: true,
+        ..Default::default()
+    })
+    .await;
+    let coalesced = round_trip(WsConfig {
+        flush: FlushPolicy::Coalesce(COALESCE),
+        ..Default::default()
+    })
+    .await;
+    println!("median round trip: immediate {immediate:?}, coalesced {coalesced:?}");
+
+    // a coalesced request waits for the flush, an immediate one only for the
+    // local server
+    assert!(coalesced >= COALESCE, "{coalesced:?}");
+    assert!(immediate < COALESCE, "{immediate:?}");
+}
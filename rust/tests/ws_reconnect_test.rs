@@ -0,0 +1,532 @@
+mod common;
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use common::ws::{accept, frame, next_message, next_request};
+use futures::{SinkExt, StreamExt};
+use pangea_client::{
+    provider::ChainProvider,
+    query::{Bound, SortOrder},
+    requests::{blocks::GetBlocksRequest, logs::GetLogsRequest},
+    tokio_tungstenite::{accept_hdr_async, WebSocketStream},
+    tungstenite::http,
+    ConnectionEvent, CredentialsProvider, Error, Format, ReconnectPolicy, WsConfig, WsProvider,
+};
+use tokio::net::{TcpListener, TcpStream};
+
+const POLICY: ReconnectPolicy = ReconnectPolicy {
+    max_attempts: 2,
+    initial_delay: Duration::from_millis(10),
+    max_delay: Duration::from_millis(50),
+};
+
+async fn connect(endpoint: String) -> WsProvider {
+    WsProvider::try_new_with_reconnect(endpoint, false, None, None, POLICY)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn subscriptions_survive_a_reconnect() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        // the first connection drops after one chunk
+        let mut ws = accept(&listener).await;
+        let id = next_request(&mut ws).await;
+        ws.send(frame("Continue", &id, b"first")).await.unwrap();
+        drop(ws);
+
+        // the subscription is sent again on the second one
+        let mut ws = accept(&listener).await;
+        assert_eq!(next_request(&mut ws).await, id);
+        ws.send(frame("Continue", &id, b"second")).await.unwrap();
+        ws.send(frame("End", &id, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = connect(endpoint).await;
+    let mut events = Box::pin(provider.events());
+    // raw chunks are passed on as they are
+    let chunks = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(chunks, [b"first".to_vec(), b"second".to_vec()]);
+    assert!(matches!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Disconnected { .. }
+    ));
+    assert!(matches!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Reconnecting { attempt: 1, .. }
+    ));
+    assert!(matches!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Connected
+    ));
+    assert!(matches!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Reconnected {
+            attempt: 1,
+            subscriptions: 1
+        }
+    ));
+
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn json_streams_resume_after_the_last_block() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let request = next_message(&mut ws).await;
+        let id = request["id"].as_str().unwrap().to_string();
+        assert_eq!(request["from_block"], 1);
+        let data = b"{\"block_number\":1,\"i\":0}\n{\"block_number\":2,\"i\":1}\n{\"block_nu";
+        ws.send(frame("Continue", &id, data)).await.unwrap();
+        drop(ws);
+
+        // block 2 is requested again, as it may have been cut off
+        let mut ws = accept(&listener).await;
+        let request = next_message(&mut ws).await;
+        assert_eq!(request["id"], id.as_str());
+        assert_eq!(request["from_block"], 2);
+        let data = b"{\"block_number\":2,\"i\":1}\n{\"block_number\":2,\"i\":2}\n";
+        ws.send(frame("Continue", &id, data)).await.unwrap();
+        let data = b"{\"block_number\":\"0x3\",\"i\":3}";
+        ws.send(frame("Continue", &id, data)).await.unwrap();
+        ws.send(frame("End", &id, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = connect(endpoint).await;
+    let request = GetBlocksRequest {
+        from_block: Bound::Exact(1),
+        ..Default::default()
+    };
+    let data = provider
+        .get_blocks_by_format(request, Format::JsonStream, false)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .concat()
+        .await;
+
+    let records = String::from_utf8(data).unwrap();
+    let indices = records
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["i"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(indices, [0, 1, 2, 3]);
+
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn relative_bounds_resume_from_a_concrete_block() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let request = next_message(&mut ws).await;
+        let id = request["id"].as_str().unwrap().to_string();
+        assert_eq!(request["from_block"], -1000);
+        let data = b"{\"block_number\":500,\"i\":0}\n";
+        ws.send(frame("Continue", &id, data)).await.unwrap();
+        drop(ws);
+
+        // the latest block moved on, but the stream continues where it was
+        let mut ws = accept(&listener).await;
+        let request = next_message(&mut ws).await;
+        assert_eq!(request["from_block"], 500);
+        ws.send(frame("End", &id, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = connect(endpoint).await;
+    let request = GetBlocksRequest {
+        from_block: Bound::FromLatest(1000),
+        ..Default::default()
+    };
+    let data = provider
+        .get_blocks_by_format(request, Format::JsonStream, false)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .concat()
+        .await;
+
+    assert_eq!(data, b"{\"block_number\":500,\"i\":0}\n");
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn limited_streams_resume_with_the_rest_of_the_limit() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let request = next_message(&mut ws).await;
+        let id = request["id"].as_str().unwrap().to_string();
+        assert_eq!(request["limit"], 5);
+        let data = b"{\"block_number\":1,\"i\":0}\n{\"block_number\":2,\"i\":1}\n";
+        ws.send(frame("Continue", &id, data)).await.unwrap();
+        drop(ws);
+
+        // the record of block 2 is sent again, and counts towards the limit
+        let mut ws = accept(&listener).await;
+        let request = next_message(&mut ws).await;
+        assert_eq!(request["from_block"], 2);
+        assert_eq!(request["limit"], 4);
+        ws.send(frame("End", &id, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = connect(endpoint).await;
+    let request = GetLogsRequest {
+        from_block: Bound::Exact(1),
+        limit: Some(5),
+        ..Default::default()
+    };
+    let data = provider
+        .get_logs_by_format(request, Format::JsonStream, false)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .concat()
+        .await;
+
+    assert_eq!(data.iter().filter(|byte| **byte == b'\n').count(), 2);
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn descending_streams_cannot_be_resumed() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = connect(endpoint).await;
+    let request = GetLogsRequest {
+        order: Some(SortOrder::Desc),
+        ..Default::default()
+    };
+    let err = provider
+        .get_logs_by_format(request, Format::JsonStream, false)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(err, Error::InvalidRequest(_)), "{err:?}");
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn descending_streams_are_sent_without_a_reconnect_policy() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let request = next_message(&mut ws).await;
+        let id = request["id"].as_str().unwrap();
+        ws.send(frame("Continue", id, b"latest")).await.unwrap();
+        ws.send(frame("End", id, b"")).await.unwrap();
+        request
+    });
+
+    let provider =
+        WsProvider::try_new_with_config(endpoint, false, None, None, WsConfig::default())
+            .await
+            .unwrap();
+    let request = GetLogsRequest {
+        order: Some(SortOrder::Desc),
+        limit: Some(100),
+        ..Default::default()
+    };
+    let chunks = provider
+        .get_logs_by_format(request, Format::JsonStream, false)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(chunks, [b"latest".to_vec()]);
+    let request = server.await.unwrap();
+    assert_eq!(request["order"], "desc", "{request}");
+}
+
+#[tokio::test]
+async fn streams_fail_after_the_last_attempt() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        next_request(&mut ws).await;
+        // reconnecting is refused from now on
+    });
+
+    let provider = connect(endpoint).await;
+    let mut events = Box::pin(provider.events());
+    let results = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    server.await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+    let gave_up = loop {
+        if let event @ ConnectionEvent::GaveUp { .. } = events.next().await.unwrap() {
+            break event;
+        }
+    };
+    assert!(matches!(
+        gave_up,
+        ConnectionEvent::GaveUp { attempts: 2, .. }
+    ));
+}
+
+#[tokio::test]
+async fn refused_credentials_are_not_retried() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        next_request(&mut ws).await;
+        drop(ws);
+
+        let (socket, _) = listener.accept().await.unwrap();
+        // the error type is the handshake callback's, set by tungstenite
+        #[allow(clippy::result_large_err)]
+        let refuse = |_: &http::Request<()>, _| {
+            Err(http::Response::builder().status(401).body(None).unwrap())
+        };
+        assert!(accept_hdr_async(socket, refuse).await.is_err());
+    });
+
+    let provider = connect(endpoint).await;
+    let mut events = Box::pin(provider.events());
+    let results = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    server.await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+    let events = [
+        events.next().await.unwrap(),
+        events.next().await.unwrap(),
+        events.next().await.unwrap(),
+        events.next().await.unwrap(),
+    ];
+    assert!(matches!(events[0], ConnectionEvent::Disconnected { .. }));
+    assert!(matches!(
+        events[1],
+        ConnectionEvent::Reconnecting { attempt: 1, .. }
+    ));
+    assert!(matches!(events[2], ConnectionEvent::AuthFailed { .. }));
+    assert!(matches!(
+        events[3],
+        ConnectionEvent::GaveUp { attempts: 1, .. }
+    ));
+}
+
+#[test]
+fn delays_back_off_with_jitter() {
+    let policy = ReconnectPolicy::default();
+
+    for _ in 0..100 {
+        let delay = policy.delay(1);
+        assert!(delay >= policy.initial_delay / 2 && delay <= policy.initial_delay);
+
+        let delay = policy.delay(3);
+        assert!(delay >= policy.initial_delay * 2 && delay <= policy.initial_delay * 4);
+
+        let delay = policy.delay(usize::MAX);
+        assert!(delay >= policy.max_delay / 2 && delay <= policy.max_delay);
+    }
+}
+
+/// Hands out a new password for every connection
+#[derive(Default)]
+struct Rotating(AtomicUsize);
+
+#[async_trait]
+impl CredentialsProvider for Rotating {
+    async fn get(&self) -> pangea_client::Result<(String, String)> {
+        let n = self.0.fetch_add(1, Ordering::Relaxed);
+        Ok(("user".to_string(), format!("password{n}")))
+    }
+}
+
+/// Accepts the next connection if `accept`, and returns its credentials
+async fn accept_credentials(
+    listener: &TcpListener,
+    accept: bool,
+) -> (String, Option<WebSocketStream<TcpStream>>) {
+    let (socket, _) = listener.accept().await.unwrap();
+    let (sender, credentials) = std::sync::mpsc::channel();
+    #[allow(clippy::result_large_err)]
+    let callback = move |request: &http::Request<()>, response| {
+        let auth = request.headers()["authorization"].to_str().unwrap();
+        let auth = auth.strip_prefix("Basic ").unwrap();
+        let auth = String::from_utf8(BASE64.decode(auth).unwrap()).unwrap();
+        sender.send(auth).unwrap();
+        match accept {
+            true => Ok(response),
+            false => Err(http::Response::builder().status(401).body(None).unwrap()),
+        }
+    };
+    let ws = accept_hdr_async(socket, callback).await.ok();
+    (credentials.recv().unwrap(), ws)
+}
+
+fn credentials_config() -> WsConfig {
+    WsConfig {
+        reconnect: Some(ReconnectPolicy {
+            max_attempts: 5,
+            ..POLICY
+        }),
+        max_auth_failures: 2,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn credentials_are_fetched_for_every_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let (credentials, ws) = accept_credentials(&listener, true).await;
+        assert_eq!(credentials, "user:password0");
+        let mut ws = ws.unwrap();
+        next_request(&mut ws).await;
+        drop(ws);
+
+        // the expired credentials are refused once
+        let (credentials, _) = accept_credentials(&listener, false).await;
+        assert_eq!(credentials, "user:password1");
+        let (credentials, ws) = accept_credentials(&listener, true).await;
+        assert_eq!(credentials, "user:password2");
+        let mut ws = ws.unwrap();
+        let id = next_request(&mut ws).await;
+        ws.send(frame("Continue", &id, b"data")).await.unwrap();
+        ws.send(frame("End", &id, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = WsProvider::try_new_with_credentials(
+        endpoint,
+        false,
+        Rotating::default(),
+        credentials_config(),
+    )
+    .await
+    .unwrap();
+    let chunks = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(chunks, [b"data".to_vec()]);
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn repeatedly_refused_credentials_give_up() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let (_, ws) = accept_credentials(&listener, true).await;
+        next_request(&mut ws.unwrap()).await;
+
+        accept_credentials(&listener, false).await;
+        accept_credentials(&listener, false).await;
+    });
+
+    let provider = WsProvider::try_new_with_credentials(
+        endpoint,
+        false,
+        Rotating::default(),
+        credentials_config(),
+    )
+    .await
+    .unwrap();
+    let mut events = Box::pin(provider.events());
+    let results = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    server.await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0], Err(Error::Unauthorized)));
+    let gave_up = loop {
+        if let event @ ConnectionEvent::GaveUp { .. } = events.next().await.unwrap() {
+            break event;
+        }
+    };
+    assert!(matches!(
+        gave_up,
+        ConnectionEvent::GaveUp { attempts: 2, .. }
+    ));
+}
+
+#[tokio::test]
+async fn connecting_times_out() {
+    // the connection is never accepted, so the handshake hangs
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let config = WsConfig {
+        connect_timeout: Duration::from_millis(50),
+        tcp_keepalive: Some(Duration::from_secs(10)),
+        ..Default::default()
+    };
+    let err = WsProvider::try_new_with_config(endpoint, false, None, None, config.clone())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::ConnectTimeout(timeout) if timeout == config.connect_timeout));
+    drop(listener);
+}
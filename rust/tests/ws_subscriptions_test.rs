@@ -0,0 +1,623 @@
+mod common;
+
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use common::ws::{accept, acknowledge, frame, next_message, next_payload};
+use futures::{SinkExt, StreamExt};
+use pangea_client::{
+    provider::{ChainProvider, Provider, ResponseStream, StreamResponse},
+    requests::blocks::GetBlocksRequest,
+    tokio_tungstenite::WebSocketStream,
+    tungstenite::Message,
+    ChainId, Client, Error, Format, Operation, OverflowPolicy, WsConfig, WsProvider,
+};
+use tokio::net::{TcpListener, TcpStream};
+
+async fn get_blocks_result(provider: &WsProvider) -> StreamResponse<Vec<u8>> {
+    provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+        .await
+}
+
+async fn get_blocks(provider: &WsProvider) -> ResponseStream<Vec<u8>> {
+    provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn dropped_streams_are_cancelled() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let first = next_message(&mut ws).await["id"].clone();
+        let second = next_message(&mut ws).await["id"].clone();
+        ws.send(frame("Continue", first.as_str().unwrap(), b"first"))
+            .await
+            .unwrap();
+
+        // the first stream is dropped after its first chunk
+        let cancel = next_message(&mut ws).await;
+        assert_eq!(cancel["id"], first);
+        assert_eq!(cancel["operation"], "unsubscribe");
+
+        let second = second.as_str().unwrap();
+        ws.send(frame("Continue", second, b"second")).await.unwrap();
+        ws.send(frame("End", second, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = WsProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let mut first = get_blocks(&provider).await;
+    let second = get_blocks(&provider).await;
+
+    assert_eq!(first.next().await.unwrap().unwrap(), b"first");
+    drop(first);
+    let chunks = second.map(Result::unwrap).collect::<Vec<_>>().await;
+    assert_eq!(chunks, [b"second".to_vec()]);
+
+    drop(provider);
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn ended_streams_are_not_cancelled() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let id = next_message(&mut ws).await["id"].clone();
+        ws.send(frame("End", id.as_str().unwrap(), b""))
+            .await
+            .unwrap();
+
+        // nothing but the close frame follows
+        let mut messages = Vec::new();
+        while let Some(Ok(message)) = ws.next().await {
+            if let Message::Binary(message) = message {
+                messages.push(message);
+            }
+        }
+        assert!(messages.is_empty());
+    });
+
+    let provider = WsProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let chunks = get_blocks(&provider).await.collect::<Vec<_>>().await;
+    assert!(chunks.is_empty());
+
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn concurrent_requests_share_a_connection() {
+    const REQUESTS: usize = 20;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let mut requests = Vec::new();
+        for _ in 0..REQUESTS {
+            let request = next_message(&mut ws).await;
+            requests.push(request["id"].as_str().unwrap().to_string());
+        }
+
+        // answer interleaved and in reverse order, every stream gets its own id back
+        for id in requests.iter().rev() {
+            ws.send(frame("Continue", id, id.as_bytes())).await.unwrap();
+        }
+        for id in &requests {
+            ws.send(frame("End", id, b"")).await.unwrap();
+        }
+        while let Some(Ok(_)) = ws.next().await {}
+        // no other connection was opened
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), listener.accept())
+                .await
+                .is_err()
+        );
+    });
+
+    let client = Arc::new(Client::new(
+        WsProvider::try_new(endpoint, false, None, None)
+            .await
+            .unwrap(),
+    ));
+    let tasks = (0..REQUESTS)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                let stream = client
+                    .inner
+                    .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+                    .await
+                    .unwrap();
+                stream.map(Result::unwrap).collect::<Vec<_>>().await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut ids = HashSet::new();
+    for task in tasks {
+        let chunks = task.await.unwrap();
+        assert_eq!(chunks.len(), 1);
+        ids.insert(chunks[0].clone());
+    }
+    assert_eq!(ids.len(), REQUESTS);
+
+    drop(client);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn requests_over_the_limit_fail() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        while let Some(Ok(message)) = ws.next().await {
+            if let Message::Binary(payload) = message {
+                acknowledge(&mut ws, &payload).await;
+            }
+        }
+    });
+
+    let config = WsConfig {
+        max_subscriptions: Some(1),
+        ..Default::default()
+    };
+    let provider = WsProvider::try_new_with_config(endpoint, false, None, None, config)
+        .await
+        .unwrap();
+
+    let first = get_blocks(&provider).await;
+    let err = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err,
+        Error::TooManySubscriptions { limit: Some(1) }
+    ));
+
+    // dropping a stream frees its slot
+    drop(first);
+    let second = get_blocks(&provider).await;
+
+    drop(provider);
+    drop(second);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn refused_requests_end_with_an_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let id = next_message(&mut ws).await["id"].clone();
+        let error = br#"{"status":429,"error":"too many operations"}"#;
+        ws.send(frame("Error", id.as_str().unwrap(), error))
+            .await
+            .unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = WsProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let results = get_blocks(&provider).await.collect::<Vec<_>>().await;
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(
+        results[0],
+        Err(Error::TooManySubscriptions { limit: None })
+    ));
+
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[test]
+fn clients_can_be_shared() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Client<WsProvider>>();
+}
+
+/// Sends five chunks for a stream that is not read, then one for a stream that
+/// is, after which the worker has handled all of them
+async fn overflow(config: WsConfig) -> (WsProvider, ResponseStream<Vec<u8>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let slow = next_message(&mut ws).await["id"].clone();
+        let barrier = next_message(&mut ws).await["id"].clone();
+        for i in 1..=5u8 {
+            ws.send(frame("Continue", slow.as_str().unwrap(), &[i]))
+                .await
+                .unwrap();
+        }
+        ws.send(frame("End", slow.as_str().unwrap(), b""))
+            .await
+            .unwrap();
+        ws.send(frame("Continue", barrier.as_str().unwrap(), b"barrier"))
+            .await
+            .unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = WsProvider::try_new_with_config(endpoint, false, None, None, config)
+        .await
+        .unwrap();
+    let slow = get_blocks(&provider).await;
+    let mut barrier = get_blocks(&provider).await;
+    barrier.next().await.unwrap().unwrap();
+
+    (provider, slow)
+}
+
+#[tokio::test]
+async fn full_buffers_drop_the_oldest_messages() {
+    let config = WsConfig {
+        buffer_size: 2,
+        overflow: OverflowPolicy::DropOldest,
+        ..Default::default()
+    };
+    let (provider, slow) = overflow(config).await;
+
+    assert_eq!(provider.buffered(), 2);
+    let chunks = slow.map(Result::unwrap).collect::<Vec<_>>().await;
+    assert_eq!(chunks, [[4], [5]]);
+    assert_eq!(provider.buffered(), 0);
+}
+
+#[tokio::test]
+async fn full_buffers_fail_streams() {
+    let config = WsConfig {
+        buffer_size: 2,
+        overflow: OverflowPolicy::Error,
+        ..Default::default()
+    };
+    let (provider, slow) = overflow(config).await;
+
+    assert_eq!(provider.buffered(), 3);
+    let results = slow.collect::<Vec<_>>().await;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap(), &[1]);
+    assert_eq!(results[1].as_ref().unwrap(), &[2]);
+    assert!(matches!(results[2], Err(Error::BufferOverflow)));
+}
+
+#[tokio::test]
+async fn unacknowledged_requests_time_out() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let running = next_message(&mut ws).await["id"].clone();
+
+        // the slow request is acknowledged late, the one that times out never
+        let slow = next_payload(&mut ws).await;
+        next_payload(&mut ws).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        acknowledge(&mut ws, &slow).await;
+
+        // the request that timed out is cancelled, the others go on
+        let cancel = next_message(&mut ws).await;
+        assert_eq!(cancel["operation"], "unsubscribe");
+        let running = running.as_str().unwrap();
+        ws.send(frame("Continue", running, b"running"))
+            .await
+            .unwrap();
+        ws.send(frame("End", running, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let config = WsConfig {
+        handshake_timeout: Duration::from_millis(50),
+        ..Default::default()
+    };
+    let provider = WsProvider::try_new_with_config(endpoint, false, None, None, config.clone())
+        .await
+        .unwrap();
+    let running = get_blocks(&provider).await;
+
+    let backfill = provider.with_handshake_timeout(Duration::from_secs(5));
+    let (slow, timed_out) = tokio::join!(
+        backfill.get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false),
+        async {
+            // sent after the slow request
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            provider
+                .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+                .await
+        },
+    );
+
+    assert!(slow.is_ok());
+    assert!(
+        matches!(timed_out.err().unwrap(), Error::Timeout(timeout) if timeout == config.handshake_timeout)
+    );
+    let chunks = running.map(Result::unwrap).collect::<Vec<_>>().await;
+    assert_eq!(chunks, [b"running".to_vec()]);
+
+    drop((provider, backfill, slow));
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn requests_refused_before_any_data_fail() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let request: serde_json::Value =
+            serde_json::from_slice(&next_payload(&mut ws).await).unwrap();
+        let error = br#"{"status":403,"error":"the dataset is not enabled"}"#;
+        ws.send(frame("Error", request["id"].as_str().unwrap(), error))
+            .await
+            .unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = WsProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let err = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::ArrowStream, false)
+        .await
+        .err()
+        .unwrap();
+
+    match err {
+        Error::ErrorResponse(err) => {
+            assert_eq!(err.status, 403);
+            assert_eq!(err.error, "the dataset is not enabled");
+        }
+        err => panic!("unexpected error {err}"),
+    }
+
+    drop(provider);
+    server.await.unwrap();
+}
+
+/// Reads until the client closes the connection, and returns whether it sent
+/// a close frame
+async fn closed_cleanly(mut ws: WebSocketStream<TcpStream>) -> bool {
+    loop {
+        match ws.next().await {
+            Some(Ok(Message::Close(_))) => return true,
+            Some(Ok(_)) => continue,
+            _ => return false,
+        }
+    }
+}
+
+#[tokio::test]
+async fn shutdown_closes_the_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let id = next_message(&mut ws).await["id"].clone();
+        ws.send(frame("Continue", id.as_str().unwrap(), b"tail"))
+            .await
+            .unwrap();
+        closed_cleanly(ws).await
+    });
+
+    let provider = WsProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let clone = provider.clone();
+    let stream = get_blocks(&provider).await;
+    provider.shutdown().await;
+
+    assert!(server.await.unwrap());
+    assert!(!clone.ready());
+    // the stream gets what arrived before the close and ends
+    let chunks = stream.map(Result::unwrap).collect::<Vec<_>>().await;
+    assert_eq!(chunks, [b"tail".to_vec()]);
+    assert!(get_blocks_result(&clone).await.is_err());
+}
+
+#[tokio::test]
+async fn dropping_the_provider_closes_the_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move { closed_cleanly(accept(&listener).await).await });
+
+    let provider = WsProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    drop(provider);
+
+    assert!(server.await.unwrap());
+}
+
+#[tokio::test]
+async fn messages_over_the_limit_end_the_streams() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let id = next_message(&mut ws).await["id"].clone();
+        ws.send(frame("Continue", id.as_str().unwrap(), &[0; 2048]))
+            .await
+            .unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let config = WsConfig {
+        max_message_size: Some(1024),
+        ..Default::default()
+    };
+    let provider = WsProvider::try_new_with_config(endpoint, false, None, None, config)
+        .await
+        .unwrap();
+    let results = get_blocks(&provider).await.collect::<Vec<_>>().await;
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(
+        results[0],
+        Err(Error::MessageTooLarge { limit: 1024, .. })
+    ));
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn stats_follow_the_open_streams() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let id = next_message(&mut ws).await["id"].clone();
+        let id = id.as_str().unwrap();
+        ws.send(frame("Continue", id, b"{\"block_number\":5}\n"))
+            .await
+            .unwrap();
+        ws.send(frame("Continue", id, b"{\"block_number\":\"0x7\"}\n"))
+            .await
+            .unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    let provider = WsProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let mut stream = provider
+        .get_blocks_by_format(GetBlocksRequest::default(), Format::JsonStream, false)
+        .await
+        .unwrap();
+    stream.next().await.unwrap().unwrap();
+    stream.next().await.unwrap().unwrap();
+
+    let stats = provider.stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].messages, 2);
+    assert_eq!(stats[0].bytes, 42);
+    assert_eq!(stats[0].last_block, Some(7));
+    assert!(stats[0].last_message_at.is_some());
+
+    drop(stream);
+    assert!(provider.stats().is_empty());
+    drop(provider);
+    server.await.unwrap();
+}
+
+#[test]
+fn the_connection_runs_on_the_given_runtime() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .unwrap();
+    let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = runtime.spawn(async move {
+        let mut ws = accept(&listener).await;
+        let id = next_message(&mut ws).await["id"].clone();
+        let id = id.as_str().unwrap();
+        ws.send(frame("Continue", id, b"data")).await.unwrap();
+        ws.send(frame("End", id, b"")).await.unwrap();
+        while let Some(Ok(_)) = ws.next().await {}
+    });
+
+    // the provider outlives the runtime it was created on
+    let provider = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(WsProvider::try_new_with_runtime(
+            endpoint,
+            false,
+            None,
+            None,
+            WsConfig::default(),
+            runtime.handle().clone(),
+        ))
+        .unwrap();
+
+    let chunks = runtime.block_on(async {
+        get_blocks(&provider)
+            .await
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await
+    });
+    assert_eq!(chunks, [b"data".to_vec()]);
+
+    drop(provider);
+    runtime.block_on(server).unwrap();
+}
+
+#[tokio::test]
+async fn subscriptions_describe_their_request() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+
+    let server = tokio::spawn(async move {
+        let mut ws = accept(&listener).await;
+        let request = next_message(&mut ws).await;
+        let id = request["id"].as_str().unwrap();
+        ws.send(frame("Continue", id, b"data")).await.unwrap();
+        // the subscription is cancelled without an end
+        let cancel = next_payload(&mut ws).await;
+        let cancel: serde_json::Value = serde_json::from_slice(&cancel).unwrap();
+        assert_eq!(cancel["operation"], "unsubscribe");
+        assert_eq!(cancel["id"], id);
+        request
+    });
+
+    let provider = WsProvider::try_new(endpoint, false, None, None)
+        .await
+        .unwrap();
+    let request = GetBlocksRequest {
+        chains: HashSet::from([ChainId::ETH]),
+        ..Default::default()
+    };
+    let mut subscription = provider
+        .subscribe(Operation::GetBlocks, request, Format::ArrowStream, false)
+        .await
+        .unwrap();
+
+    assert!(matches!(subscription.operation(), Operation::GetBlocks));
+    assert_eq!(subscription.params()["chains"], "ETH");
+    assert!(subscription.created_at() <= SystemTime::now());
+    assert_eq!(subscription.next().await.unwrap().unwrap(), b"data");
+    assert_eq!(subscription.stats().messages, 1);
+
+    let id = subscription.id();
+    subscription.cancel();
+    let request = server.await.unwrap();
+    assert_eq!(request["id"], id.to_string());
+}